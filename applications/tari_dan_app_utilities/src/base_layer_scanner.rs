@@ -23,10 +23,9 @@
 use std::time::Duration;
 
 use log::*;
-use minotari_app_grpc::tari_rpc::ValidatorNodeChangeState;
 use tari_base_node_client::{
     grpc::GrpcBaseNodeClient,
-    types::{BaseLayerMetadata, BlockInfo},
+    types::{BaseLayerMetadata, BlockInfo, ValidatorNodeChange},
     BaseNodeClient,
     BaseNodeClientError,
 };
@@ -43,7 +42,7 @@ use tari_core::transactions::{
 };
 use tari_crypto::{
     ristretto::RistrettoPublicKey,
-    tari_utilities::{hex::Hex, ByteArray, ByteArrayError},
+    tari_utilities::{hex::Hex, ByteArray},
 };
 use tari_dan_common_types::{optional::Optional, NodeAddressable, VersionedSubstateId};
 use tari_dan_storage::{
@@ -206,6 +205,15 @@ impl<TAddr: NodeAddressable + 'static> BaseLayerScanner<TAddr> {
         // fetch the new base layer info since the previous scan
         let tip = self.base_node_client.get_tip_info().await?;
 
+        // A syncing base node reports its local progress as the tip, which must not be mistaken for the chain's tip
+        if !tip.initial_sync_achieved {
+            warn!(
+                target: LOG_TARGET,
+                "⏳ Base node has not completed its initial sync. Waiting for it to sync before scanning."
+            );
+            return Ok(());
+        }
+
         match self.get_blockchain_progression(&tip).await? {
             BlockchainProgression::Progressed => {
                 info!(
@@ -316,32 +324,22 @@ impl<TAddr: NodeAddressable + 'static> BaseLayerScanner<TAddr> {
                 .map_err(BaseLayerScannerError::BaseNodeError)?;
 
             for node_change in node_changes {
-                if node_change.registration.is_none() {
-                    warn!(
-                        target: LOG_TARGET,
-                        "Can't register validator node \"{}\" because it has empty registration!",
-                        node_change.public_key.to_hex(),
-                    );
-                    continue;
-                }
-                let registration = ValidatorNodeRegistration::try_from(node_change.registration.clone().unwrap())
-                    .map_err(BaseLayerScannerError::GrpcConversion)?;
-                match node_change.state() {
-                    ValidatorNodeChangeState::Add => {
-                        self.add_validator_node_registration(
-                            node_change.start_height,
-                            registration,
-                            node_change.minimum_value_promise.into(),
-                        )
-                        .await?;
+                match node_change {
+                    ValidatorNodeChange::Add {
+                        registration,
+                        activation_height,
+                        minimum_value_promise,
+                        ..
+                    } => {
+                        self.add_validator_node_registration(activation_height, registration, minimum_value_promise)
+                            .await?;
                     },
-                    ValidatorNodeChangeState::Remove => {
-                        self.remove_validator_node_registration(
-                            PublicKey::from_canonical_bytes(node_change.public_key.as_slice())
-                                .map_err(BaseLayerScannerError::PublicKeyConversion)?,
-                            registration.sidechain_id().cloned(),
-                        )
-                        .await?;
+                    ValidatorNodeChange::Remove {
+                        public_key,
+                        sidechain_id,
+                    } => {
+                        self.remove_validator_node_registration(public_key, sidechain_id)
+                            .await?;
                     },
                 }
             }
@@ -629,10 +627,6 @@ pub enum BaseLayerScannerError {
         commitment: Box<Commitment>,
         source: StorageError,
     },
-    #[error("Public key conversion error: {0}")]
-    PublicKeyConversion(ByteArrayError),
-    #[error("GRPC conversion error: {0}")]
-    GrpcConversion(String),
 }
 
 enum BlockchainProgression {
@@ -24,13 +24,19 @@ use std::{net::SocketAddr, path::PathBuf, time::Duration};
 
 use config::Config;
 use serde::{Deserialize, Serialize};
-use tari_common::{configuration::CommonConfig, ConfigurationError, DefaultConfigLoader, SubConfigPath};
+use tari_common::{
+    configuration::{CommonConfig, Network},
+    ConfigurationError,
+    DefaultConfigLoader,
+    SubConfigPath,
+};
 use tari_dan_common_types::crypto::create_secret;
 
 #[derive(Debug, Clone)]
 pub struct ApplicationConfig {
     pub common: CommonConfig,
     pub dan_wallet_daemon: WalletDaemonConfig,
+    pub network: Network,
 }
 
 impl ApplicationConfig {
@@ -38,6 +44,7 @@ impl ApplicationConfig {
         let config = Self {
             common: CommonConfig::load_from(cfg)?,
             dan_wallet_daemon: WalletDaemonConfig::load_from(cfg)?,
+            network: cfg.get("network")?,
         };
         Ok(config)
     }
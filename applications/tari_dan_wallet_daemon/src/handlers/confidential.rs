@@ -9,14 +9,13 @@ use log::*;
 use rand::rngs::OsRng;
 use serde_json::json;
 use tari_common_types::types::PublicKey;
-use tari_crypto::{commitment::HomomorphicCommitmentFactory, keys::PublicKey as _};
+use tari_crypto::keys::PublicKey as _;
 use tari_dan_common_types::optional::Optional;
-use tari_dan_wallet_crypto::{AlwaysMissLookupTable, ConfidentialProofStatement, IoReaderValueLookup};
+use tari_dan_wallet_crypto::{commitment_for, AlwaysMissLookupTable, ConfidentialProofStatement, IoReaderValueLookup};
 use tari_dan_wallet_sdk::{
     apis::{jwt::JrpcPermission, key_manager},
     models::{ConfidentialOutputModel, OutputStatus},
 };
-use tari_engine_types::confidential::get_commitment_factory;
 use tari_template_lib::models::Amount;
 use tari_wallet_daemon_client::types::{
     ConfidentialCreateOutputProofRequest,
@@ -138,7 +137,7 @@ pub async fn handle_create_transfer_proof(
         sdk.confidential_outputs_api().add_output(ConfidentialOutputModel {
             account_address: account.address,
             vault_address: vault.address,
-            commitment: get_commitment_factory().commit_value(&change_mask.key, change_amount),
+            commitment: commitment_for(change_amount, &change_mask.key),
             value: change_amount,
             sender_public_nonce: Some(public_nonce.clone()),
             encryption_secret_key_index: change_mask.key_index,
@@ -132,6 +132,7 @@ pub fn initialize_wallet_sdk(
         password: None,
         jwt_expiry: config.dan_wallet_daemon.jwt_expiry.unwrap(),
         jwt_secret_key: config.dan_wallet_daemon.jwt_secret_key.clone().unwrap(),
+        network: config.network,
     };
     let config_api = ConfigApi::new(&store);
     let indexer_jrpc_endpoint = if let Some(indexer_url) = config_api.get(ConfigKey::IndexerUrl).optional()? {
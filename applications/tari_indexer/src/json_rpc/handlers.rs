@@ -170,8 +170,11 @@ impl JsonRpcHandlers {
     pub async fn get_all_vns(&self, value: JsonRpcExtractor) -> JrpcResult {
         let answer_id = value.get_answer_id();
         let GetAllVnsRequest { epoch } = value.parse_params()?;
-        let epoch_blocks = self.consensus_constants.epoch_to_height(epoch);
-        match self.base_node_client().get_validator_nodes(epoch_blocks).await {
+        match self
+            .base_node_client()
+            .get_validator_nodes_for_epoch(epoch, self.consensus_constants.epoch_length)
+            .await
+        {
             Ok(vns) => Ok(JsonRpcResponse::success(answer_id, GetAllVnsResponse { vns })),
             Err(e) => Err(Self::internal_error(answer_id, format!("Failed to get all vns: {}", e))),
         }
@@ -84,6 +84,7 @@ use tari_epoch_manager::{
 use tari_indexer_lib::substate_scanner::SubstateScanner;
 use tari_networking::{MessagingMode, NetworkingHandle, RelayCircuitLimits, RelayReservationLimits, SwarmConfig};
 use tari_rpc_framework::RpcServer;
+use tari_rpc_state_sync::RpcStateSyncManager;
 use tari_shutdown::ShutdownSignal;
 use tari_state_store_sqlite::SqliteStateStore;
 use tari_template_lib::{
@@ -201,8 +202,12 @@ pub async fn spawn_services(
 
     info!(target: LOG_TARGET, "State store initializing");
     // Connect to shard db
-    let state_store =
-        SqliteStateStore::connect(&format!("sqlite://{}", config.validator_node.state_db_path().display()))?;
+    let state_db_url = format!("sqlite://{}", config.validator_node.state_db_path().display());
+    let state_store = if config.validator_node.state_db_snapshot_reads {
+        SqliteStateStore::connect_with_snapshot_reads(&state_db_url)?
+    } else {
+        SqliteStateStore::connect(&state_db_url)?
+    };
     let sidechain_id = config.validator_node.validator_node_sidechain_id.clone();
     state_store.with_write_tx(|tx| {
         bootstrap_state(
@@ -311,11 +316,16 @@ pub async fn spawn_services(
         epoch_manager.clone(),
         inbound_messaging,
         outbound_messaging.clone(),
-        validator_node_client_factory.clone(),
+        consensus::TariSyncManager::Rpc(RpcStateSyncManager::new(
+            epoch_manager.clone(),
+            state_store.clone(),
+            validator_node_client_factory.clone(),
+        )),
         metrics,
         shutdown.clone(),
         transaction_executor,
         consensus_constants.clone(),
+        &config.validator_node.consensus,
     )
     .await;
     handles.push(consensus_join_handle);
@@ -75,6 +75,9 @@ pub struct ValidatorNodeConfig {
     // pub public_address: Option<Multiaddr>,
     /// The Tari base node's GRPC URL
     pub base_node_grpc_url: Option<Url>,
+    /// If true, validator nodes returned by the base node that cannot be parsed are logged and skipped rather than
+    /// failing the whole request
+    pub base_node_skip_invalid_validator_nodes: bool,
     /// If set to false, there will be no base layer scanning at all
     pub scan_base_layer: bool,
     /// How often do we want to scan the base layer for changes
@@ -107,6 +110,11 @@ pub struct ValidatorNodeConfig {
     pub template_sidechain_id: Option<RistrettoPublicKey>,
     /// The burnt utxo sidechain id
     pub burnt_utxo_sidechain_id: Option<RistrettoPublicKey>,
+    /// If true, catch-up syncs are served from a separate read-only connection to the state database so that they do
+    /// not contend with consensus writes. This switches the state database to WAL mode.
+    pub state_db_snapshot_reads: bool,
+    /// Consensus configuration
+    pub consensus: ConsensusConfig,
 }
 
 impl ValidatorNodeConfig {
@@ -134,6 +142,7 @@ impl Default for ValidatorNodeConfig {
             shard_key_file: PathBuf::from("shard_key.json"),
             identity_file: PathBuf::from("validator_node_id.json"),
             base_node_grpc_url: None,
+            base_node_skip_invalid_validator_nodes: false,
             scan_base_layer: true,
             base_layer_scanning_interval: Duration::from_secs(10),
             data_dir: PathBuf::from("data/validator_node"),
@@ -150,6 +159,60 @@ impl Default for ValidatorNodeConfig {
             validator_node_sidechain_id: None,
             template_sidechain_id: None,
             burnt_utxo_sidechain_id: None,
+            state_db_snapshot_reads: false,
+            consensus: ConsensusConfig::default(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ConsensusConfig {
+    /// The maximum number of blocks to request from a peer in a single catch-up page. If 0, a single unpaginated
+    /// catch-up request is made.
+    pub sync_request_max_blocks: u64,
+    /// If true, the high QC of a catch-up request must be signed by a quorum of our committee and be for a block in
+    /// our store before any blocks are served. Requests from older peers that do not send the high QC certificate are
+    /// rejected.
+    pub sync_request_verify_qc: bool,
+    /// The minimum delay in milliseconds between proposals sent to a peer that is catching up. If 0, proposals are
+    /// sent as fast as the outbound messaging allows.
+    pub sync_response_message_interval_ms: u64,
+    /// The maximum time spent serving a single catch-up request. If 0, requests are served to completion.
+    #[serde(with = "serializers::seconds")]
+    pub sync_response_timeout: Duration,
+    /// The maximum time to wait for a single message to be sent to a peer that is catching up, after which the peer is
+    /// considered unresponsive. If 0, sends are not timed out.
+    #[serde(with = "serializers::seconds")]
+    pub sync_response_send_timeout: Duration,
+}
+
+impl ConsensusConfig {
+    pub fn sync_response_message_interval(&self) -> Option<Duration> {
+        non_zero(Duration::from_millis(self.sync_response_message_interval_ms))
+    }
+
+    pub fn sync_response_timeout(&self) -> Option<Duration> {
+        non_zero(self.sync_response_timeout)
+    }
+
+    pub fn sync_response_send_timeout(&self) -> Option<Duration> {
+        non_zero(self.sync_response_send_timeout)
+    }
+}
+
+fn non_zero(duration: Duration) -> Option<Duration> {
+    (!duration.is_zero()).then_some(duration)
+}
+
+impl Default for ConsensusConfig {
+    fn default() -> Self {
+        Self {
+            sync_request_max_blocks: 500,
+            sync_request_verify_qc: true,
+            sync_response_message_interval_ms: 0,
+            sync_response_timeout: Duration::from_secs(5 * 60),
+            sync_response_send_timeout: Duration::from_secs(30),
         }
     }
 }
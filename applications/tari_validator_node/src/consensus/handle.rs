@@ -1,12 +1,20 @@
 //   Copyright 2023 The Tari Project
 //   SPDX-License-Identifier: BSD-3-Clause
 
-use tari_consensus::hotstuff::{ConsensusCurrentState, CurrentView, HotstuffEvent};
-use tari_dan_common_types::Epoch;
+use tari_common_types::types::PublicKey;
+use tari_consensus::{
+    hotstuff::{ConsensusCurrentState, CurrentView, HotstuffEvent},
+    traits::{ConsensusSpec, LeaderStrategy},
+};
+use tari_dan_common_types::{Epoch, NodeHeight, ShardGroup};
+use tari_epoch_manager::{EpochManagerError, EpochManagerReader};
 use tari_transaction::Transaction;
 use tokio::sync::{broadcast, mpsc, watch};
 
-use crate::event_subscription::EventSubscription;
+use crate::{consensus::spec::TariConsensusSpec, event_subscription::EventSubscription};
+
+/// The maximum number of heights that a single leader schedule may span
+pub const MAX_LEADER_SCHEDULE_HEIGHTS: u64 = 1000;
 
 #[derive(Debug, Clone)]
 pub struct ConsensusHandle {
@@ -14,6 +22,8 @@ pub struct ConsensusHandle {
     events_subscription: EventSubscription<HotstuffEvent>,
     current_view: CurrentView,
     tx_new_transaction: mpsc::Sender<(Transaction, usize)>,
+    leader_strategy: <TariConsensusSpec as ConsensusSpec>::LeaderStrategy,
+    epoch_manager: <TariConsensusSpec as ConsensusSpec>::EpochManager,
 }
 
 impl ConsensusHandle {
@@ -22,13 +32,54 @@ impl ConsensusHandle {
         events_subscription: EventSubscription<HotstuffEvent>,
         current_view: CurrentView,
         tx_new_transaction: mpsc::Sender<(Transaction, usize)>,
+        leader_strategy: <TariConsensusSpec as ConsensusSpec>::LeaderStrategy,
+        epoch_manager: <TariConsensusSpec as ConsensusSpec>::EpochManager,
     ) -> Self {
         Self {
             rx_current_state,
             events_subscription,
             current_view,
             tx_new_transaction,
+            leader_strategy,
+            epoch_manager,
+        }
+    }
+
+    /// Returns the ordered leader public keys for the heights `from_height..=to_height` of the committee for
+    /// `shard_group` in `epoch`, using the configured leader strategy. At most [MAX_LEADER_SCHEDULE_HEIGHTS] heights
+    /// may be requested.
+    pub async fn get_leader_schedule(
+        &self,
+        epoch: Epoch,
+        shard_group: ShardGroup,
+        from_height: NodeHeight,
+        to_height: NodeHeight,
+    ) -> Result<Vec<PublicKey>, LeaderScheduleError> {
+        if from_height > to_height {
+            return Err(LeaderScheduleError::InvalidRange { from_height, to_height });
         }
+        let num_heights = (to_height - from_height).as_u64().saturating_add(1);
+        if num_heights > MAX_LEADER_SCHEDULE_HEIGHTS {
+            return Err(LeaderScheduleError::RangeTooLarge {
+                num_heights,
+                max_heights: MAX_LEADER_SCHEDULE_HEIGHTS,
+            });
+        }
+
+        let committee = self
+            .epoch_manager
+            .get_committees_by_shard_group(epoch, shard_group)
+            .await?
+            .remove(&shard_group)
+            .filter(|committee| !committee.is_empty())
+            .ok_or(LeaderScheduleError::CommitteeNotFound { epoch, shard_group })?;
+
+        Ok(self
+            .leader_strategy
+            .get_leader_schedule(&committee, from_height, to_height)
+            .into_iter()
+            .cloned()
+            .collect())
     }
 
     pub fn current_epoch(&self) -> Epoch {
@@ -62,3 +113,18 @@ impl ConsensusHandle {
         self.get_current_state().is_running()
     }
 }
+
+#[derive(Debug, thiserror::Error)]
+pub enum LeaderScheduleError {
+    #[error("Invalid height range: from height {from_height} is greater than to height {to_height}")]
+    InvalidRange {
+        from_height: NodeHeight,
+        to_height: NodeHeight,
+    },
+    #[error("Height range of {num_heights} heights exceeds the maximum of {max_heights}")]
+    RangeTooLarge { num_heights: u64, max_heights: u64 },
+    #[error("No committee for shard group {shard_group} in epoch {epoch}")]
+    CommitteeNotFound { epoch: Epoch, shard_group: ShardGroup },
+    #[error("Epoch manager error: {0}")]
+    EpochManagerError(#[from] EpochManagerError),
+}
@@ -1,10 +1,24 @@
 //   Copyright 2024 The Tari Project
 //   SPDX-License-Identifier: BSD-3-Clause
 
-use std::{collections::HashMap, str::FromStr};
+use std::{collections::HashMap, str::FromStr, time::Duration};
 
-use prometheus::{core::Collector, IntCounter, IntGauge, IntGaugeVec, Opts, Registry};
-use tari_consensus::{hotstuff::HotStuffError, messages::HotstuffMessage, traits::hooks::ConsensusHooks};
+use prometheus::{
+    core::Collector,
+    Histogram,
+    HistogramOpts,
+    IntCounter,
+    IntCounterVec,
+    IntGauge,
+    IntGaugeVec,
+    Opts,
+    Registry,
+};
+use tari_consensus::{
+    hotstuff::HotStuffError,
+    messages::HotstuffMessage,
+    traits::hooks::{ConsensusHooks, SyncRequestRejection},
+};
 use tari_dan_common_types::{NodeHeight, PeerAddress};
 use tari_dan_storage::{
     consensus_models::{Decision, QuorumDecision, TransactionAtom, ValidBlock},
@@ -33,6 +47,13 @@ pub struct PrometheusConsensusMetrics<S = SqliteStateStore<PeerAddress>> {
     pacemaker_leader_failures: IntCounter,
     needs_sync: IntCounter,
 
+    sync_requests_served: IntCounter,
+    sync_blocks_served: IntCounter,
+    sync_bytes_served: IntCounter,
+    sync_request_duration: Histogram,
+    sync_requests_rejected: IntCounterVec,
+    sync_requests_truncated: IntCounter,
+
     _transactions_pool_size: IntGauge,
     transactions_ready_for_consensus: IntCounter,
     transactions_finalized_committed: IntCounter,
@@ -79,6 +100,45 @@ impl<S: StateStore> PrometheusConsensusMetrics<S> {
             needs_sync: IntCounter::new("consensus_needs_sync", "Number of times consensus needs to sync")
                 .unwrap()
                 .register_at(registry),
+            sync_requests_served: IntCounter::new(
+                "consensus_sync_requests_served",
+                "Number of catch-up sync requests served",
+            )
+            .unwrap()
+            .register_at(registry),
+            sync_blocks_served: IntCounter::new(
+                "consensus_sync_blocks_served",
+                "Number of blocks sent in response to catch-up sync requests",
+            )
+            .unwrap()
+            .register_at(registry),
+            sync_bytes_served: IntCounter::new(
+                "consensus_sync_bytes_served",
+                "Number of encoded block bytes sent in response to catch-up sync requests",
+            )
+            .unwrap()
+            .register_at(registry),
+            sync_request_duration: Histogram::with_opts(HistogramOpts::new(
+                "consensus_sync_request_duration",
+                "Time in seconds taken to serve a catch-up sync request",
+            ))
+            .unwrap()
+            .register_at(registry),
+            sync_requests_rejected: IntCounterVec::new(
+                Opts::new(
+                    "consensus_sync_requests_rejected",
+                    "Number of catch-up sync requests that were not served in full by rejection reason",
+                ),
+                &["reason"],
+            )
+            .unwrap()
+            .register_at(registry),
+            sync_requests_truncated: IntCounter::new(
+                "consensus_sync_requests_truncated",
+                "Number of unpaginated catch-up sync requests that were served up to the maximum response size",
+            )
+            .unwrap()
+            .register_at(registry),
             transactions_ready_for_consensus: IntCounter::new(
                 "consensus_transaction_ready_for_consensus",
                 "Number of transactions ready for consensus",
@@ -175,6 +235,21 @@ impl<S: StateStore> ConsensusHooks for PrometheusConsensusMetrics<S> {
         self.needs_sync.inc();
     }
 
+    fn on_sync_request_served(&mut self, num_blocks: usize, num_bytes: usize, elapsed: Duration) {
+        self.sync_requests_served.inc();
+        self.sync_blocks_served.inc_by(num_blocks as u64);
+        self.sync_bytes_served.inc_by(num_bytes as u64);
+        self.sync_request_duration.observe(elapsed.as_secs_f64());
+    }
+
+    fn on_sync_request_rejected(&mut self, reason: SyncRequestRejection) {
+        self.sync_requests_rejected.with_label(&reason).inc();
+    }
+
+    fn on_sync_request_truncated(&mut self, _num_blocks_requested: u64, _num_blocks_served: u64) {
+        self.sync_requests_truncated.inc();
+    }
+
     fn on_transaction_ready(&mut self, _tx_id: &TransactionId) {
         self.transactions_ready_for_consensus.inc();
     }
@@ -14,17 +14,16 @@ use tari_dan_app_utilities::{
 use tari_dan_common_types::PeerAddress;
 use tari_dan_storage::consensus_models::TransactionPool;
 use tari_epoch_manager::base_layer::EpochManagerHandle;
-use tari_rpc_state_sync::RpcStateSyncManager;
 use tari_shutdown::ShutdownSignal;
 use tari_state_store_sqlite::SqliteStateStore;
 use tari_transaction::Transaction;
-use tari_validator_node_rpc::client::TariValidatorNodeRpcClientFactory;
 use tokio::{
     sync::{broadcast, mpsc, watch},
     task::JoinHandle,
 };
 
 use crate::{
+    config::ConsensusConfig,
     consensus::{leader_selection::RoundRobinLeaderStrategy, spec::TariConsensusSpec},
     event_subscription::EventSubscription,
     p2p::services::messaging::{ConsensusInboundMessaging, ConsensusOutboundMessaging},
@@ -47,10 +46,12 @@ mod leader_selection;
 pub mod metrics;
 mod signature_service;
 mod spec;
+mod sync_manager;
 
 pub use block_transaction_executor::*;
 pub use handle::*;
 pub use signature_service::*;
+pub use sync_manager::*;
 use tari_consensus::consensus_constants::ConsensusConstants;
 
 use crate::{p2p::NopLogger, transaction_validators::WithContext};
@@ -66,7 +67,7 @@ pub async fn spawn(
     epoch_manager: EpochManagerHandle<PeerAddress>,
     inbound_messaging: ConsensusInboundMessaging<NopLogger>,
     outbound_messaging: ConsensusOutboundMessaging<NopLogger>,
-    client_factory: TariValidatorNodeRpcClientFactory,
+    state_sync: TariSyncManager,
     hooks: <TariConsensusSpec as ConsensusSpec>::Hooks,
     shutdown_signal: ShutdownSignal,
     transaction_executor: TariDanBlockTransactionExecutor<
@@ -74,6 +75,7 @@ pub async fn spawn(
         ConsensusTransactionValidator,
     >,
     consensus_constants: ConsensusConstants,
+    config: &ConsensusConfig,
 ) -> (JoinHandle<Result<(), anyhow::Error>>, ConsensusHandle) {
     let (tx_new_transaction, rx_new_transactions) = mpsc::channel(10);
 
@@ -85,6 +87,11 @@ pub async fn spawn(
         network,
         sidechain_id,
         consensus_constants,
+        sync_response_message_interval: config.sync_response_message_interval(),
+        sync_response_timeout: config.sync_response_timeout(),
+        sync_response_send_timeout: config.sync_response_send_timeout(),
+        sync_request_max_blocks: config.sync_request_max_blocks,
+        sync_request_verify_qc: config.sync_request_verify_qc,
     };
 
     let hotstuff_worker = HotstuffWorker::<TariConsensusSpec>::new(
@@ -93,7 +100,7 @@ pub async fn spawn(
         inbound_messaging,
         outbound_messaging,
         rx_new_transactions,
-        store.clone(),
+        store,
         epoch_manager.clone(),
         leader_strategy,
         signing_service,
@@ -109,7 +116,7 @@ pub async fn spawn(
     let context = ConsensusWorkerContext {
         epoch_manager: epoch_manager.clone(),
         hotstuff: hotstuff_worker,
-        state_sync: RpcStateSyncManager::new(epoch_manager, store, client_factory),
+        state_sync,
         tx_current_state,
     };
 
@@ -120,6 +127,8 @@ pub async fn spawn(
         EventSubscription::new(tx_hotstuff_events),
         current_view,
         tx_new_transaction,
+        leader_strategy,
+        epoch_manager,
     );
 
     (join_handle, consensus_handle)
@@ -0,0 +1,35 @@
+//    Copyright 2024 The Tari Project
+//    SPDX-License-Identifier: BSD-3-Clause
+
+use async_trait::async_trait;
+use tari_consensus::{
+    hotstuff::HotStuffError,
+    traits::{SyncManager, SyncStatus},
+};
+use tari_rpc_state_sync::RpcStateSyncManager;
+
+use crate::consensus::spec::TariConsensusSpec;
+
+/// A [SyncManager] whose transport is selected when consensus is spawned. An alternative sync transport, for where the
+/// comms RPC layer is not available, is added as another variant.
+pub enum TariSyncManager {
+    /// Syncs state from the previous epoch's committees over comms RPC. This is the default.
+    Rpc(RpcStateSyncManager<TariConsensusSpec>),
+}
+
+#[async_trait]
+impl SyncManager for TariSyncManager {
+    type Error = HotStuffError;
+
+    async fn check_sync(&self) -> Result<SyncStatus, Self::Error> {
+        match self {
+            Self::Rpc(sync_manager) => Ok(sync_manager.check_sync().await?),
+        }
+    }
+
+    async fn sync(&mut self) -> Result<(), Self::Error> {
+        match self {
+            Self::Rpc(sync_manager) => Ok(sync_manager.sync().await?),
+        }
+    }
+}
@@ -62,6 +62,8 @@ use tari_validator_node_client::types::{
     GetEpochManagerStatsResponse,
     GetFilteredBlocksCountRequest,
     GetIdentityResponse,
+    GetLeaderScheduleRequest,
+    GetLeaderScheduleResponse,
     GetMempoolStatsResponse,
     GetRecentTransactionsResponse,
     GetShardKeyRequest,
@@ -91,6 +93,7 @@ use tari_validator_node_client::types::{
 };
 
 use crate::{
+    consensus::{ConsensusHandle, LeaderScheduleError},
     dry_run_transaction_processor::DryRunTransactionProcessor,
     json_rpc::jrpc_errors::{internal_error, not_found},
     p2p::services::mempool::MempoolHandle,
@@ -104,6 +107,7 @@ pub struct JsonRpcHandlers {
     mempool: MempoolHandle,
     template_manager: TemplateManagerHandle,
     epoch_manager: EpochManagerHandle<PeerAddress>,
+    consensus: ConsensusHandle,
     networking: NetworkingHandle<TariMessagingSpec>,
     base_node_client: GrpcBaseNodeClient,
     state_store: SqliteStateStore<PeerAddress>,
@@ -116,6 +120,7 @@ impl JsonRpcHandlers {
             keypair: services.keypair.clone(),
             mempool: services.mempool.clone(),
             epoch_manager: services.epoch_manager.clone(),
+            consensus: services.consensus_handle.clone(),
             template_manager: services.template_manager.clone(),
             networking: services.networking.clone(),
             base_node_client,
@@ -742,6 +747,31 @@ impl JsonRpcHandlers {
         }
     }
 
+    pub async fn get_leader_schedule(&self, value: JsonRpcExtractor) -> JrpcResult {
+        let answer_id = value.get_answer_id();
+        let request = value.parse_params::<GetLeaderScheduleRequest>()?;
+        let leaders = self
+            .consensus
+            .get_leader_schedule(
+                request.epoch,
+                request.shard_group,
+                request.from_height,
+                request.to_height,
+            )
+            .await
+            .map_err(|err| match err {
+                LeaderScheduleError::EpochManagerError(err) => internal_error(answer_id)(err),
+                err @ LeaderScheduleError::CommitteeNotFound { .. } => not_found(answer_id, err.to_string()),
+                err => JsonRpcResponse::error(
+                    answer_id,
+                    JsonRpcError::new(JsonRpcErrorReason::InvalidParams, err.to_string(), json::Value::Null),
+                ),
+            })?;
+        Ok(JsonRpcResponse::success(answer_id, GetLeaderScheduleResponse {
+            leaders,
+        }))
+    }
+
     pub async fn get_all_vns(&self, value: JsonRpcExtractor) -> JrpcResult {
         let answer_id = value.get_answer_id();
         let GetAllVnsRequest { epoch } = value.parse_params::<GetAllVnsRequest>()?;
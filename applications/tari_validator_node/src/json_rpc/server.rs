@@ -90,6 +90,7 @@ async fn handler(Extension(handlers): Extension<Arc<JsonRpcHandlers>>, value: Js
         "get_epoch_manager_stats" => handlers.get_epoch_manager_stats(value).await,
         "get_shard_key" => handlers.get_shard_key(value).await,
         "get_committee" => handlers.get_committee(value).await,
+        "get_leader_schedule" => handlers.get_leader_schedule(value).await,
         "get_all_vns" => handlers.get_all_vns(value).await,
         // "get_network_committees" => handlers.get_network_committees(value).await,
         "get_fees" => handlers.get_validator_fees(value).await,
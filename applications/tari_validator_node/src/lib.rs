@@ -43,7 +43,7 @@ use std::{fs, io, process};
 
 use log::*;
 use serde::{Deserialize, Serialize};
-use tari_base_node_client::{grpc::GrpcBaseNodeClient, BaseNodeClientError};
+use tari_base_node_client::{grpc::GrpcBaseNodeClient, BaseNodeClient, BaseNodeClientError};
 use tari_common::{
     configuration::bootstrap::{grpc_default_port, ApplicationType},
     exit_codes::{ExitCode, ExitError},
@@ -177,17 +177,17 @@ async fn create_base_layer_client(config: &ApplicationConfig) -> Result<GrpcBase
             .expect("Default base node GRPC URL is malformed")
     });
     info!(target: LOG_TARGET, "Connecting to base node on GRPC at {}", base_node_address);
-    let base_node_client = GrpcBaseNodeClient::connect(base_node_address.clone())
-        .await
-        .map_err(|error| {
-            ExitError::new(
-                ExitCode::ConfigError,
-                format!(
-                    "Could not connect to the Minotari node at address {base_node_address}: {error}. Please ensure \
-                     that the Minotari node is running and configured for GRPC."
-                ),
-            )
-        })?;
+    let mut base_node_client = GrpcBaseNodeClient::new(base_node_address.clone())
+        .with_skip_invalid_validator_nodes(config.validator_node.base_node_skip_invalid_validator_nodes);
+    base_node_client.test_connection().await.map_err(|error| {
+        ExitError::new(
+            ExitCode::ConfigError,
+            format!(
+                "Could not connect to the Minotari node at address {base_node_address}: {error}. Please ensure that \
+                 the Minotari node is running and configured for GRPC."
+            ),
+        )
+    })?;
 
     Ok(base_node_client)
 }
@@ -1,14 +1,40 @@
 // Copyright 2024 The Tari Project
 // SPDX-License-Identifier: BSD-3-Clause
 
-use std::path::PathBuf;
+use std::{net::SocketAddr, path::PathBuf, time::Duration};
 
 use tokio::io::{self, AsyncWriteExt};
 use url::Url;
 
 use crate::{
     cli::Cli,
-    constants::{DEFAULT_BASE_NODE_GRPC_URL, DEFAULT_BASE_WALLET_GRPC_URL, DEFAULT_VALIDATOR_NODE_BINARY_PATH},
+    constants::{
+        DEFAULT_ALERT_CHANNEL_CAPACITY,
+        DEFAULT_ALERT_CRASHED_TEMPLATE,
+        DEFAULT_ALERT_CRASH_LOOP_TEMPLATE,
+        DEFAULT_ALERT_EXITED_TEMPLATE,
+        DEFAULT_ALERT_INTERNAL_ERROR_TEMPLATE,
+        DEFAULT_ALERT_KILLED_TEMPLATE,
+        DEFAULT_ALERT_NODE_NAME,
+        DEFAULT_ALERT_OOM_KILLED_TEMPLATE,
+        DEFAULT_ALERT_REGISTRATION_EXPIRING_SOON_TEMPLATE,
+        DEFAULT_ALERT_RESTARTED_TEMPLATE,
+        DEFAULT_ALERT_RESTART_HOOK_FAILED_TEMPLATE,
+        DEFAULT_ALERT_SUBMITTED_TEMPLATE,
+        DEFAULT_ALERT_UNRESPONSIVE_TEMPLATE,
+        DEFAULT_BASE_NODE_GRPC_URL,
+        DEFAULT_BASE_WALLET_GRPC_URL,
+        DEFAULT_CRASH_ESCALATION_THRESHOLD,
+        DEFAULT_CRASH_ESCALATION_WINDOW_SECS,
+        DEFAULT_LIVENESS_PROBE_INTERVAL_SECS,
+        DEFAULT_LIVENESS_PROBE_TIMEOUT_SECS,
+        DEFAULT_LOG_CHANNEL_CAPACITY,
+        DEFAULT_REGISTRATION_EXPIRY_WARNING_EPOCHS,
+        DEFAULT_REGISTRATION_RETRY_ATTEMPTS,
+        DEFAULT_REGISTRATION_RETRY_DELAY_SECS,
+        DEFAULT_VALIDATOR_NODE_BINARY_PATH,
+        DEFAULT_VALIDATOR_NODE_JSON_RPC_URL,
+    },
 };
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -44,6 +70,58 @@ pub struct Config {
 
     /// The channel configurations for alerting and monitoring
     pub channel_config: Channels,
+
+    /// Retry policy used when a registration transaction cannot be submitted because the wallet is unavailable.
+    /// Submissions that reached the wallet are never retried.
+    #[serde(default)]
+    pub registration_retry: RetryConfig,
+
+    /// Warning sent ahead of the expiry of the validator node registration, so that it can be renewed in time
+    #[serde(default)]
+    pub registration_expiry: RegistrationExpiryConfig,
+
+    /// Optional on-disk history of the most recent alerts sent, kept for postmortems
+    #[serde(default)]
+    pub alert_history: Option<AlertHistoryConfig>,
+
+    /// Message templates used for alerts, e.g. to include the node name in every alert
+    #[serde(default)]
+    pub alert_templates: AlertTemplates,
+
+    /// Escalation policy used when the validator node crashes repeatedly in a short period of time
+    #[serde(default)]
+    pub crash_escalation: CrashEscalationConfig,
+
+    /// Optional liveness probe used to detect a validator node that is still running but has stopped responding
+    #[serde(default)]
+    pub liveness_probe: Option<LivenessProbeConfig>,
+
+    /// Optional address on which the PID and uptime of the validator node process are served as JSON over HTTP, and on
+    /// which operators can restart the validator node and mute or unmute alert channels. This address should not be
+    /// publicly reachable.
+    #[serde(default)]
+    pub status_listener_address: Option<SocketAddr>,
+
+    /// Optional path to the cgroup v2 `memory.events` file of the cgroup the validator node runs in, e.g.
+    /// `/sys/fs/cgroup/validator_node/memory.events`. A validator node killed with SIGKILL is only reported as OOM
+    /// killed if the `oom_kill` count of the cgroup increased while it was running, otherwise it is reported as killed
+    /// by an unknown source.
+    #[serde(default)]
+    pub cgroup_memory_events_path: Option<PathBuf>,
+
+    /// Optional shell command run before the watcher stops the validator node to restart it, i.e. on a requested
+    /// restart or when an unresponsive node is killed. The restart proceeds even if the command fails or times out.
+    #[serde(default)]
+    pub pre_restart_cmd: Option<String>,
+
+    /// Optional shell command run after the validator node has been started again following any restart, e.g. to
+    /// notify a load balancer. A failure is alerted but does not affect the running node.
+    #[serde(default)]
+    pub post_restart_cmd: Option<String>,
+
+    /// Capacities of the channels that carry validator node status events to the logging and alerting tasks
+    #[serde(default)]
+    pub status_channels: StatusChannelsConfig,
 }
 
 impl Config {
@@ -63,6 +141,177 @@ pub struct ChannelConfig {
     pub credentials: String,
 }
 
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct RetryConfig {
+    /// The maximum number of attempts, including the first one
+    pub attempts: u32,
+    /// The delay in seconds between attempts
+    pub delay_secs: u64,
+}
+
+impl RetryConfig {
+    pub fn delay(&self) -> Duration {
+        Duration::from_secs(self.delay_secs)
+    }
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            attempts: DEFAULT_REGISTRATION_RETRY_ATTEMPTS,
+            delay_secs: DEFAULT_REGISTRATION_RETRY_DELAY_SECS,
+        }
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct RegistrationExpiryConfig {
+    /// Warn when the registration expires within this many epochs, or never warn if 0
+    pub warning_epochs: u64,
+}
+
+impl Default for RegistrationExpiryConfig {
+    fn default() -> Self {
+        Self {
+            warning_epochs: DEFAULT_REGISTRATION_EXPIRY_WARNING_EPOCHS,
+        }
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct AlertHistoryConfig {
+    /// The path of the JSON-lines file the alerts are written to
+    pub path: PathBuf,
+    /// The maximum number of alerts kept, the oldest alerts are dropped first
+    pub capacity: usize,
+}
+
+/// Alert message templates. The placeholders `{node_name}`, `{code}`, `{error}`, `{tx_id}`, `{block}`, `{count}`,
+/// `{window_secs}`, `{unresponsive_secs}`, `{hook}` and `{blocks_remaining}` are replaced with the values of the status
+/// being alerted on, where applicable.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct AlertTemplates {
+    /// The name used to identify this node in alerts
+    pub node_name: String,
+    pub exited: String,
+    pub internal_error: String,
+    pub crashed: String,
+    pub crash_loop: String,
+    pub unresponsive: String,
+    pub oom_killed: String,
+    pub killed: String,
+    pub restarted: String,
+    pub restart_hook_failed: String,
+    pub submitted: String,
+    pub registration_expiring_soon: String,
+}
+
+impl Default for AlertTemplates {
+    fn default() -> Self {
+        Self {
+            node_name: DEFAULT_ALERT_NODE_NAME.to_string(),
+            exited: DEFAULT_ALERT_EXITED_TEMPLATE.to_string(),
+            internal_error: DEFAULT_ALERT_INTERNAL_ERROR_TEMPLATE.to_string(),
+            crashed: DEFAULT_ALERT_CRASHED_TEMPLATE.to_string(),
+            crash_loop: DEFAULT_ALERT_CRASH_LOOP_TEMPLATE.to_string(),
+            unresponsive: DEFAULT_ALERT_UNRESPONSIVE_TEMPLATE.to_string(),
+            oom_killed: DEFAULT_ALERT_OOM_KILLED_TEMPLATE.to_string(),
+            killed: DEFAULT_ALERT_KILLED_TEMPLATE.to_string(),
+            restarted: DEFAULT_ALERT_RESTARTED_TEMPLATE.to_string(),
+            restart_hook_failed: DEFAULT_ALERT_RESTART_HOOK_FAILED_TEMPLATE.to_string(),
+            submitted: DEFAULT_ALERT_SUBMITTED_TEMPLATE.to_string(),
+            registration_expiring_soon: DEFAULT_ALERT_REGISTRATION_EXPIRING_SOON_TEMPLATE.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct CrashEscalationConfig {
+    /// The number of crashes within the window at which a critical crash loop alert is sent
+    pub threshold: usize,
+    /// The length in seconds of the window in which crashes are counted
+    pub window_secs: u64,
+    /// Stop restarting the validator node once it is detected to be in a crash loop
+    pub stop_auto_restart: bool,
+}
+
+impl CrashEscalationConfig {
+    pub fn window(&self) -> Duration {
+        Duration::from_secs(self.window_secs)
+    }
+}
+
+impl Default for CrashEscalationConfig {
+    fn default() -> Self {
+        Self {
+            threshold: DEFAULT_CRASH_ESCALATION_THRESHOLD,
+            window_secs: DEFAULT_CRASH_ESCALATION_WINDOW_SECS,
+            stop_auto_restart: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct LivenessProbeConfig {
+    /// The JSON-RPC URL of the validator node that is probed
+    pub json_rpc_url: Url,
+    /// The interval in seconds between probes
+    pub interval_secs: u64,
+    /// The number of seconds without a successful probe after which the validator node is considered unresponsive
+    pub timeout_secs: u64,
+    /// Kill the validator node when it is unresponsive, so that it is restarted if auto restart is enabled
+    pub restart: bool,
+}
+
+impl LivenessProbeConfig {
+    pub fn interval(&self) -> Duration {
+        Duration::from_secs(self.interval_secs)
+    }
+
+    pub fn timeout(&self) -> Duration {
+        Duration::from_secs(self.timeout_secs)
+    }
+}
+
+impl Default for LivenessProbeConfig {
+    fn default() -> Self {
+        Self {
+            json_rpc_url: DEFAULT_VALIDATOR_NODE_JSON_RPC_URL
+                .parse()
+                .expect("default JSON-RPC URL is valid"),
+            interval_secs: DEFAULT_LIVENESS_PROBE_INTERVAL_SECS,
+            timeout_secs: DEFAULT_LIVENESS_PROBE_TIMEOUT_SECS,
+            restart: false,
+        }
+    }
+}
+
+/// Capacities of the status event channels. When the logging channel is full, the oldest events are dropped so that
+/// logging never holds up the watcher. When the alerting channel is full, senders wait for the alerting task to catch
+/// up, so that no alert is lost. The validator node restart is signalled before its exit is alerted, so a slow
+/// alerting channel does not delay restarts.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct StatusChannelsConfig {
+    /// The number of status events buffered for the logging task
+    pub log_capacity: usize,
+    /// The number of status events buffered for the alerting task
+    pub alert_capacity: usize,
+}
+
+impl Default for StatusChannelsConfig {
+    fn default() -> Self {
+        Self {
+            log_capacity: DEFAULT_LOG_CHANNEL_CAPACITY,
+            alert_capacity: DEFAULT_ALERT_CHANNEL_CAPACITY,
+        }
+    }
+}
+
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct Channels {
     pub mattermost: ChannelConfig,
@@ -100,5 +349,16 @@ pub fn get_base_config(cli: &Cli) -> anyhow::Result<Config> {
                 credentials: "".to_string(),
             },
         },
+        registration_retry: RetryConfig::default(),
+        registration_expiry: RegistrationExpiryConfig::default(),
+        alert_history: None,
+        alert_templates: AlertTemplates::default(),
+        crash_escalation: CrashEscalationConfig::default(),
+        liveness_probe: None,
+        status_listener_address: None,
+        cgroup_memory_events_path: None,
+        pre_restart_cmd: None,
+        post_restart_cmd: None,
+        status_channels: StatusChannelsConfig::default(),
     })
 }
@@ -4,8 +4,42 @@
 pub const DEFAULT_WATCHER_BASE_PATH: &str = "data/watcher/";
 pub const DEFAULT_WATCHER_CONFIG_PATH: &str = "data/watcher/config.toml";
 pub const DEFAULT_VALIDATOR_PID_PATH: &str = "data/watcher/validator.pid";
+pub const DEFAULT_VALIDATOR_STDERR_LOG_PATH: &str = "data/watcher/validator_stderr.log";
 pub const DEFAULT_VALIDATOR_DIR: &str = "data/vn1";
 pub const DEFAULT_VALIDATOR_KEY_PATH: &str = "data/vn1/esmeralda/registration.json";
 pub const DEFAULT_VALIDATOR_NODE_BINARY_PATH: &str = "target/release/tari_validator_node";
 pub const DEFAULT_BASE_NODE_GRPC_URL: &str = "http://127.0.0.1:12001"; // note: protocol
 pub const DEFAULT_BASE_WALLET_GRPC_URL: &str = "http://127.0.0.1:12003"; // note: protocol
+pub const DEFAULT_REGISTRATION_RETRY_ATTEMPTS: u32 = 3;
+pub const DEFAULT_REGISTRATION_RETRY_DELAY_SECS: u64 = 5;
+pub const DEFAULT_ALERT_NODE_NAME: &str = "validator node";
+pub const DEFAULT_ALERT_EXITED_TEMPLATE: &str = "Validator node process exited with code {code}";
+pub const DEFAULT_ALERT_INTERNAL_ERROR_TEMPLATE: &str = "Validator node process internal error: {error}";
+pub const DEFAULT_ALERT_CRASHED_TEMPLATE: &str = "Validator node process crashed";
+pub const DEFAULT_ALERT_SUBMITTED_TEMPLATE: &str =
+    "Validator node registration submitted (tx: {tx_id}, block: {block})";
+pub const DEFAULT_ALERT_CRASH_LOOP_TEMPLATE: &str =
+    "CRITICAL: Validator node is in a crash loop ({count} crashes in the last {window_secs}s)";
+pub const DEFAULT_CRASH_ESCALATION_THRESHOLD: usize = 3;
+pub const DEFAULT_CRASH_ESCALATION_WINDOW_SECS: u64 = 600;
+pub const DEFAULT_ALERT_UNRESPONSIVE_TEMPLATE: &str =
+    "Validator node process is running but has been unresponsive for {unresponsive_secs}s";
+pub const DEFAULT_ALERT_OOM_KILLED_TEMPLATE: &str =
+    "CRITICAL: Validator node process was killed by the kernel OOM killer, reduce its load or add memory";
+pub const DEFAULT_ALERT_KILLED_TEMPLATE: &str =
+    "Validator node process was killed with SIGKILL by an unknown source, check the kernel log for an OOM kill";
+pub const DEFAULT_ALERT_RESTARTED_TEMPLATE: &str = "Validator node process was restarted on request";
+pub const DEFAULT_RESTART_GRACE_PERIOD_SECS: u64 = 30;
+pub const DEFAULT_ALERT_RESTART_HOOK_FAILED_TEMPLATE: &str =
+    "Validator node {hook} hook failed, the restart proceeded regardless: {error}";
+pub const DEFAULT_RESTART_HOOK_TIMEOUT_SECS: u64 = 60;
+pub const DEFAULT_STDERR_TAIL_LINES: usize = 20;
+pub const DEFAULT_STDERR_TAIL_MAX_BYTES: u64 = 64 * 1024;
+pub const DEFAULT_VALIDATOR_NODE_JSON_RPC_URL: &str = "http://127.0.0.1:18200/json_rpc";
+pub const DEFAULT_LIVENESS_PROBE_INTERVAL_SECS: u64 = 30;
+pub const DEFAULT_LIVENESS_PROBE_TIMEOUT_SECS: u64 = 300;
+pub const DEFAULT_REGISTRATION_EXPIRY_WARNING_EPOCHS: u64 = 2;
+pub const DEFAULT_ALERT_REGISTRATION_EXPIRING_SOON_TEMPLATE: &str =
+    "WARNING: Validator node registration expires in {blocks_remaining} blocks, re-register to stay in the committee";
+pub const DEFAULT_LOG_CHANNEL_CAPACITY: usize = 64;
+pub const DEFAULT_ALERT_CHANNEL_CAPACITY: usize = 16;
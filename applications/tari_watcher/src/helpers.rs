@@ -63,3 +63,29 @@ pub fn to_vn_public_keys(vns: Vec<GetActiveValidatorNodesResponse>) -> Vec<Publi
 pub fn contains_key(vns: Vec<RistrettoPublicKey>, needle: PublicKey) -> bool {
     vns.iter().any(|vn| vn.eq(&needle))
 }
+
+// Returns the number of blocks from `height` until `expiry_height` if the expiry is within `warning_epochs` epochs,
+// otherwise None.
+pub fn blocks_remaining_within_warning(
+    expiry_height: u64,
+    height: u64,
+    epoch_length: u64,
+    warning_epochs: u64,
+) -> Option<u64> {
+    let blocks_remaining = expiry_height.saturating_sub(height);
+    (blocks_remaining <= warning_epochs.saturating_mul(epoch_length)).then_some(blocks_remaining)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_warns_only_when_the_expiry_is_within_the_warning_window() {
+        assert_eq!(blocks_remaining_within_warning(50, 25, 10, 2), None);
+        assert_eq!(blocks_remaining_within_warning(50, 30, 10, 2), Some(20));
+        assert_eq!(blocks_remaining_within_warning(50, 45, 10, 2), Some(5));
+        assert_eq!(blocks_remaining_within_warning(50, 45, 10, 0), None);
+        assert_eq!(blocks_remaining_within_warning(50, 50, 10, 0), Some(0));
+    }
+}
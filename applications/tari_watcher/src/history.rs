@@ -0,0 +1,79 @@
+// Copyright 2024 The Tari Project
+// SPDX-License-Identifier: BSD-3-Clause
+
+use std::{
+    io,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use tokio::fs;
+
+use crate::config::AlertHistoryConfig;
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct AlertRecord {
+    // Seconds since the UNIX epoch at which the status that triggered the alert was observed
+    pub timestamp: u64,
+    // The process status that triggered the alert
+    pub status: String,
+    // The alert message sent to the channel(s)
+    pub message: String,
+}
+
+// Keeps the last `capacity` alerts in a JSON-lines file on disk
+pub struct AlertHistory {
+    path: PathBuf,
+    capacity: usize,
+}
+
+impl AlertHistory {
+    pub fn new(config: AlertHistoryConfig) -> Self {
+        Self {
+            path: config.path,
+            capacity: config.capacity,
+        }
+    }
+
+    pub async fn record(&self, status: &str, message: &str, observed_at: SystemTime) -> anyhow::Result<()> {
+        let timestamp = observed_at.duration_since(UNIX_EPOCH)?.as_secs();
+        let mut records = read_alert_history(&self.path).await?;
+        records.push(AlertRecord {
+            timestamp,
+            status: status.to_string(),
+            message: message.to_string(),
+        });
+
+        // drop the oldest records once the capacity has been reached
+        let excess = records.len().saturating_sub(self.capacity);
+        let mut contents = String::new();
+        for record in records.iter().skip(excess) {
+            contents.push_str(&serde_json::to_string(record)?);
+            contents.push('\n');
+        }
+
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+        fs::write(&self.path, contents).await?;
+
+        Ok(())
+    }
+}
+
+// Reads back the recorded alerts, oldest first. A missing history file is treated as an empty history.
+pub async fn read_alert_history<P: AsRef<Path>>(path: P) -> anyhow::Result<Vec<AlertRecord>> {
+    let contents = match fs::read_to_string(path).await {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(e) => return Err(e.into()),
+    };
+
+    let records = contents
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(serde_json::from_str)
+        .collect::<Result<Vec<AlertRecord>, _>>()?;
+
+    Ok(records)
+}
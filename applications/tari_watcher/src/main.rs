@@ -15,6 +15,7 @@ use crate::{
     manager::{start_receivers, ManagerHandle, ProcessManager},
     process::create_pid_file,
     shutdown::exit_signal,
+    status::serve_status,
 };
 
 mod alerting;
@@ -22,6 +23,7 @@ mod cli;
 mod config;
 mod constants;
 mod helpers;
+mod history;
 mod logger;
 mod manager;
 mod minotari;
@@ -29,6 +31,7 @@ mod monitoring;
 mod process;
 mod registration;
 mod shutdown;
+mod status;
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
@@ -108,9 +111,31 @@ struct Handlers {
 }
 
 async fn spawn_manager(config: Config, shutdown: ShutdownSignal, trigger: Shutdown) -> anyhow::Result<Handlers> {
+    let alert_history = config.alert_history.clone();
+    let alert_templates = config.alert_templates.clone();
+    let crash_escalation = config.crash_escalation.clone();
+    let status_listener_address = config.status_listener_address;
     let (manager, manager_handle) = ProcessManager::new(config, shutdown, trigger);
     let cr = manager.start_request_handler().await?;
-    start_receivers(cr.rx_log, cr.rx_alert, cr.cfg_alert).await;
+    let rx_child = cr.rx_child.clone();
+    let child_control = cr.tx_command.clone();
+    let alert_control = start_receivers(
+        cr.rx_log,
+        cr.rx_alert,
+        cr.cfg_alert,
+        alert_history,
+        alert_templates,
+        crash_escalation,
+        cr.auto_restart,
+    )
+    .await;
+    if let Some(address) = status_listener_address {
+        tokio::spawn(async move {
+            if let Err(e) = serve_status(address, rx_child, alert_control, child_control).await {
+                log::error!("Validator node status server exited with error: {:#}", e);
+            }
+        });
+    }
 
     Ok(Handlers {
         manager: manager_handle,
@@ -1,6 +1,8 @@
 // Copyright 2024 The Tari Project
 // SPDX-License-Identifier: BSD-3-Clause
 
+use std::sync::{atomic::AtomicBool, Arc};
+
 use log::*;
 use minotari_app_grpc::tari_rpc::{
     self as grpc,
@@ -11,17 +13,29 @@ use minotari_app_grpc::tari_rpc::{
 use tari_shutdown::{Shutdown, ShutdownSignal};
 use tokio::{
     sync::{
+        broadcast,
         mpsc::{self, Receiver},
         oneshot,
+        watch,
     },
     task::JoinHandle,
 };
 
 use crate::{
-    config::{Channels, Config},
+    config::{AlertHistoryConfig, AlertTemplates, Channels, Config, CrashEscalationConfig},
     minotari::{MinotariNodes, TipStatus},
-    monitoring::{process_status_alert, process_status_log, ProcessStatus, Transaction},
+    monitoring::{
+        process_status_alert,
+        process_status_log,
+        send_to_logging,
+        AlertCommand,
+        ChildCommand,
+        ProcessStatus,
+        ProcessStatusEvent,
+        Transaction,
+    },
     process::{start_validator, ChildChannel},
+    status::ChildInfo,
 };
 
 pub struct ProcessManager {
@@ -33,9 +47,12 @@ pub struct ProcessManager {
 }
 
 pub struct ChannelReceivers {
-    pub rx_log: Receiver<ProcessStatus>,
-    pub rx_alert: Receiver<ProcessStatus>,
+    pub rx_log: broadcast::Receiver<ProcessStatusEvent>,
+    pub rx_alert: Receiver<ProcessStatusEvent>,
     pub cfg_alert: Channels,
+    pub auto_restart: Arc<AtomicBool>,
+    pub rx_child: watch::Receiver<Option<ChildInfo>>,
+    pub tx_command: broadcast::Sender<ChildCommand>,
     pub task: JoinHandle<()>,
 }
 
@@ -67,6 +84,8 @@ impl ProcessManager {
 
         info!("Setup completed: connected to base node and wallet, ready to receive requests");
         let task_handle = tokio::spawn(async move {
+            // the expiry height for which a warning was last sent, so that each upcoming expiry is only alerted once
+            let mut warned_expiry_height = None;
             loop {
                 tokio::select! {
                     Some(req) = self.rx_request.recv() => {
@@ -93,24 +112,68 @@ impl ProcessManager {
                                 drop(reply.send(Ok(response)));
                             }
                             ManagerRequest::RegisterValidatorNode { block, reply } => {
-                                let response = match self.chain.register_validator_node().await {
-                                    Ok(resp) => resp,
+                                match self.chain.is_registered_and_valid(block).await {
+                                    Ok(true) => {
+                                        info!("Validator node is already registered and not close to expiry, skipping registration");
+                                        send_to_logging(&cc.tx_log, ProcessStatusEvent::new(ProcessStatus::AlreadyRegistered(block)));
+                                        drop(reply.send(Ok(None)));
+                                        continue;
+                                    },
+                                    Ok(false) => {},
+                                    Err(e) => {
+                                        warn!("Failed to check for an existing registration, submitting anyway: {}", e);
+                                    },
+                                }
+
+                                let response = match self.chain.register_validator_node_with_retry(block, &self.config.registration_retry).await {
+                                    Ok(Some(resp)) => resp,
+                                    Ok(None) => {
+                                        send_to_logging(&cc.tx_log, ProcessStatusEvent::new(ProcessStatus::AlreadyRegistered(block)));
+                                        drop(reply.send(Ok(None)));
+                                        continue;
+                                    },
                                     Err(e) => {
-                                        error!("Failed to register validator node: {}", e);
+                                        error!("Failed to register validator node: {:#}", e);
+                                        let event = ProcessStatusEvent::new(ProcessStatus::InternalError(format!("Validator node registration failed: {:#}", e)));
+                                        send_to_logging(&cc.tx_log, event.clone());
+                                        if let Err(e) = cc.tx_alert.send(event).await {
+                                            error!("Failed to send node registration failure to alerting: {}", e);
+                                        }
+                                        drop(reply.send(Err(e)));
                                         continue;
                                     }
                                 };
 
-                                // send registration response to logger
-                                if let Err(e) = cc.tx_log.send(ProcessStatus::Submitted(Transaction::new(response.clone(), block))).await {
-                                    error!("Failed to send node registration update to monitoring: {}", e);
-                                }
-                                // send registration response to alerting
-                                if let Err(e) = cc.tx_alert.send(ProcessStatus::Submitted(Transaction::new(response.clone(), block))).await {
+                                let event = ProcessStatusEvent::new(ProcessStatus::Submitted(Transaction::new(response.clone(), block)));
+                                send_to_logging(&cc.tx_log, event.clone());
+                                if let Err(e) = cc.tx_alert.send(event).await {
                                     error!("Failed to send node registration update to alerting: {}", e);
                                 }
 
-                                drop(reply.send(Ok(response)));
+                                drop(reply.send(Ok(Some(response))));
+                            },
+                            ManagerRequest::CheckRegistrationExpiry { block, warning_epochs, reply } => {
+                                let blocks_remaining = match self.chain.get_registration_blocks_remaining(block, warning_epochs).await {
+                                    Ok(resp) => resp,
+                                    Err(e) => {
+                                        drop(reply.send(Err(e)));
+                                        continue;
+                                    }
+                                };
+
+                                if let Some(blocks_remaining) = blocks_remaining {
+                                    let expiry_height = block + blocks_remaining;
+                                    if warned_expiry_height != Some(expiry_height) {
+                                        warned_expiry_height = Some(expiry_height);
+                                        let event = ProcessStatusEvent::new(ProcessStatus::RegistrationExpiringSoon { blocks_remaining });
+                                        send_to_logging(&cc.tx_log, event.clone());
+                                        if let Err(e) = cc.tx_alert.send(event).await {
+                                            error!("Failed to send registration expiry warning to alerting: {}", e);
+                                        }
+                                    }
+                                }
+
+                                drop(reply.send(Ok(blocks_remaining)));
                             },
                             ManagerRequest::GetConsensusConstants { block, reply } => {
                                 let response = match self.chain.get_consensus_constants(block).await {
@@ -137,6 +200,9 @@ impl ProcessManager {
             rx_log: cc.rx_log,
             rx_alert: cc.rx_alert,
             cfg_alert: cc.cfg_alert,
+            auto_restart: cc.auto_restart,
+            rx_child: cc.rx_child,
+            tx_command: cc.tx_command,
             task: task_handle,
         })
     }
@@ -153,6 +219,11 @@ impl ProcessManager {
             self.config.base_node_grpc_url.clone(),
             self.config.channel_config.clone(),
             self.config.auto_restart,
+            self.config.liveness_probe.clone(),
+            self.config.cgroup_memory_events_path.clone(),
+            self.config.pre_restart_cmd.clone(),
+            self.config.post_restart_cmd.clone(),
+            self.config.status_channels.clone(),
             self.trigger_signal.clone(),
         )
         .await;
@@ -165,19 +236,35 @@ impl ProcessManager {
 }
 
 pub async fn start_receivers(
-    rx_log: mpsc::Receiver<ProcessStatus>,
-    rx_alert: mpsc::Receiver<ProcessStatus>,
+    rx_log: broadcast::Receiver<ProcessStatusEvent>,
+    rx_alert: mpsc::Receiver<ProcessStatusEvent>,
     cfg_alert: Channels,
-) {
+    cfg_history: Option<AlertHistoryConfig>,
+    cfg_templates: AlertTemplates,
+    cfg_escalation: CrashEscalationConfig,
+    auto_restart: Arc<AtomicBool>,
+) -> mpsc::Sender<AlertCommand> {
+    let (tx_control, rx_control) = mpsc::channel(10);
     // spawn logging and alerting tasks to process status updates
     tokio::spawn(async move {
         process_status_log(rx_log).await;
         warn!("Logging task has exited");
     });
     tokio::spawn(async move {
-        process_status_alert(rx_alert, cfg_alert).await;
+        process_status_alert(
+            rx_alert,
+            rx_control,
+            cfg_alert,
+            cfg_history,
+            cfg_templates,
+            cfg_escalation,
+            auto_restart,
+        )
+        .await;
         warn!("Alerting task has exited");
     });
+
+    tx_control
 }
 
 type Reply<T> = oneshot::Sender<anyhow::Result<T>>;
@@ -193,9 +280,17 @@ pub enum ManagerRequest {
         block: u64,
         reply: Reply<grpc::ConsensusConstants>,
     },
+    // Replies with None if the node is already registered and the registration was skipped
     RegisterValidatorNode {
         block: u64,
-        reply: Reply<RegisterValidatorNodeResponse>,
+        reply: Reply<Option<RegisterValidatorNodeResponse>>,
+    },
+    // Replies with the number of blocks until the registration expires, if that is within `warning_epochs` epochs.
+    // A warning is sent to monitoring and alerting the first time each upcoming expiry is detected.
+    CheckRegistrationExpiry {
+        block: u64,
+        warning_epochs: u64,
+        reply: Reply<Option<u64>>,
     },
 }
 
@@ -224,7 +319,10 @@ impl ManagerHandle {
         rx.await?
     }
 
-    pub async fn register_validator_node(&mut self, block: u64) -> anyhow::Result<RegisterValidatorNodeResponse> {
+    pub async fn register_validator_node(
+        &mut self,
+        block: u64,
+    ) -> anyhow::Result<Option<RegisterValidatorNodeResponse>> {
         let (tx, rx) = oneshot::channel();
         self.tx_request
             .send(ManagerRequest::RegisterValidatorNode { block, reply: tx })
@@ -232,6 +330,18 @@ impl ManagerHandle {
         rx.await?
     }
 
+    pub async fn check_registration_expiry(&mut self, block: u64, warning_epochs: u64) -> anyhow::Result<Option<u64>> {
+        let (tx, rx) = oneshot::channel();
+        self.tx_request
+            .send(ManagerRequest::CheckRegistrationExpiry {
+                block,
+                warning_epochs,
+                reply: tx,
+            })
+            .await?;
+        rx.await?
+    }
+
     pub async fn get_tip_info(&mut self) -> anyhow::Result<TipStatus> {
         let (tx, rx) = oneshot::channel();
         self.tx_request.send(ManagerRequest::GetTipInfo { reply: tx }).await?;
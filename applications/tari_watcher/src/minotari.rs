@@ -8,13 +8,18 @@ use log::*;
 use minotari_app_grpc::tari_rpc::{self as grpc, GetActiveValidatorNodesResponse, RegisterValidatorNodeResponse};
 use minotari_node_grpc_client::BaseNodeGrpcClient;
 use minotari_wallet_grpc_client::WalletGrpcClient;
+use tari_base_node_client::types::BaseLayerConsensusConstants;
 use tari_common::exit_codes::{ExitCode, ExitError};
-use tari_common_types::types::FixedHash;
+use tari_common_types::types::{FixedHash, PublicKey};
 use tari_crypto::tari_utilities::ByteArray;
+use tokio::time;
 use tonic::transport::Channel;
 use url::Url;
 
-use crate::helpers::read_registration_file;
+use crate::{
+    config::RetryConfig,
+    helpers::{blocks_remaining_within_warning, contains_key, read_registration_file, to_vn_public_keys},
+};
 
 #[derive(Clone)]
 pub struct MinotariNodes {
@@ -112,11 +117,14 @@ impl MinotariNodes {
     }
 
     pub async fn get_active_validator_nodes(&self) -> anyhow::Result<Vec<GetActiveValidatorNodesResponse>> {
+        self.get_active_validator_nodes_at(self.current_height).await
+    }
+
+    async fn get_active_validator_nodes_at(&self, height: u64) -> anyhow::Result<Vec<GetActiveValidatorNodesResponse>> {
         if !self.bootstrapped {
             bail!("Node client not connected");
         }
 
-        let height = self.current_height;
         let mut stream = self
             .node
             .clone()
@@ -150,6 +158,110 @@ impl MinotariNodes {
         Ok(vns)
     }
 
+    // Returns true if the local node is active at the given height and its registration remains valid for more than one
+    // epoch, i.e. the current registration is valid and not close to expiry so there is no need to submit a new one.
+    pub async fn is_registered_and_valid(&self, height: u64) -> anyhow::Result<bool> {
+        let Some(info) = read_registration_file(self.node_registration_file.clone()).await? else {
+            return Ok(false);
+        };
+
+        let active_keys = to_vn_public_keys(self.get_active_validator_nodes_at(height).await?);
+        if !contains_key(active_keys, info.public_key.clone()) {
+            return Ok(false);
+        }
+
+        let constants = self.get_consensus_constants(height).await?;
+        let Some(expiry_height) = self
+            .get_registration_expiry_height(&info.public_key, height, &constants)
+            .await?
+        else {
+            return Ok(false);
+        };
+
+        Ok(expiry_height.saturating_sub(height) > constants.epoch_length)
+    }
+
+    // Returns the height at which the latest registration of the given node that is still within its validity window at
+    // the given height expires, or None if there is no such registration. The expiry is derived from the activation
+    // height of the registration and the validity period, so only blocks up to the given height are queried.
+    async fn get_registration_expiry_height(
+        &self,
+        public_key: &PublicKey,
+        height: u64,
+        constants: &grpc::ConsensusConstants,
+    ) -> anyhow::Result<Option<u64>> {
+        if !self.bootstrapped {
+            bail!("Node client not connected");
+        }
+        if constants.epoch_length == 0 {
+            bail!("Base node returned an epoch length of 0");
+        }
+        if constants.validator_node_validity_period == 0 {
+            bail!("Base node returned a validator node validity period of 0");
+        }
+
+        let validity_period = constants.validator_node_validity_period;
+        let current_epoch = height / constants.epoch_length;
+        let start_height = current_epoch.saturating_sub(validity_period) * constants.epoch_length;
+        let changes = self
+            .node
+            .clone()
+            .unwrap()
+            .get_validator_node_changes(grpc::GetValidatorNodeChangesRequest {
+                start_height,
+                end_height: height,
+                sidechain_id: vec![],
+            })
+            .await?
+            .into_inner()
+            .changes;
+
+        let activation_height = changes
+            .into_iter()
+            .filter(|change| change.state() == grpc::ValidatorNodeChangeState::Add)
+            .filter(|change| change.public_key == public_key.as_bytes())
+            .map(|change| change.start_height)
+            .max();
+
+        let constants = BaseLayerConsensusConstants::from(constants);
+        Ok(activation_height
+            .and_then(|h| constants.registration_expiry_height(h))
+            .filter(|expiry_height| *expiry_height > height))
+    }
+
+    // Returns the number of blocks until the registration of the local node expires, if it expires within
+    // `warning_epochs` epochs of the given height. Returns None if the node is not active at the given height or
+    // remains active for longer.
+    pub async fn get_registration_blocks_remaining(
+        &self,
+        height: u64,
+        warning_epochs: u64,
+    ) -> anyhow::Result<Option<u64>> {
+        let Some(info) = read_registration_file(self.node_registration_file.clone()).await? else {
+            return Ok(None);
+        };
+
+        let active_keys = to_vn_public_keys(self.get_active_validator_nodes_at(height).await?);
+        if !contains_key(active_keys, info.public_key.clone()) {
+            return Ok(None);
+        }
+
+        let constants = self.get_consensus_constants(height).await?;
+        let Some(expiry_height) = self
+            .get_registration_expiry_height(&info.public_key, height, &constants)
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        Ok(blocks_remaining_within_warning(
+            expiry_height,
+            height,
+            constants.epoch_length,
+            warning_epochs,
+        ))
+    }
+
     pub async fn register_validator_node(&self) -> anyhow::Result<RegisterValidatorNodeResponse> {
         if !self.bootstrapped {
             bail!("Node client not connected");
@@ -192,6 +304,40 @@ impl MinotariNodes {
         Ok(resp)
     }
 
+    // Submits the registration transaction, retrying with a fixed delay if the wallet could not be reached. Only errors
+    // that show the wallet did not process the request are retried, because submitting a registration that the wallet
+    // already accepted again would lock a second deposit. Before each retry, the registration is checked again at the
+    // given height and None is returned if it is already registered and valid. The error of the last attempt is
+    // returned once all attempts are exhausted.
+    pub async fn register_validator_node_with_retry(
+        &self,
+        height: u64,
+        retry: &RetryConfig,
+    ) -> anyhow::Result<Option<RegisterValidatorNodeResponse>> {
+        let attempts = retry.attempts.max(1);
+        let mut attempt = 1;
+        loop {
+            match self.register_validator_node().await {
+                Ok(resp) => return Ok(Some(resp)),
+                Err(e) if attempt < attempts && is_unprocessed_request_error(&e) => {
+                    warn!(
+                        "Registration attempt {}/{} failed: {}. Retrying in {}s",
+                        attempt, attempts, e, retry.delay_secs
+                    );
+                    time::sleep(retry.delay()).await;
+                    if self.is_registered_and_valid(height).await? {
+                        info!(
+                            "Validator node is already registered and not close to expiry, not retrying registration"
+                        );
+                        return Ok(None);
+                    }
+                    attempt += 1;
+                },
+                Err(e) => return Err(e.context(format!("registration failed after {} attempt(s)", attempt))),
+            }
+        }
+    }
+
     pub async fn get_consensus_constants(&self, block_height: u64) -> anyhow::Result<grpc::ConsensusConstants> {
         if !self.bootstrapped {
             bail!("Node client not connected");
@@ -208,3 +354,9 @@ impl MinotariNodes {
         Ok(constants)
     }
 }
+
+// Returns true if the error shows that the request never reached the wallet, so that it is safe to submit it again
+fn is_unprocessed_request_error(err: &anyhow::Error) -> bool {
+    err.downcast_ref::<tonic::Status>()
+        .is_some_and(|status| status.code() == tonic::Code::Unavailable)
+}
@@ -1,17 +1,45 @@
 // Copyright 2024 The Tari Project
 // SPDX-License-Identifier: BSD-3-Clause
 
+use std::{
+    collections::VecDeque,
+    io::{self, SeekFrom},
+    path::{Path, PathBuf},
+    process::{ExitStatus, Stdio},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Instant, SystemTime},
+};
+
+use anyhow::{bail, Context};
 use log::*;
 use minotari_app_grpc::tari_rpc::RegisterValidatorNodeResponse;
+use serde::Deserialize;
+use serde_json::json;
 use tokio::{
-    process::Child,
-    sync::mpsc,
-    time::{sleep, Duration},
+    fs,
+    io::{AsyncReadExt, AsyncSeekExt},
+    process::{Child, Command as TokioCommand},
+    sync::{
+        broadcast::{self, error::RecvError},
+        mpsc,
+    },
+    time::{self, sleep, Duration},
 };
+use url::Url;
 
 use crate::{
     alerting::{Alerting, MatterMostNotifier, TelegramNotifier},
-    config::Channels,
+    config::{AlertHistoryConfig, AlertTemplates, ChannelConfig, Channels, CrashEscalationConfig, LivenessProbeConfig},
+    constants::{
+        DEFAULT_RESTART_GRACE_PERIOD_SECS,
+        DEFAULT_RESTART_HOOK_TIMEOUT_SECS,
+        DEFAULT_STDERR_TAIL_LINES,
+        DEFAULT_STDERR_TAIL_MAX_BYTES,
+    },
+    history::AlertHistory,
 };
 
 #[derive(Copy, Clone, Debug)]
@@ -27,136 +55,586 @@ impl Transaction {
             block,
         }
     }
+
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    pub fn block(&self) -> u64 {
+        self.block
+    }
 }
 
 #[derive(Clone, Debug)]
 pub enum ProcessStatus {
     Running,
-    Exited(i32), // status code
-    Crashed,
+    Exited(i32, String), // status code, last lines of stderr
+    Crashed(String),     // last lines of stderr
+    Unresponsive(u64),   // seconds since the node last responded
+    OomKilled,
+    Killed(String), // last lines of stderr, killed with SIGKILL without evidence of an OOM kill
+    Restarted,
+    RestartHookFailed(&'static str, String), // hook name, error
     InternalError(String),
     Submitted(Transaction),
+    AlreadyRegistered(u64), // block at which the registration was skipped
+    RegistrationExpiringSoon { blocks_remaining: u64 },
+}
+
+// A process status along with the time at which it was observed, so that events that queue up (e.g. during the restart
+// pause) are still reported with the time they actually occurred
+#[derive(Clone, Debug)]
+pub struct ProcessStatusEvent {
+    pub status: ProcessStatus,
+    pub observed_at: SystemTime,
+}
+
+impl ProcessStatusEvent {
+    pub fn new(status: ProcessStatus) -> Self {
+        Self {
+            status,
+            observed_at: SystemTime::now(),
+        }
+    }
+
+    fn observed_at_str(&self) -> String {
+        humantime::format_rfc3339_seconds(self.observed_at).to_string()
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AlertChannel {
+    Mattermost,
+    Telegram,
+}
+
+// Commands to change which notifiers are invoked while the watcher is running, sent by operators through the status
+// server
+#[derive(Copy, Clone, Debug)]
+pub enum AlertCommand {
+    MuteChannel(AlertChannel),
+    UnmuteChannel(AlertChannel),
+}
+
+// Commands to control the running validator node process, sent by operators through the status server
+#[derive(Copy, Clone, Debug)]
+pub enum ChildCommand {
+    // Gracefully stops the validator node and starts it again, regardless of whether auto restart is enabled
+    Restart,
+}
+
+// Sent by the child monitor once the validator node process has stopped
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ChildStopped {
+    Exited,
+    RestartRequested,
+}
+
+// Probes the validator node at the configured interval and returns how long it has been unresponsive once no probe
+// has succeeded within the timeout. The node is given the timeout to start up before it is considered unresponsive.
+// Never returns if no probe is configured.
+async fn wait_until_unresponsive(cfg: Option<&LivenessProbeConfig>, client: &reqwest::Client) -> Duration {
+    let Some(cfg) = cfg else {
+        return std::future::pending().await;
+    };
+    let mut last_response = Instant::now();
+    let mut interval = time::interval(cfg.interval());
+    interval.set_missed_tick_behavior(time::MissedTickBehavior::Delay);
+    loop {
+        interval.tick().await;
+        match probe_validator_node(cfg, client).await {
+            Ok(()) => last_response = Instant::now(),
+            Err(e) => {
+                debug!("Validator node liveness probe failed: {:#}", e);
+                if last_response.elapsed() >= cfg.timeout() {
+                    return last_response.elapsed();
+                }
+            },
+        }
+    }
+}
+
+async fn probe_validator_node(cfg: &LivenessProbeConfig, client: &reqwest::Client) -> anyhow::Result<()> {
+    let req = json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "get_identity",
+        "params": {},
+    });
+    let resp = client
+        .post(cfg.json_rpc_url.clone())
+        .json(&req)
+        .timeout(cfg.interval())
+        .send()
+        .await?;
+    if !resp.status().is_success() {
+        bail!("Validator node responded with status {}", resp.status());
+    }
+    Ok(())
+}
+
+// Runs an operator configured restart hook with the shell. The hook is killed if it does not complete within the
+// timeout.
+async fn run_restart_hook(name: &str, cmd: &str, timeout: Duration) -> anyhow::Result<()> {
+    info!("Running {} hook: {}", name, cmd);
+    let mut hook = TokioCommand::new("sh")
+        .arg("-c")
+        .arg(cmd)
+        .stdin(Stdio::null())
+        .kill_on_drop(true)
+        .spawn()
+        .with_context(|| format!("Failed to spawn {} hook", name))?;
+    let status = match time::timeout(timeout, hook.wait()).await {
+        Ok(status) => status.with_context(|| format!("Failed to wait for {} hook", name))?,
+        Err(_) => bail!("{} hook did not complete within {}s", name, timeout.as_secs()),
+    };
+    info!("{} hook exited with status: {}", name, status);
+    if !status.success() {
+        bail!("{} hook exited with status: {}", name, status);
+    }
+    Ok(())
+}
+
+// Sends the event to the logging task without waiting. If the logging task falls behind, the oldest events in the
+// channel are dropped, so that a slow log consumer cannot stall the monitor.
+pub fn send_to_logging(tx_logging: &broadcast::Sender<ProcessStatusEvent>, event: ProcessStatusEvent) {
+    if tx_logging.send(event).is_err() {
+        warn!("Logging task has exited, dropping status event");
+    }
+}
+
+// Runs the restart hook, if configured, and reports a failure. The restart proceeds regardless of the outcome.
+pub async fn run_restart_hook_and_report(
+    name: &'static str,
+    cmd: Option<&str>,
+    tx_logging: &broadcast::Sender<ProcessStatusEvent>,
+    tx_alerting: &mpsc::Sender<ProcessStatusEvent>,
+) {
+    let Some(cmd) = cmd else {
+        return;
+    };
+    let timeout = Duration::from_secs(DEFAULT_RESTART_HOOK_TIMEOUT_SECS);
+    if let Err(err) = run_restart_hook(name, cmd, timeout).await {
+        let event = ProcessStatusEvent::new(ProcessStatus::RestartHookFailed(name, format!("{:#}", err)));
+        send_to_logging(tx_logging, event.clone());
+        tx_alerting
+            .send(event)
+            .await
+            .expect("Failed to send restart hook failure to alerting");
+    }
+}
+
+// The file the validator node's stderr is appended to, and its length when the process was spawned, so that only the
+// lines written by that process are included in its exit and crash alerts
+#[derive(Debug, Clone)]
+pub struct StderrLog {
+    pub path: PathBuf,
+    pub start_offset: u64,
+}
+
+// Reads back the last `max_lines` lines the process wrote to its stderr log. At most the last
+// `DEFAULT_STDERR_TAIL_MAX_BYTES` are read, in which case the first, possibly partial, line is dropped.
+async fn read_stderr_tail(log: &StderrLog, max_lines: usize) -> io::Result<String> {
+    let mut file = fs::File::open(&log.path).await?;
+    let len = file.metadata().await?.len();
+    let offset = log.start_offset.max(len.saturating_sub(DEFAULT_STDERR_TAIL_MAX_BYTES));
+    file.seek(SeekFrom::Start(offset)).await?;
+    let mut buf = Vec::new();
+    file.read_to_end(&mut buf).await?;
+    let content = String::from_utf8_lossy(&buf);
+    let content = if offset > log.start_offset {
+        content.split_once('\n').map_or("", |(_, rest)| rest)
+    } else {
+        &*content
+    };
+    Ok(last_lines(content, max_lines))
 }
 
+fn last_lines(content: &str, max_lines: usize) -> String {
+    let lines = content.lines().collect::<Vec<_>>();
+    lines[lines.len().saturating_sub(max_lines)..].join("\n")
+}
+
+#[allow(clippy::too_many_lines)]
 pub async fn monitor_child(
     mut child: Child,
-    tx_logging: mpsc::Sender<ProcessStatus>,
-    tx_alerting: mpsc::Sender<ProcessStatus>,
-    tx_restart: mpsc::Sender<()>,
+    stderr_log: StderrLog,
+    tx_logging: broadcast::Sender<ProcessStatusEvent>,
+    tx_alerting: mpsc::Sender<ProcessStatusEvent>,
+    tx_restart: mpsc::Sender<ChildStopped>,
+    mut rx_command: broadcast::Receiver<ChildCommand>,
+    liveness: Option<LivenessProbeConfig>,
+    memory_events_path: Option<PathBuf>,
+    pre_restart_cmd: Option<String>,
 ) {
     // process is still running
-    tx_logging
-        .send(ProcessStatus::Running)
-        .await
-        .expect("Failed to send process running status to logging");
+    let event = ProcessStatusEvent::new(ProcessStatus::Running);
+    send_to_logging(&tx_logging, event.clone());
     tx_alerting
-        .send(ProcessStatus::Running)
+        .send(event)
         .await
         .expect("Failed to send process running status to alerting");
-    let exit = child.wait().await;
+
+    let oom_kills_before = match &memory_events_path {
+        Some(path) => read_oom_kill_count(path).await,
+        None => None,
+    };
+    // a SIGKILL sent by the watcher is not an OOM kill
+    let mut killed_by_watcher = false;
+
+    let mut restart_requested = false;
+
+    let client = reqwest::Client::new();
+    let exit = loop {
+        tokio::select! {
+            exit = child.wait() => break exit,
+            Ok(ChildCommand::Restart) = rx_command.recv() => {
+                info!("Received restart command, stopping child process");
+                restart_requested = true;
+                run_restart_hook_and_report("pre-restart", pre_restart_cmd.as_deref(), &tx_logging, &tx_alerting).await;
+                break terminate_child(&mut child, Duration::from_secs(DEFAULT_RESTART_GRACE_PERIOD_SECS)).await;
+            },
+            unresponsive_for = wait_until_unresponsive(liveness.as_ref(), &client) => {
+                warn!("Child process is UNRESPONSIVE for {}s", unresponsive_for.as_secs());
+                let event = ProcessStatusEvent::new(ProcessStatus::Unresponsive(unresponsive_for.as_secs()));
+                send_to_logging(&tx_logging, event.clone());
+                tx_alerting
+                    .send(event)
+                    .await
+                    .expect("Failed to send unresponsive status to alerting");
+                if liveness.as_ref().is_some_and(|probe| probe.restart) {
+                    // the exit is then reported and handled like any other crash
+                    warn!("Killing unresponsive child process");
+                    killed_by_watcher = true;
+                    run_restart_hook_and_report("pre-restart", pre_restart_cmd.as_deref(), &tx_logging, &tx_alerting).await;
+                    if let Err(err) = child.kill().await {
+                        error!("Failed to kill unresponsive child process: {}", err);
+                    }
+                    break child.wait().await;
+                }
+                // otherwise keep probing, and alert again if the node stays unresponsive for another timeout
+            },
+        }
+    };
+
+    let stderr_tail = match read_stderr_tail(&stderr_log, DEFAULT_STDERR_TAIL_LINES).await {
+        Ok(tail) => tail,
+        Err(err) => {
+            warn!(
+                "Failed to read validator node stderr log at {}: {}",
+                stderr_log.path.display(),
+                err
+            );
+            String::new()
+        },
+    };
+
+    // in each case below the restart is signalled before the alert is sent, so that a slow alerting channel (e.g. a
+    // slow Telegram API) cannot delay restarting the validator node
+    if restart_requested {
+        // a requested restart is reported as such regardless of how the process exited
+        match &exit {
+            Ok(status) => info!("Child process stopped for restart with status: {}", status),
+            Err(err) => warn!("Child process encountered an error while stopping for restart: {}", err),
+        }
+        let event = ProcessStatusEvent::new(ProcessStatus::Restarted);
+        send_to_logging(&tx_logging, event.clone());
+        tx_restart
+            .send(ChildStopped::RestartRequested)
+            .await
+            .expect("Failed to send restart node signal");
+        tx_alerting
+            .send(event)
+            .await
+            .expect("Failed to send restarted status to alerting");
+        return;
+    }
 
     match exit {
         Ok(status) => {
             if status.success() {
                 info!("Child process exited with status: {}", status);
-                tx_logging
-                    .send(ProcessStatus::Exited(status.code().unwrap_or(0)))
+                let event = ProcessStatusEvent::new(ProcessStatus::Exited(status.code().unwrap_or(0), stderr_tail));
+                send_to_logging(&tx_logging, event.clone());
+                tx_restart
+                    .send(ChildStopped::Exited)
                     .await
-                    .expect("Failed to send process exit status to logging");
+                    .expect("Failed to send restart node signal");
                 tx_alerting
-                    .send(ProcessStatus::Exited(status.code().unwrap_or(0)))
+                    .send(event)
                     .await
                     .expect("Failed to send process exit status to alerting");
-                tx_restart.send(()).await.expect("Failed to send restart node signal");
+            } else if !killed_by_watcher && was_sigkilled(&status) {
+                let oom_kills_after = match &memory_events_path {
+                    Some(path) => read_oom_kill_count(path).await,
+                    None => None,
+                };
+                let event = if is_oom_kill(oom_kills_before, oom_kills_after) {
+                    error!("Child process was OOM KILLED with status: {}", status);
+                    ProcessStatusEvent::new(ProcessStatus::OomKilled)
+                } else {
+                    error!("Child process was KILLED by an unknown source with status: {}", status);
+                    ProcessStatusEvent::new(ProcessStatus::Killed(stderr_tail))
+                };
+                send_to_logging(&tx_logging, event.clone());
+                tx_restart
+                    .send(ChildStopped::Exited)
+                    .await
+                    .expect("Failed to send restart node signal");
+                tx_alerting
+                    .send(event)
+                    .await
+                    .expect("Failed to send status to alerting");
             } else {
                 warn!("Child process CRASHED with status: {}", status);
-                tx_logging
-                    .send(ProcessStatus::Crashed)
+                let event = ProcessStatusEvent::new(ProcessStatus::Crashed(stderr_tail));
+                send_to_logging(&tx_logging, event.clone());
+                tx_restart
+                    .send(ChildStopped::Exited)
                     .await
-                    .expect("Failed to send status to logging");
+                    .expect("Failed to send restart node signal");
                 tx_alerting
-                    .send(ProcessStatus::Crashed)
+                    .send(event)
                     .await
                     .expect("Failed to send status to alerting");
-                tx_restart.send(()).await.expect("Failed to send restart node signal");
             }
         },
         // if the child process encountered an unexpected error, not related to the process itself
         Err(err) => {
             error!("Child process encountered an error: {}", err);
-            let err_msg = err.to_string();
-            tx_logging
-                .send(ProcessStatus::InternalError(err_msg.clone()))
+            let event = ProcessStatusEvent::new(ProcessStatus::InternalError(err.to_string()));
+            send_to_logging(&tx_logging, event.clone());
+            tx_restart
+                .send(ChildStopped::Exited)
                 .await
-                .expect("Failed to send internal error status to logging");
+                .expect("Failed to send restart node signal");
             tx_alerting
-                .send(ProcessStatus::InternalError(err_msg))
+                .send(event)
                 .await
                 .expect("Failed to send internal error status to alerting");
-            tx_restart.send(()).await.expect("Failed to send restart node signal");
         },
     }
 }
 
-pub async fn process_status_log(mut rx: mpsc::Receiver<ProcessStatus>) {
+// Sends SIGTERM to the child process and waits for it to exit, killing it if it has not exited within the grace period
+async fn terminate_child(child: &mut Child, grace_period: Duration) -> io::Result<ExitStatus> {
+    if let Some(pid) = child.id() {
+        let status = TokioCommand::new("kill")
+            .arg("-TERM")
+            .arg(pid.to_string())
+            .status()
+            .await;
+        if status.map(|s| !s.success()).unwrap_or(true) {
+            warn!("Failed to send SIGTERM to child process {}", pid);
+        }
+    }
+    match time::timeout(grace_period, child.wait()).await {
+        Ok(exit) => exit,
+        Err(_) => {
+            warn!(
+                "Child process did not exit within {}s of SIGTERM, killing it",
+                grace_period.as_secs()
+            );
+            child.kill().await?;
+            child.wait().await
+        },
+    }
+}
+
+// Reads the `oom_kill` count from a cgroup v2 memory.events file
+async fn read_oom_kill_count(path: &Path) -> Option<u64> {
+    match tokio::fs::read_to_string(path).await {
+        Ok(contents) => contents
+            .lines()
+            .find_map(|line| line.strip_prefix("oom_kill "))
+            .and_then(|count| count.trim().parse().ok()),
+        Err(err) => {
+            warn!("Failed to read cgroup memory events from {}: {}", path.display(), err);
+            None
+        },
+    }
+}
+
+// The kernel OOM killer terminates the process with SIGKILL, but so may an operator or another supervisor
+#[cfg(target_os = "linux")]
+fn was_sigkilled(status: &ExitStatus) -> bool {
+    use std::os::unix::process::ExitStatusExt;
+
+    const SIGKILL: i32 = 9;
+    status.signal() == Some(SIGKILL)
+}
+
+// Exit signals are only inspected on Linux
+#[cfg(not(target_os = "linux"))]
+fn was_sigkilled(_status: &ExitStatus) -> bool {
+    false
+}
+
+// A SIGKILL is only attributed to the OOM killer if the `oom_kill` count of the cgroup increased while the process was
+// running. Without a readable count there is no evidence either way.
+fn is_oom_kill(oom_kills_before: Option<u64>, oom_kills_after: Option<u64>) -> bool {
+    match (oom_kills_before, oom_kills_after) {
+        (Some(before), Some(after)) => after > before,
+        _ => false,
+    }
+}
+
+pub async fn process_status_log(mut rx: broadcast::Receiver<ProcessStatusEvent>) {
     loop {
-        if let Some(status) = rx.recv().await {
-            match status {
-                ProcessStatus::Exited(code) => {
-                    error!("Validator node process exited with code {}", code);
-                    info!("Pauses process logging for 5 seconds to allow the validator node to restart");
-                    sleep(Duration::from_secs(5)).await;
-                },
-                ProcessStatus::InternalError(err) => {
-                    error!("Validator node process exited with error: {}", err);
-                    info!("Pausing process logging 5 seconds to allow the validator node to restart");
-                    sleep(Duration::from_secs(5)).await;
-                },
-                ProcessStatus::Crashed => {
-                    error!("Validator node process crashed");
-                    info!("Pausing process logging for 5 seconds to allow the validator node to restart");
-                    sleep(Duration::from_secs(5)).await;
-                },
-                ProcessStatus::Running => {
-                    // all good, process is still running
-                },
-                ProcessStatus::Submitted(tx) => {
-                    info!(
-                        "Validator node registration submitted (tx: {}, block: {})",
-                        tx.id, tx.block
-                    );
-                },
-            }
+        let event = match rx.recv().await {
+            Ok(event) => event,
+            Err(RecvError::Lagged(count)) => {
+                warn!("Logging fell behind, the oldest {} status events were dropped", count);
+                continue;
+            },
+            Err(RecvError::Closed) => break,
+        };
+        let observed_at = event.observed_at_str();
+        match event.status {
+            ProcessStatus::Exited(code, _) => {
+                error!("Validator node process exited with code {} at {}", code, observed_at);
+                info!("Pauses process logging for 5 seconds to allow the validator node to restart");
+                sleep(Duration::from_secs(5)).await;
+            },
+            ProcessStatus::InternalError(err) => {
+                error!("Validator node process exited with error at {}: {}", observed_at, err);
+                info!("Pausing process logging 5 seconds to allow the validator node to restart");
+                sleep(Duration::from_secs(5)).await;
+            },
+            ProcessStatus::Crashed(_) => {
+                error!("Validator node process crashed at {}", observed_at);
+                info!("Pausing process logging for 5 seconds to allow the validator node to restart");
+                sleep(Duration::from_secs(5)).await;
+            },
+            ProcessStatus::OomKilled => {
+                error!("Validator node process was killed by the OOM killer at {}", observed_at);
+                info!("Pausing process logging for 5 seconds to allow the validator node to restart");
+                sleep(Duration::from_secs(5)).await;
+            },
+            ProcessStatus::Killed(_) => {
+                error!(
+                    "Validator node process was killed by an unknown source at {}",
+                    observed_at
+                );
+                info!("Pausing process logging for 5 seconds to allow the validator node to restart");
+                sleep(Duration::from_secs(5)).await;
+            },
+            ProcessStatus::Restarted => {
+                info!("Validator node process was restarted on request at {}", observed_at);
+            },
+            ProcessStatus::RestartHookFailed(hook, err) => {
+                error!("Validator node {} hook failed at {}: {}", hook, observed_at, err);
+            },
+            ProcessStatus::Unresponsive(secs) => {
+                error!(
+                    "Validator node process has been unresponsive for {}s at {}",
+                    secs, observed_at
+                );
+            },
+            ProcessStatus::Running => {
+                // all good, process is still running
+            },
+            ProcessStatus::Submitted(tx) => {
+                info!(
+                    "Validator node registration submitted at {} (tx: {}, block: {})",
+                    observed_at,
+                    tx.id(),
+                    tx.block()
+                );
+            },
+            ProcessStatus::AlreadyRegistered(block) => {
+                info!(
+                    "Validator node registration is still valid, skipped submission at {} (block: {})",
+                    observed_at, block
+                );
+            },
+            ProcessStatus::RegistrationExpiringSoon { blocks_remaining } => {
+                warn!(
+                    "Validator node registration expires in {} blocks at {}",
+                    blocks_remaining, observed_at
+                );
+            },
         }
     }
 }
 
+// Appends the last lines the validator node wrote to stderr, if any, to an alert message
+fn with_stderr_tail(message: String, stderr_tail: &str) -> String {
+    if stderr_tail.is_empty() {
+        return message;
+    }
+    format!("{}\nLast lines of stderr:\n{}", message, stderr_tail)
+}
+
+// Replaces the `{name}` placeholders in the template with their values
+fn render_template(template: &str, node_name: &str, values: &[(&str, String)]) -> String {
+    let mut message = template.replace("{node_name}", node_name);
+    for (name, value) in values {
+        message = message.replace(&format!("{{{}}}", name), value);
+    }
+    message
+}
+
+// Checks the Mattermost configuration up front so that a typo disables the channel instead of failing every alert
+fn validate_mattermost_config(cfg: &ChannelConfig) -> anyhow::Result<()> {
+    let url = Url::parse(&cfg.server_url).with_context(|| format!("Invalid server URL '{}'", cfg.server_url))?;
+    if !matches!(url.scheme(), "http" | "https") {
+        bail!("Server URL '{}' must use http or https", cfg.server_url);
+    }
+    if cfg.credentials.is_empty() {
+        bail!("Credentials field is empty");
+    }
+    if cfg.channel_id.is_empty() {
+        bail!("Channel ID is empty");
+    }
+    Ok(())
+}
+
+fn validate_telegram_config(cfg: &ChannelConfig) -> anyhow::Result<()> {
+    if cfg.credentials.is_empty() {
+        bail!("Bot token (credentials) is empty");
+    }
+    if cfg.channel_id.is_empty() {
+        bail!("Chat ID is empty");
+    }
+    Ok(())
+}
+
 fn setup_alerting_clients(cfg: Channels) -> (Option<MatterMostNotifier>, Option<TelegramNotifier>) {
     let mut mattermost: Option<MatterMostNotifier> = None;
     if cfg.mattermost.enabled {
-        let cfg = cfg.mattermost.clone();
-        info!("Mattermost alerting enabled");
-        mattermost = Some(MatterMostNotifier {
-            server_url: cfg.server_url,
-            channel_id: cfg.channel_id,
-            credentials: cfg.credentials,
-            alerts_sent: 0,
-            client: reqwest::Client::new(),
-        });
+        if let Err(e) = validate_mattermost_config(&cfg.mattermost) {
+            warn!("Mattermost alerting disabled due to invalid configuration: {:#}", e);
+        } else {
+            let cfg = cfg.mattermost.clone();
+            info!("Mattermost alerting enabled");
+            mattermost = Some(MatterMostNotifier {
+                server_url: cfg.server_url,
+                channel_id: cfg.channel_id,
+                credentials: cfg.credentials,
+                alerts_sent: 0,
+                client: reqwest::Client::new(),
+            });
+        }
     } else {
         info!("Mattermost alerting disabled");
     }
 
     let mut telegram: Option<TelegramNotifier> = None;
     if cfg.telegram.enabled {
-        let cfg = cfg.telegram.clone();
-        info!("Telegram alerting enabled");
-        telegram = Some(TelegramNotifier {
-            bot_token: cfg.credentials,
-            chat_id: cfg.channel_id,
-            alerts_sent: 0,
-            client: reqwest::Client::new(),
-        });
+        if let Err(e) = validate_telegram_config(&cfg.telegram) {
+            warn!("Telegram alerting disabled due to invalid configuration: {:#}", e);
+        } else {
+            let cfg = cfg.telegram.clone();
+            info!("Telegram alerting enabled");
+            telegram = Some(TelegramNotifier {
+                bot_token: cfg.credentials,
+                chat_id: cfg.channel_id,
+                alerts_sent: 0,
+                client: reqwest::Client::new(),
+            });
+        }
     } else {
         info!("Telegram alerting disabled");
     }
@@ -164,80 +642,263 @@ fn setup_alerting_clients(cfg: Channels) -> (Option<MatterMostNotifier>, Option<
     (mattermost, telegram)
 }
 
-pub async fn process_status_alert(mut rx: mpsc::Receiver<ProcessStatus>, cfg: Channels) {
+// Records a crash and returns the number of crashes in the escalation window if it reached the threshold. The window
+// is cleared on escalation so that the crash loop alert is sent once per `threshold` crashes rather than every crash.
+fn record_crash(
+    recent_crashes: &mut VecDeque<SystemTime>,
+    observed_at: SystemTime,
+    escalation: &CrashEscalationConfig,
+) -> Option<usize> {
+    if escalation.threshold == 0 {
+        return None;
+    }
+    while recent_crashes.front().is_some_and(|t| {
+        observed_at
+            .duration_since(*t)
+            .is_ok_and(|age| age > escalation.window())
+    }) {
+        recent_crashes.pop_front();
+    }
+    recent_crashes.push_back(observed_at);
+
+    if recent_crashes.len() < escalation.threshold {
+        return None;
+    }
+    let count = recent_crashes.len();
+    recent_crashes.clear();
+    Some(count)
+}
+
+pub async fn process_status_alert(
+    mut rx: mpsc::Receiver<ProcessStatusEvent>,
+    mut rx_control: mpsc::Receiver<AlertCommand>,
+    cfg: Channels,
+    history_cfg: Option<AlertHistoryConfig>,
+    templates: AlertTemplates,
+    escalation: CrashEscalationConfig,
+    auto_restart: Arc<AtomicBool>,
+) {
     let (mut mattermost, mut telegram) = setup_alerting_clients(cfg);
+    let history = history_cfg.map(AlertHistory::new);
+    let mut mattermost_muted = false;
+    let mut telegram_muted = false;
+    // times of the crashes within the escalation window, oldest first
+    let mut recent_crashes = VecDeque::new();
 
     loop {
-        while let Some(status) = rx.recv().await {
-            match status {
-                ProcessStatus::Exited(code) => {
-                    if let Some(mm) = &mut mattermost {
-                        mm.alert(&format!("Validator node process exited with code {}", code))
-                            .await
-                            .expect("Failed to send alert to MatterMost");
-                    }
-                    if let Some(tg) = &mut telegram {
-                        tg.alert(&format!("Validator node process exited with code {}", code))
-                            .await
-                            .expect("Failed to send alert to Telegram");
-                    }
-                },
-                ProcessStatus::InternalError(err) => {
-                    if let Some(mm) = &mut mattermost {
-                        mm.alert(&format!("Validator node process internal error: {}", err))
-                            .await
-                            .expect("Failed to send alert to MatterMost");
-                    }
-                    if let Some(tg) = &mut telegram {
-                        tg.alert(&format!("Validator node process internal error: {}", err))
-                            .await
-                            .expect("Failed to send alert to Telegram");
-                    }
-                },
-                ProcessStatus::Crashed => {
-                    if let Some(mm) = &mut mattermost {
-                        mm.alert("Validator node process crashed")
-                            .await
-                            .expect("Failed to send alert to MatterMost");
-                    }
-                    if let Some(tg) = &mut telegram {
-                        tg.alert("Validator node process crashed")
-                            .await
-                            .expect("Failed to send alert to Telegram");
+        tokio::select! {
+            Some(cmd) = rx_control.recv() => {
+                let (channel, muted) = match cmd {
+                    AlertCommand::MuteChannel(channel) => (channel, true),
+                    AlertCommand::UnmuteChannel(channel) => (channel, false),
+                };
+                match channel {
+                    AlertChannel::Mattermost => mattermost_muted = muted,
+                    AlertChannel::Telegram => telegram_muted = muted,
+                }
+                info!("{:?} alerting {}", channel, if muted { "muted" } else { "unmuted" });
+            },
+            Some(event) = rx.recv() => {
+                let observed_at = event.observed_at_str();
+                let node_name = templates.node_name.as_str();
+                let is_crash = matches!(
+                    event.status,
+                    ProcessStatus::Crashed(_) | ProcessStatus::OomKilled | ProcessStatus::Killed(_)
+                );
+                let (name, message) = match event.status {
+                    ProcessStatus::Exited(code, stderr_tail) => (
+                        "exited",
+                        with_stderr_tail(
+                            render_template(&templates.exited, node_name, &[("code", code.to_string())]),
+                            &stderr_tail,
+                        ),
+                    ),
+                    ProcessStatus::InternalError(err) => (
+                        "internal_error",
+                        render_template(&templates.internal_error, node_name, &[("error", err)]),
+                    ),
+                    ProcessStatus::Crashed(stderr_tail) => (
+                        "crashed",
+                        with_stderr_tail(render_template(&templates.crashed, node_name, &[]), &stderr_tail),
+                    ),
+                    ProcessStatus::OomKilled => ("oom_killed", render_template(&templates.oom_killed, node_name, &[])),
+                    ProcessStatus::Killed(stderr_tail) => (
+                        "killed",
+                        with_stderr_tail(render_template(&templates.killed, node_name, &[]), &stderr_tail),
+                    ),
+                    ProcessStatus::Restarted => ("restarted", render_template(&templates.restarted, node_name, &[])),
+                    ProcessStatus::RestartHookFailed(hook, err) => (
+                        "restart_hook_failed",
+                        render_template(&templates.restart_hook_failed, node_name, &[
+                            ("hook", hook.to_string()),
+                            ("error", err),
+                        ]),
+                    ),
+                    ProcessStatus::Unresponsive(secs) => (
+                        "unresponsive",
+                        render_template(&templates.unresponsive, node_name, &[("unresponsive_secs", secs.to_string())]),
+                    ),
+                    ProcessStatus::Running => {
+                        // all good, process is still running, send heartbeat to channel(s)
+                        if let Some(mm) = &mut mattermost {
+                            if mm.ping().await.is_err() {
+                                warn!("Failed to send heartbeat to MatterMost");
+                            }
+                        }
+                        if let Some(tg) = &mut telegram {
+                            if tg.ping().await.is_err() {
+                                warn!("Failed to send heartbeat to Telegram");
+                            }
+                        }
+                        continue;
+                    },
+                    // not worth alerting on, the registration is still valid
+                    ProcessStatus::AlreadyRegistered(_) => continue,
+                    ProcessStatus::Submitted(tx) => (
+                        "submitted",
+                        render_template(&templates.submitted, node_name, &[
+                            ("tx_id", tx.id().to_string()),
+                            ("block", tx.block().to_string()),
+                        ]),
+                    ),
+                    ProcessStatus::RegistrationExpiringSoon { blocks_remaining } => (
+                        "registration_expiring_soon",
+                        render_template(&templates.registration_expiring_soon, node_name, &[
+                            ("blocks_remaining", blocks_remaining.to_string()),
+                        ]),
+                    ),
+                };
+                let mut alerts = vec![(name, message)];
+                if is_crash {
+                    if let Some(count) = record_crash(&mut recent_crashes, event.observed_at, &escalation) {
+                        error!("Validator node crashed {} times in the last {}s", count, escalation.window_secs);
+                        alerts.push((
+                            "crash_loop",
+                            render_template(&templates.crash_loop, node_name, &[
+                                ("count", count.to_string()),
+                                ("window_secs", escalation.window_secs.to_string()),
+                            ]),
+                        ));
+                        if escalation.stop_auto_restart {
+                            // takes effect the next time the node stops, it may already be restarting after this crash
+                            warn!("Validator node is in a crash loop, disabling auto restart");
+                            auto_restart.store(false, Ordering::SeqCst);
+                        }
                     }
-                },
-                ProcessStatus::Running => {
-                    // all good, process is still running, send heartbeat to channel(s)
+                }
+
+                for (name, message) in alerts {
+                    let message = format!("[{}] {}", observed_at, message);
+
                     if let Some(mm) = &mut mattermost {
-                        if mm.ping().await.is_err() {
-                            warn!("Failed to send heartbeat to MatterMost");
+                        if !mattermost_muted {
+                            mm.alert(&message).await.expect("Failed to send alert to MatterMost");
                         }
                     }
                     if let Some(tg) = &mut telegram {
-                        if tg.ping().await.is_err() {
-                            warn!("Failed to send heartbeat to Telegram");
+                        if !telegram_muted {
+                            tg.alert(&message).await.expect("Failed to send alert to Telegram");
                         }
                     }
-                },
-                ProcessStatus::Submitted(tx) => {
-                    if let Some(mm) = &mut mattermost {
-                        mm.alert(&format!(
-                            "Validator node registration submitted (tx: {}, block: {})",
-                            tx.id, tx.block
-                        ))
-                        .await
-                        .expect("Failed to send alert to MatterMost");
-                    }
-                    if let Some(tg) = &mut telegram {
-                        tg.alert(&format!(
-                            "Validator node registration submitted (tx: {}, block: {})",
-                            tx.id, tx.block
-                        ))
-                        .await
-                        .expect("Failed to send alert to Telegram");
+                    if let Some(history) = &history {
+                        if let Err(e) = history.record(name, &message, event.observed_at).await {
+                            warn!("Failed to record alert in history: {}", e);
+                        }
                     }
-                },
-            }
+                }
+            },
+            else => break,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn escalation(threshold: usize) -> CrashEscalationConfig {
+        CrashEscalationConfig {
+            threshold,
+            window_secs: 60,
+            stop_auto_restart: false,
         }
     }
+
+    #[test]
+    fn it_escalates_once_the_threshold_is_reached_within_the_window() {
+        let escalation = escalation(3);
+        let start = SystemTime::UNIX_EPOCH;
+        let mut recent_crashes = VecDeque::new();
+
+        assert_eq!(record_crash(&mut recent_crashes, start, &escalation), None);
+        assert_eq!(
+            record_crash(&mut recent_crashes, start + Duration::from_secs(10), &escalation),
+            None
+        );
+        assert_eq!(
+            record_crash(&mut recent_crashes, start + Duration::from_secs(20), &escalation),
+            Some(3)
+        );
+        // the window is cleared on escalation, so the next crash starts counting again
+        assert!(recent_crashes.is_empty());
+        assert_eq!(
+            record_crash(&mut recent_crashes, start + Duration::from_secs(30), &escalation),
+            None
+        );
+    }
+
+    #[test]
+    fn it_does_not_count_crashes_outside_the_window() {
+        let escalation = escalation(3);
+        let start = SystemTime::UNIX_EPOCH;
+        let mut recent_crashes = VecDeque::new();
+
+        record_crash(&mut recent_crashes, start, &escalation);
+        record_crash(&mut recent_crashes, start + Duration::from_secs(10), &escalation);
+        // the first crash is older than the window by now
+        assert_eq!(
+            record_crash(&mut recent_crashes, start + Duration::from_secs(61), &escalation),
+            None
+        );
+        assert_eq!(recent_crashes.len(), 2);
+    }
+
+    #[test]
+    fn it_never_escalates_with_a_zero_threshold() {
+        let escalation = escalation(0);
+        let mut recent_crashes = VecDeque::new();
+        for _ in 0..10 {
+            assert_eq!(
+                record_crash(&mut recent_crashes, SystemTime::UNIX_EPOCH, &escalation),
+                None
+            );
+        }
+    }
+
+    #[test]
+    fn it_only_reports_an_oom_kill_with_cgroup_evidence() {
+        assert!(is_oom_kill(Some(1), Some(2)));
+        assert!(!is_oom_kill(Some(1), Some(1)));
+        // no memory.events file configured or readable, so a SIGKILL is not attributed to the OOM killer
+        assert!(!is_oom_kill(None, None));
+        assert!(!is_oom_kill(Some(1), None));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn it_detects_a_sigkill() {
+        use std::os::unix::process::ExitStatusExt;
+
+        // the raw wait status of a process terminated by a signal is the signal number
+        assert!(was_sigkilled(&ExitStatus::from_raw(9)));
+        assert!(!was_sigkilled(&ExitStatus::from_raw(15)));
+        assert!(!was_sigkilled(&ExitStatus::from_raw(0)));
+    }
+
+    #[test]
+    fn it_keeps_the_last_lines() {
+        assert_eq!(last_lines("a\nb\nc\n", 2), "b\nc");
+        assert_eq!(last_lines("a\nb", 5), "a\nb");
+        assert_eq!(last_lines("", 5), "");
+    }
 }
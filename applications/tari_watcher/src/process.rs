@@ -4,6 +4,11 @@
 use std::{
     path::{Path, PathBuf},
     process::Stdio,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::SystemTime,
 };
 
 use anyhow::bail;
@@ -13,15 +18,27 @@ use tokio::{
     fs::{self, OpenOptions},
     io::AsyncWriteExt,
     process::{Child, Command as TokioCommand},
-    sync::mpsc::{self},
+    sync::{
+        broadcast,
+        mpsc::{self},
+        watch,
+    },
     time::sleep,
 };
 use url::Url;
 
 use crate::{
-    config::Channels,
-    constants::DEFAULT_VALIDATOR_PID_PATH,
-    monitoring::{monitor_child, ProcessStatus},
+    config::{Channels, LivenessProbeConfig, StatusChannelsConfig},
+    constants::{DEFAULT_VALIDATOR_PID_PATH, DEFAULT_VALIDATOR_STDERR_LOG_PATH},
+    monitoring::{
+        monitor_child,
+        run_restart_hook_and_report,
+        ChildCommand,
+        ChildStopped,
+        ProcessStatusEvent,
+        StderrLog,
+    },
+    status::ChildInfo,
 };
 
 #[allow(unused)]
@@ -51,34 +68,53 @@ pub async fn clean_stale_pid_file(pid_file_path: PathBuf) -> anyhow::Result<()>
 }
 
 pub struct ChildChannel {
-    pub rx_log: mpsc::Receiver<ProcessStatus>,
-    pub tx_log: mpsc::Sender<ProcessStatus>,
-    pub rx_alert: mpsc::Receiver<ProcessStatus>,
-    pub tx_alert: mpsc::Sender<ProcessStatus>,
+    // Status events are broadcast to logging so that the oldest are dropped if the logging task falls behind
+    pub rx_log: broadcast::Receiver<ProcessStatusEvent>,
+    pub tx_log: broadcast::Sender<ProcessStatusEvent>,
+    pub rx_alert: mpsc::Receiver<ProcessStatusEvent>,
+    pub tx_alert: mpsc::Sender<ProcessStatusEvent>,
     pub cfg_alert: Channels,
+    // Cleared by the alerting task to stop restarting the validator node, e.g. when it is in a crash loop
+    pub auto_restart: Arc<AtomicBool>,
+    // The currently running validator node process, if any
+    pub rx_child: watch::Receiver<Option<ChildInfo>>,
+    // Commands for the currently running validator node process, dropped if no process is running
+    pub tx_command: broadcast::Sender<ChildCommand>,
 }
 
 async fn spawn_validator_node(
     binary_path: PathBuf,
     base_dir: PathBuf,
     minotari_node_grpc_url: &Url,
-) -> anyhow::Result<Child> {
+) -> anyhow::Result<(Child, StderrLog)> {
     debug!("Using VN binary at: {}", binary_path.display());
     debug!("Using VN base dir in directory: {}", base_dir.display());
     // Needed to ensure the base dir exists before we create the pid file
     fs::create_dir_all(&base_dir).await?;
 
+    // stderr is appended to a file rather than piped to the watcher, so that the node is unaffected if the watcher
+    // exits. The child monitor reads the lines written by this process back from the file to include them in alerts.
+    let stderr_path = base_dir.join(DEFAULT_VALIDATOR_STDERR_LOG_PATH);
+    if let Some(parent) = stderr_path.parent() {
+        fs::create_dir_all(parent).await?;
+    }
+    let stderr_file = OpenOptions::new().create(true).append(true).open(&stderr_path).await?;
+    let stderr_log = StderrLog {
+        start_offset: stderr_file.metadata().await?.len(),
+        path: stderr_path,
+    };
+
     let child = TokioCommand::new(binary_path)
         .arg(format!("-b{}", base_dir.display()))
         .arg(format!("--node-grpc={minotari_node_grpc_url}"))
         .stdin(Stdio::null())
-        // TODO: redirect these to a file and optionally stdout
+        // TODO: redirect stdout to a file
         // .stdout(Stdio::null())
-        // .stderr(Stdio::null())
+        .stderr(Stdio::from(stderr_file.into_std().await))
         .kill_on_drop(false)
         .spawn()?;
 
-    Ok(child)
+    Ok((child, stderr_log))
 }
 
 pub async fn spawn_validator_node_os(
@@ -87,24 +123,40 @@ pub async fn spawn_validator_node_os(
     cfg_alert: Channels,
     auto_restart: bool,
     minotari_node_grpc_url: Url,
+    liveness: Option<LivenessProbeConfig>,
+    memory_events_path: Option<PathBuf>,
+    pre_restart_cmd: Option<String>,
+    post_restart_cmd: Option<String>,
+    status_channels: StatusChannelsConfig,
     mut trigger_signal: Shutdown,
 ) -> anyhow::Result<ChildChannel> {
-    let (tx_log, rx_log) = mpsc::channel(16);
-    let (tx_alert, rx_alert) = mpsc::channel(16);
+    let (tx_log, rx_log) = broadcast::channel(status_channels.log_capacity.max(1));
+    let (tx_alert, rx_alert) = mpsc::channel(status_channels.alert_capacity.max(1));
     let (tx_restart, mut rx_restart) = mpsc::channel(1);
+    let auto_restart = Arc::new(AtomicBool::new(auto_restart));
+    let (tx_child, rx_child) = watch::channel(None);
+    let (tx_command, _) = broadcast::channel(1);
 
+    let auto_restart_main = auto_restart.clone();
     let tx_log_clone_main = tx_log.clone();
     let tx_alert_clone_main = tx_alert.clone();
     let tx_restart_clone_main = tx_restart.clone();
+    let tx_command_main = tx_command.clone();
     tokio::spawn(async move {
+        let mut restarts = 0;
         loop {
             let child_res =
                 spawn_validator_node(binary_path.clone(), vn_base_dir.clone(), &minotari_node_grpc_url).await;
 
             match child_res {
-                Ok(child) => {
+                Ok((child, stderr_log)) => {
                     let pid = child.id().unwrap_or(0);
                     info!("Spawned validator child process with id {}", pid);
+                    tx_child.send_replace(Some(ChildInfo {
+                        pid,
+                        started_at: SystemTime::now(),
+                        restarts,
+                    }));
 
                     // TODO: the VN should create a PID file in its base dir
                     let path = vn_base_dir.join(DEFAULT_VALIDATOR_PID_PATH);
@@ -118,10 +170,25 @@ pub async fn spawn_validator_node_os(
                     // spawn monitoring and handle logs and alerts
                     tokio::spawn(monitor_child(
                         child,
+                        stderr_log,
                         tx_log_monitor,
                         tx_alert_monitor,
                         tx_restart_monitor,
+                        tx_command_main.subscribe(),
+                        liveness.clone(),
+                        memory_events_path.clone(),
+                        pre_restart_cmd.clone(),
                     ));
+
+                    if restarts > 0 {
+                        run_restart_hook_and_report(
+                            "post-restart",
+                            post_restart_cmd.as_deref(),
+                            &tx_log_clone_main,
+                            &tx_alert_clone_main,
+                        )
+                        .await;
+                    }
                 },
                 Err(e) => {
                     error!("Failed to spawn child process: {}. Retrying in 5s", e);
@@ -131,15 +198,22 @@ pub async fn spawn_validator_node_os(
             }
 
             // block channel until we receive a restart signal
-            match rx_restart.recv().await {
-                Some(_) => {
-                    if !auto_restart {
+            let stopped = rx_restart.recv().await;
+            tx_child.send_replace(None);
+            match stopped {
+                Some(ChildStopped::RestartRequested) => {
+                    info!("Restarting VN process on request");
+                    restarts += 1;
+                },
+                Some(ChildStopped::Exited) => {
+                    if !auto_restart_main.load(Ordering::SeqCst) {
                         info!("Received restart signal, but auto restart is disabled, exiting");
                         trigger_signal.trigger();
                         break;
                     }
 
                     info!("Received signal, preparing to restart VN process");
+                    restarts += 1;
                 },
                 None => {
                     error!("Failed to receive restart signal, exiting");
@@ -155,6 +229,9 @@ pub async fn spawn_validator_node_os(
         tx_alert,
         rx_alert,
         cfg_alert,
+        auto_restart,
+        rx_child,
+        tx_command,
     })
 }
 
@@ -201,6 +278,11 @@ pub async fn start_validator(
     minotari_node_grpc_url: Url,
     alerting_config: Channels,
     auto_restart: bool,
+    liveness: Option<LivenessProbeConfig>,
+    memory_events_path: Option<PathBuf>,
+    pre_restart_cmd: Option<String>,
+    post_restart_cmd: Option<String>,
+    status_channels: StatusChannelsConfig,
     trigger_signal: Shutdown,
 ) -> Option<ChildChannel> {
     let opt = check_existing_node_os(vn_base_dir.clone()).await;
@@ -218,6 +300,11 @@ pub async fn start_validator(
         alerting_config,
         auto_restart,
         minotari_node_grpc_url,
+        liveness,
+        memory_events_path,
+        pre_restart_cmd,
+        post_restart_cmd,
+        status_channels,
         trigger_signal,
     )
     .await
@@ -18,6 +18,7 @@ const REGISTRATION_LOOP_INTERVAL: Duration = Duration::from_secs(30);
 // Periodically checks that the local node is still registered on the network.
 // If it is no longer registered or close to expiry (1 epoch of blocks or less), it will attempt to re-register.
 // It will do nothing if it is registered already and not close to expiry.
+// A warning is alerted once the registration expires within the configured number of epochs.
 pub async fn registration_loop(config: Config, mut handle: ManagerHandle) -> anyhow::Result<ManagerHandle> {
     let mut interval = time::interval(REGISTRATION_LOOP_INTERVAL);
     interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
@@ -69,6 +70,18 @@ pub async fn registration_loop(config: Config, mut handle: ManagerHandle) -> any
             continue;
         }
 
+        // warn ahead of time so that operators can act before the node drops out of the committee
+        if config.registration_expiry.warning_epochs > 0 && contains_key(active_keys.clone(), public_key.clone()) {
+            match handle
+                .check_registration_expiry(current_block, config.registration_expiry.warning_epochs)
+                .await
+            {
+                Ok(Some(blocks_remaining)) => warn!("VN registration expires in {} blocks", blocks_remaining),
+                Ok(None) => {},
+                Err(e) => error!("Failed to check VN registration expiry: {}", e),
+            }
+        }
+
         // if the node is already registered and not close to expiring in the next epoch, skip registration
         if contains_key(active_keys.clone(), public_key.clone()) || recently_registered {
             info!("VN has an active registration and will not expire in the next epoch, skip");
@@ -84,7 +97,10 @@ pub async fn registration_loop(config: Config, mut handle: ManagerHandle) -> any
             error!("Failed to register VN: {}", e);
             continue;
         }
-        let tx = tx.unwrap();
+        let Some(tx) = tx.unwrap() else {
+            info!("VN already has a valid registration, skipped submission");
+            continue;
+        };
         if !tx.is_success {
             error!("Failed to register VN: {}", tx.failure_message);
             continue;
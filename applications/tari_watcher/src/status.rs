@@ -0,0 +1,116 @@
+// Copyright 2024 The Tari Project
+// SPDX-License-Identifier: BSD-3-Clause
+
+use std::{net::SocketAddr, time::SystemTime};
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    routing::{get, post},
+    Json,
+    Router,
+};
+use log::*;
+use serde_json::json;
+use tokio::sync::{broadcast, mpsc, watch};
+
+use crate::monitoring::{AlertChannel, AlertCommand, ChildCommand};
+
+// The validator node child process currently being monitored
+#[derive(Copy, Clone, Debug)]
+pub struct ChildInfo {
+    pub pid: u32,
+    pub started_at: SystemTime,
+    // The number of times the child has been restarted by the watcher
+    pub restarts: u64,
+}
+
+impl ChildInfo {
+    pub fn uptime_secs(&self) -> u64 {
+        SystemTime::now()
+            .duration_since(self.started_at)
+            .map(|uptime| uptime.as_secs())
+            .unwrap_or(0)
+    }
+
+    fn to_json(self) -> serde_json::Value {
+        json!({
+            "pid": self.pid,
+            "started_at": humantime::format_rfc3339_seconds(self.started_at).to_string(),
+            "uptime_secs": self.uptime_secs(),
+            "restarts": self.restarts,
+        })
+    }
+}
+
+#[derive(Clone)]
+struct StatusState {
+    rx_child: watch::Receiver<Option<ChildInfo>>,
+    alert_control: mpsc::Sender<AlertCommand>,
+    child_control: broadcast::Sender<ChildCommand>,
+}
+
+// Serves the watcher's status and operator commands over HTTP on the given address. This address should only be
+// reachable by operators, since the commands are not authenticated.
+//
+// - `GET /` returns the current child info as JSON, e.g. for external supervisors to correlate the watcher's child with
+//   OS-level process metrics. The response is `null` while no child is running.
+// - `POST /restart` gracefully restarts the validator node, regardless of whether auto restart is enabled.
+// - `POST /alerts/{mattermost,telegram}/{mute,unmute}` mutes or unmutes an alert channel until the watcher restarts.
+pub async fn serve_status(
+    address: SocketAddr,
+    rx_child: watch::Receiver<Option<ChildInfo>>,
+    alert_control: mpsc::Sender<AlertCommand>,
+    child_control: broadcast::Sender<ChildCommand>,
+) -> anyhow::Result<()> {
+    let router = Router::new()
+        .route("/", get(get_child_status))
+        .route("/restart", post(restart_child))
+        .route("/alerts/:channel/mute", post(mute_alert_channel))
+        .route("/alerts/:channel/unmute", post(unmute_alert_channel))
+        .with_state(StatusState {
+            rx_child,
+            alert_control,
+            child_control,
+        });
+    let server = axum::Server::try_bind(&address)?.serve(router.into_make_service());
+    info!("Serving validator node status on http://{}", server.local_addr());
+    server.await?;
+    Ok(())
+}
+
+async fn get_child_status(State(state): State<StatusState>) -> Json<serde_json::Value> {
+    let child = *state.rx_child.borrow();
+    Json(child.map(ChildInfo::to_json).unwrap_or(serde_json::Value::Null))
+}
+
+async fn restart_child(State(state): State<StatusState>) -> StatusCode {
+    info!("Operator requested a validator node restart");
+    // there are no receivers while the validator node is being (re)spawned
+    match state.child_control.send(ChildCommand::Restart) {
+        Ok(_) => StatusCode::ACCEPTED,
+        Err(_) => {
+            warn!("No validator node is running, restart was not requested");
+            StatusCode::CONFLICT
+        },
+    }
+}
+
+async fn mute_alert_channel(State(state): State<StatusState>, Path(channel): Path<AlertChannel>) -> StatusCode {
+    send_alert_command(&state, AlertCommand::MuteChannel(channel)).await
+}
+
+async fn unmute_alert_channel(State(state): State<StatusState>, Path(channel): Path<AlertChannel>) -> StatusCode {
+    send_alert_command(&state, AlertCommand::UnmuteChannel(channel)).await
+}
+
+async fn send_alert_command(state: &StatusState, cmd: AlertCommand) -> StatusCode {
+    info!("Operator requested {:?}", cmd);
+    match state.alert_control.send(cmd).await {
+        Ok(()) => StatusCode::NO_CONTENT,
+        Err(_) => {
+            warn!("Alerting task has exited, {:?} was not applied", cmd);
+            StatusCode::SERVICE_UNAVAILABLE
+        },
+    }
+}
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Epoch } from "../Epoch";
+import type { NodeHeight } from "../NodeHeight";
+import type { ShardGroup } from "../ShardGroup";
+
+export interface GetLeaderScheduleRequest {
+  epoch: Epoch;
+  shard_group: ShardGroup;
+  from_height: NodeHeight;
+  to_height: NodeHeight;
+}
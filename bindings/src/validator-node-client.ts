@@ -29,6 +29,8 @@ export * from "./types/validator-node-client/GetTransactionResponse";
 export * from "./types/validator-node-client/GetCommitteeResponse";
 export * from "./types/validator-node-client/VNGetAllVnsRequest";
 export * from "./types/validator-node-client/GetCommitteeRequest";
+export * from "./types/validator-node-client/GetLeaderScheduleRequest";
+export * from "./types/validator-node-client/GetLeaderScheduleResponse";
 export * from "./types/validator-node-client/DryRunTransactionFinalizeResult";
 export * from "./types/validator-node-client/VNGetValidatorFeesResponse";
 export * from "./types/validator-node-client/GetStateResponse";
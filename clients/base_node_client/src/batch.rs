@@ -0,0 +1,80 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use crate::{
+    types::{BaseLayerConsensusConstants, BaseLayerMetadata, BaseLayerValidatorNode},
+    BaseNodeClient,
+    BaseNodeClientError,
+};
+
+/// Collects independent reads and issues them concurrently, each on its own clone of the client, so that fetching
+/// e.g. the tip info, consensus constants and validator nodes together takes as long as the slowest read rather than
+/// all three. Created with [BaseNodeClient::batch].
+#[derive(Debug)]
+pub struct BaseNodeClientBatch<'a, TClient> {
+    client: &'a TClient,
+    tip_info: bool,
+    consensus_constants_tip: Option<u64>,
+    validator_nodes_height: Option<u64>,
+}
+
+impl<'a, TClient: BaseNodeClient> BaseNodeClientBatch<'a, TClient> {
+    pub fn new(client: &'a TClient) -> Self {
+        Self {
+            client,
+            tip_info: false,
+            consensus_constants_tip: None,
+            validator_nodes_height: None,
+        }
+    }
+
+    pub fn tip_info(mut self) -> Self {
+        self.tip_info = true;
+        self
+    }
+
+    pub fn consensus_constants(mut self, tip: u64) -> Self {
+        self.consensus_constants_tip = Some(tip);
+        self
+    }
+
+    pub fn validator_nodes(mut self, height: u64) -> Self {
+        self.validator_nodes_height = Some(height);
+        self
+    }
+
+    /// Issues the requested reads concurrently and waits for all of them to complete. A failed read does not cancel
+    /// the others.
+    pub async fn execute(self) -> BaseNodeClientBatchResults {
+        let tip_info = async {
+            if !self.tip_info {
+                return None;
+            }
+            Some(self.client.clone().get_tip_info().await)
+        };
+        let consensus_constants = async {
+            let tip = self.consensus_constants_tip?;
+            Some(self.client.clone().get_consensus_constants(tip).await)
+        };
+        let validator_nodes = async {
+            let height = self.validator_nodes_height?;
+            Some(self.client.clone().get_validator_nodes(height).await)
+        };
+
+        let (tip_info, consensus_constants, validator_nodes) =
+            futures::join!(tip_info, consensus_constants, validator_nodes);
+        BaseNodeClientBatchResults {
+            tip_info,
+            consensus_constants,
+            validator_nodes,
+        }
+    }
+}
+
+/// The results of a [BaseNodeClientBatch]. A result is None if the read was not requested.
+#[derive(Debug)]
+pub struct BaseNodeClientBatchResults {
+    pub tip_info: Option<Result<BaseLayerMetadata, BaseNodeClientError>>,
+    pub consensus_constants: Option<Result<BaseLayerConsensusConstants, BaseNodeClientError>>,
+    pub validator_nodes: Option<Result<Vec<BaseLayerValidatorNode>, BaseNodeClientError>>,
+}
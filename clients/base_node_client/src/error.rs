@@ -1,7 +1,9 @@
 //   Copyright 2023 The Tari Project
 //   SPDX-License-Identifier: BSD-3-Clause
 
-use tari_common_types::types::FixedHashSizeError;
+use std::{error::Error as StdError, time::Duration};
+
+use tari_common_types::types::{FixedHash, FixedHashSizeError};
 use tari_dan_common_types::optional::IsNotFoundError;
 use thiserror::Error;
 
@@ -12,20 +14,86 @@ pub enum BaseNodeClientError {
     ConnectionError,
     #[error("Connection error: {0}")]
     GrpcConnection(#[from] tonic::transport::Error),
-    #[error("GRPC error: {0}")]
-    GrpcStatus(#[from] tonic::Status),
+    #[error("Not found: {message}")]
+    NotFound { message: String },
+    #[error("Bad request: {message}")]
+    BadRequest { message: String },
+    #[error("GRPC error ({code}): {message}")]
+    Rpc { code: tonic::Code, message: String },
     #[error("Peer sent an invalid message: {0}")]
     InvalidPeerMessage(String),
+    #[error("Base node returned an invalid {context}: {source}")]
+    Serialization {
+        context: String,
+        source: Box<dyn StdError + Send + Sync>,
+    },
     #[error("Hash size error: {0}")]
     HashSizeError(#[from] FixedHashSizeError),
+    #[error("Block at height {height} has been pruned by the base node")]
+    Pruned { height: u64 },
+    #[error("Timed out waiting for tip height {target_height} (current tip height: {tip_height})")]
+    Timeout { target_height: u64, tip_height: u64 },
+    #[error("Base node tip at height {tip_height} is {age:.0?} old which exceeds the maximum age of {max_age:.0?}")]
+    StaleTip {
+        tip_height: u64,
+        age: Duration,
+        max_age: Duration,
+    },
+    #[error("Recording error: {details}")]
+    Recording { details: String },
+    #[error(
+        "Block header {hash} has insufficient proof of work: achieved difficulty {achieved} is less than the target \
+         difficulty {target}"
+    )]
+    InsufficientProofOfWork {
+        hash: FixedHash,
+        achieved: u64,
+        target: u64,
+    },
+    #[error("Cannot verify the proof of work of block header {hash}: {details}")]
+    UnverifiableProofOfWork { hash: FixedHash, details: String },
+    #[error("Base node has not completed its initial sync (local tip height: {tip_height})")]
+    NotSynced { tip_height: u64 },
+}
+
+impl BaseNodeClientError {
+    /// Returns a [BaseNodeClientError::Serialization] error for the field of a base node response named by `context`
+    /// that could not be converted
+    pub fn serialization<E>(context: &str, source: E) -> Self
+    where E: Into<Box<dyn StdError + Send + Sync>> {
+        Self::Serialization {
+            context: context.to_string(),
+            source: source.into(),
+        }
+    }
+
+    /// Returns true if the request may succeed when retried, i.e. the base node could not be reached or did not respond
+    /// in time
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, Self::ConnectionError | Self::GrpcConnection(_))
+    }
+}
+
+impl From<tonic::Status> for BaseNodeClientError {
+    fn from(status: tonic::Status) -> Self {
+        match status.code() {
+            tonic::Code::Unavailable | tonic::Code::DeadlineExceeded => Self::ConnectionError,
+            tonic::Code::NotFound => Self::NotFound {
+                message: status.message().to_string(),
+            },
+            tonic::Code::InvalidArgument | tonic::Code::FailedPrecondition => Self::BadRequest {
+                message: status.message().to_string(),
+            },
+            code => Self::Rpc {
+                code,
+                message: status.message().to_string(),
+            },
+        }
+    }
 }
 
 impl IsNotFoundError for BaseNodeClientError {
     fn is_not_found_error(&self) -> bool {
-        if let Self::GrpcStatus(status) = self {
-            status.code() == tonic::Code::NotFound
-        } else {
-            false
-        }
+        matches!(self, Self::NotFound { .. })
     }
 }
@@ -22,25 +22,42 @@
 //  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use std::convert::TryInto;
+use std::{
+    convert::TryInto,
+    sync::{Arc, OnceLock},
+    time::{Duration, Instant},
+};
 
 use async_trait::async_trait;
+use futures::future;
 use log::*;
-use minotari_app_grpc::tari_rpc::{
-    self as grpc,
-    GetShardKeyRequest,
-    GetValidatorNodeChangesRequest,
-    ValidatorNodeChange,
-};
+use mini_moka::sync::Cache;
+use minotari_app_grpc::tari_rpc::{self as grpc, GetShardKeyRequest, GetValidatorNodeChangesRequest};
 use minotari_node_grpc_client::BaseNodeGrpcClient;
-use tari_common_types::types::{FixedHash, PublicKey};
-use tari_core::{blocks::BlockHeader, transactions::transaction_components::CodeTemplateRegistration};
+use tari_common_types::types::{FixedHash, PedersenCommitment, PublicKey};
+use tari_core::{
+    blocks::{Block, BlockHeader},
+    transactions::{
+        transaction_components::{CodeTemplateRegistration, Transaction, TransactionOutput, ValidatorNodeRegistration},
+        weight::TransactionWeight,
+    },
+};
 use tari_dan_common_types::SubstateAddress;
-use tari_utilities::ByteArray;
+use tari_utilities::{hex::Hex, ByteArray};
+use tonic::transport::ClientTlsConfig;
 use url::Url;
 
 use crate::{
-    types::{BaseLayerConsensusConstants, BaseLayerMetadata, BaseLayerValidatorNode, BlockInfo, SideChainUtxos},
+    types::{
+        BaseLayerConsensusConstants,
+        BaseLayerMetadata,
+        BaseLayerValidatorNode,
+        BlockInfo,
+        FeeEstimates,
+        NetworkInfo,
+        SideChainUtxos,
+        ValidatorNodeChange,
+    },
     BaseNodeClient,
     BaseNodeClientError,
 };
@@ -49,31 +66,181 @@ const LOG_TARGET: &str = "tari::validator_node::app";
 
 type Client = BaseNodeGrpcClient<tonic::transport::Channel>;
 
+/// The transport used to connect to the base node, see [GrpcBaseNodeClient::with_transport]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transport {
+    /// Unencrypted HTTP/2
+    Plaintext,
+    /// HTTP/2 over TLS, verified against the native root certificates of the platform
+    Tls,
+}
+
 #[derive(Clone)]
 pub struct GrpcBaseNodeClient {
-    endpoint: Url,
+    /// The endpoint to (re)connect to. This is None if the client wraps an externally managed channel.
+    endpoint: Option<Url>,
     client: Option<Client>,
+    /// The network info never changes, so it is fetched once and shared by all clones of the client
+    network_info: Arc<OnceLock<NetworkInfo>>,
+    skip_invalid_validator_nodes: bool,
+    header_cache: Option<Cache<FixedHash, BlockHeader>>,
+    /// Overrides the transport inferred from the endpoint URL scheme
+    transport: Option<Transport>,
 }
 
 impl GrpcBaseNodeClient {
     pub fn new(endpoint: Url) -> Self {
-        Self { endpoint, client: None }
+        Self {
+            endpoint: Some(endpoint),
+            client: None,
+            network_info: Arc::default(),
+            skip_invalid_validator_nodes: false,
+            header_cache: None,
+            transport: None,
+        }
     }
 
     pub async fn connect(endpoint: Url) -> Result<Self, BaseNodeClientError> {
-        let mut client = Self { endpoint, client: None };
+        let mut client = Self::new(endpoint);
         client.test_connection().await?;
         Ok(client)
     }
 
+    /// Creates a client that uses an existing channel, for example one that is shared with other clients or connected
+    /// to an in-process server.
+    pub fn from_channel(channel: tonic::transport::Channel) -> Self {
+        Self {
+            endpoint: None,
+            client: Some(Client::new(channel)),
+            network_info: Arc::default(),
+            skip_invalid_validator_nodes: false,
+            header_cache: None,
+            transport: None,
+        }
+    }
+
+    /// Connects to each endpoint concurrently and reports whether it is reachable. For reachable endpoints, the time
+    /// taken to connect and fetch the tip info is returned. Results are returned in the same order as the endpoints.
+    pub async fn health_check_all(endpoints: &[Url]) -> Vec<(Url, Result<Duration, BaseNodeClientError>)> {
+        let checks = endpoints.iter().cloned().map(|endpoint| async move {
+            let timer = Instant::now();
+            let result = async {
+                let mut client = Self::connect(endpoint.clone()).await?;
+                client.get_tip_info().await?;
+                Ok(timer.elapsed())
+            }
+            .await;
+            if let Err(err) = &result {
+                debug!(target: LOG_TARGET, "Base node {} is not reachable: {}", endpoint, err);
+            }
+            (endpoint, result)
+        });
+
+        future::join_all(checks).await
+    }
+
     async fn connection(&mut self) -> Result<&mut Client, BaseNodeClientError> {
         if self.client.is_none() {
-            let inner = Client::connect(self.endpoint.to_string()).await?;
+            let endpoint = self.endpoint.as_ref().ok_or(BaseNodeClientError::ConnectionError)?;
+            let inner = match self.transport {
+                Some(transport) => Client::new(connect_with_transport(endpoint, transport).await?),
+                None => Client::connect(endpoint.to_string()).await?,
+            };
             self.client = Some(inner);
         }
         self.client.as_mut().ok_or(BaseNodeClientError::ConnectionError)
     }
 
+    /// When enabled, validator nodes that cannot be parsed are logged and skipped rather than failing the whole
+    /// request. This is disabled by default.
+    pub fn with_skip_invalid_validator_nodes(mut self, skip_invalid_validator_nodes: bool) -> Self {
+        self.skip_invalid_validator_nodes = skip_invalid_validator_nodes;
+        self
+    }
+
+    /// Forces the transport used to connect to the base node, taking precedence over the transport inferred from the
+    /// endpoint URL scheme. This is useful when, for example, an `https://` URL fronts a plaintext sidecar. The
+    /// transport is inferred from the URL scheme by default. This has no effect on a client created with
+    /// [GrpcBaseNodeClient::from_channel].
+    pub fn with_transport(mut self, transport: Transport) -> Self {
+        self.transport = Some(transport);
+        self
+    }
+
+    /// Caches up to `capacity` headers fetched by hash, so that fetching the same header again does not make a request
+    /// to the base node. The header for a given hash never changes, however a cached header is still returned after its
+    /// block is reorged out of the main chain, so a client with a header cache cannot be used to check whether a block
+    /// is still on the main chain. Clones of the client share the cache. This is disabled by default.
+    pub fn with_header_cache(mut self, capacity: u64) -> Self {
+        self.header_cache = Some(Cache::builder().max_capacity(capacity).build());
+        self
+    }
+
+    /// Returns the active validator nodes at the given height along with the number of malformed validator nodes that
+    /// were skipped. Nodes are only skipped if [GrpcBaseNodeClient::with_skip_invalid_validator_nodes] is enabled,
+    /// otherwise the first malformed node results in an error.
+    pub async fn get_validator_nodes_with_skipped(
+        &mut self,
+        height: u64,
+    ) -> Result<(Vec<BaseLayerValidatorNode>, usize), BaseNodeClientError> {
+        self.fetch_active_validator_nodes(height, vec![]).await
+    }
+
+    async fn fetch_active_validator_nodes(
+        &mut self,
+        height: u64,
+        sidechain_id: Vec<u8>,
+    ) -> Result<(Vec<BaseLayerValidatorNode>, usize), BaseNodeClientError> {
+        let skip_invalid = self.skip_invalid_validator_nodes;
+        let inner = self.connection().await?;
+
+        let request = grpc::GetActiveValidatorNodesRequest { height, sidechain_id };
+        let mut stream = inner.get_active_validator_nodes(request).await?.into_inner();
+
+        let mut vns = vec![];
+        let mut num_skipped = 0;
+        loop {
+            match stream.message().await {
+                Ok(Some(val)) => match parse_validator_node(&val) {
+                    Ok(vn) => vns.push(vn),
+                    Err(err) if skip_invalid => {
+                        warn!(
+                            target: LOG_TARGET,
+                            "Skipping invalid validator node (public key: {}) at height {}: {}",
+                            val.public_key.to_hex(),
+                            height,
+                            err
+                        );
+                        num_skipped += 1;
+                    },
+                    Err(err) => return Err(err),
+                },
+                Ok(None) => {
+                    break;
+                },
+                Err(e) => {
+                    return Err(e.into());
+                },
+            }
+        }
+
+        if num_skipped > 0 {
+            warn!(
+                target: LOG_TARGET,
+                "Skipped {} invalid validator node(s) at height {} ({} valid)",
+                num_skipped,
+                height,
+                vns.len()
+            );
+        }
+
+        if vns.is_empty() {
+            debug!(target: LOG_TARGET, "No validator nodes at height {}", height);
+        }
+
+        Ok((vns, num_skipped))
+    }
+
     pub async fn get_mempool_transaction_count(&mut self) -> Result<usize, BaseNodeClientError> {
         let inner = self.connection().await.unwrap();
         let request = grpc::GetMempoolTransactionsRequest {};
@@ -90,7 +257,7 @@ impl GrpcBaseNodeClient {
                 },
                 Err(e) => {
                     warn!(target: LOG_TARGET, "Error getting mempool transaction count: {}", e);
-                    return Err(BaseNodeClientError::ConnectionError);
+                    return Err(e.into());
                 },
             }
         }
@@ -114,18 +281,54 @@ impl BaseNodeClient for GrpcBaseNodeClient {
             .ok_or_else(|| BaseNodeClientError::InvalidPeerMessage("Base node returned no metadata".to_string()))?;
         Ok(BaseLayerMetadata {
             height_of_longest_chain: metadata.best_block_height,
-            tip_hash: metadata.best_block_hash.try_into().map_err(|_| {
-                BaseNodeClientError::InvalidPeerMessage("best_block was not a valid fixed hash".to_string())
-            })?,
+            tip_hash: metadata
+                .best_block_hash
+                .try_into()
+                .map_err(|e| BaseNodeClientError::serialization("metadata.best_block_hash", e))?,
+            tip_timestamp: metadata.timestamp,
+            initial_sync_achieved: result.initial_sync_achieved,
         })
     }
 
+    async fn is_base_node_synced(&mut self) -> Result<bool, BaseNodeClientError> {
+        Ok(self.get_tip_info().await?.initial_sync_achieved)
+    }
+
+    async fn get_network_info(&mut self) -> Result<NetworkInfo, BaseNodeClientError> {
+        if let Some(network_info) = self.network_info.get() {
+            return Ok(*network_info);
+        }
+
+        let inner = self.connection().await?;
+        // Only the genesis header is needed, so it is listed rather than fetching the whole genesis block
+        let request = grpc::ListHeadersRequest {
+            from_height: 0,
+            num_headers: 1,
+            sorting: grpc::Sorting::Asc as i32,
+        };
+        let mut stream = inner.list_headers(request).await?.into_inner();
+        let network_info = network_info_from_genesis_header(stream.message().await?)?;
+
+        // If a clone fetched the network info concurrently, both fetched the same immutable value
+        Ok(*self.network_info.get_or_init(|| network_info))
+    }
+
     async fn get_validator_node_changes(
         &mut self,
         start_height: u64,
         end_height: u64,
         sidechain_id: Option<&PublicKey>,
     ) -> Result<Vec<ValidatorNodeChange>, BaseNodeClientError> {
+        // The base node range is inclusive, so start_height == end_height is a valid single block range
+        if start_height > end_height {
+            return Err(BaseNodeClientError::BadRequest {
+                message: format!(
+                    "Invalid validator node change range: start height {start_height} is greater than end height \
+                     {end_height}"
+                ),
+            });
+        }
+
         let client = self.connection().await?;
         let result = client
             .get_validator_node_changes(GetValidatorNodeChangesRequest {
@@ -139,64 +342,54 @@ impl BaseNodeClient for GrpcBaseNodeClient {
             .await?
             .into_inner();
 
-        Ok(result.changes)
+        let mut changes = Vec::with_capacity(result.changes.len());
+        for change in result.changes {
+            changes.extend(parse_validator_node_change(change)?);
+        }
+        Ok(changes)
     }
 
     async fn get_validator_nodes(&mut self, height: u64) -> Result<Vec<BaseLayerValidatorNode>, BaseNodeClientError> {
-        let inner = self.connection().await?;
-
         // SidechainId is empty because we need all the sidechain nodes to create the merkle root
+        self.fetch_active_validator_nodes(height, vec![])
+            .await
+            .map(|(vns, _)| vns)
+    }
+
+    async fn get_active_validator_nodes_count(&mut self, height: u64) -> Result<usize, BaseNodeClientError> {
+        let inner = self.connection().await?;
+        // SidechainId is empty to count the nodes of all sidechains, as with get_validator_nodes
         let request = grpc::GetActiveValidatorNodesRequest {
             height,
             sidechain_id: vec![],
         };
-        let mut stream = inner.get_active_validator_nodes(request).await?.into_inner();
 
-        let mut vns = vec![];
+        let mut count = 0;
+        let mut stream = inner.get_active_validator_nodes(request).await?.into_inner();
         loop {
             match stream.message().await {
-                Ok(Some(val)) => {
-                    vns.push(BaseLayerValidatorNode {
-                        public_key: PublicKey::from_canonical_bytes(&val.public_key).map_err(|_| {
-                            BaseNodeClientError::InvalidPeerMessage("public_key was not a valid public key".to_string())
-                        })?,
-                        shard_key: {
-                            let hash = FixedHash::try_from(val.shard_key.as_slice()).map_err(|_| {
-                                BaseNodeClientError::InvalidPeerMessage(
-                                    "shard_key was not a valid fixed hash".to_string(),
-                                )
-                            })?;
-                            SubstateAddress::from_hash_and_version(hash, 0)
-                        },
-                        sidechain_id: if val.sidechain_id.is_empty() {
-                            None
-                        } else {
-                            Some(PublicKey::from_canonical_bytes(&val.sidechain_id).map_err(|_| {
-                                BaseNodeClientError::InvalidPeerMessage(
-                                    "sidechain_id was not a valid public key".to_string(),
-                                )
-                            }))
-                        }
-                        .transpose()?,
-                    });
+                Ok(Some(_val)) => {
+                    count += 1;
                 },
                 Ok(None) => {
                     break;
                 },
                 Err(e) => {
-                    return Err(BaseNodeClientError::InvalidPeerMessage(format!(
-                        "Error reading stream: {}",
-                        e
-                    )));
+                    return Err(e.into());
                 },
             }
         }
+        Ok(count)
+    }
 
-        if vns.is_empty() {
-            debug!(target: LOG_TARGET, "No validator nodes at height {}", height);
-        }
-
-        Ok(vns)
+    async fn get_validator_nodes_for_sidechain(
+        &mut self,
+        height: u64,
+        sidechain_id: &PublicKey,
+    ) -> Result<Vec<BaseLayerValidatorNode>, BaseNodeClientError> {
+        self.fetch_active_validator_nodes(height, sidechain_id.to_vec())
+            .await
+            .map(|(vns, _)| vns)
     }
 
     async fn get_shard_key(
@@ -216,7 +409,8 @@ impl BaseNodeClient for GrpcBaseNodeClient {
             // The SubstateAddress type has 4 extra bytes for the version, this is disregarded for validator node shard
             // key.
             // TODO: separate type for validator node shard key
-            let hash = FixedHash::try_from(result.shard_key.as_slice())?;
+            let hash = FixedHash::try_from(result.shard_key.as_slice())
+                .map_err(|e| BaseNodeClientError::serialization("shard_key", e))?;
             Ok(Some(SubstateAddress::from_hash_and_version(hash, 0)))
         }
     }
@@ -244,26 +438,72 @@ impl BaseNodeClient for GrpcBaseNodeClient {
                             )
                         })?
                         .try_into()
-                        .map_err(|_| {
-                            BaseNodeClientError::InvalidPeerMessage("invalid template registration".to_string())
-                        })?;
+                        .map_err(|e| BaseNodeClientError::serialization("template registration", e))?;
                     templates.push(template_registration);
                 },
                 Ok(None) => {
                     break;
                 },
                 Err(e) => {
-                    return Err(BaseNodeClientError::InvalidPeerMessage(format!(
-                        "Error reading stream: {}",
-                        e
-                    )));
+                    return Err(e.into());
                 },
             }
         }
         Ok(templates)
     }
 
+    async fn get_blocks(&mut self, heights: Vec<u64>) -> Result<Vec<Block>, BaseNodeClientError> {
+        let Some(min_height) = heights.iter().copied().min() else {
+            return Ok(vec![]);
+        };
+
+        let inner = self.connection().await?;
+        let metadata = inner
+            .get_tip_info(grpc::Empty {})
+            .await?
+            .into_inner()
+            .metadata
+            .ok_or_else(|| BaseNodeClientError::InvalidPeerMessage("Base node returned no metadata".to_string()))?;
+        // A pruned height of zero indicates an archival node
+        if min_height < metadata.pruned_height {
+            return Err(BaseNodeClientError::Pruned { height: min_height });
+        }
+
+        let request = grpc::GetBlocksRequest { heights };
+        let mut stream = inner
+            .get_blocks(request)
+            .await
+            .map_err(|status| pruned_or_status(status, min_height))?
+            .into_inner();
+        let mut blocks = vec![];
+        loop {
+            match stream.message().await {
+                Ok(Some(historical_block)) => {
+                    let block = historical_block
+                        .block
+                        .ok_or_else(|| {
+                            BaseNodeClientError::InvalidPeerMessage("Base node returned no block".to_string())
+                        })?
+                        .try_into()
+                        .map_err(|e| BaseNodeClientError::serialization("block", e))?;
+                    blocks.push(block);
+                },
+                Ok(None) => {
+                    break;
+                },
+                Err(status) => {
+                    return Err(pruned_or_status(status, min_height));
+                },
+            }
+        }
+
+        Ok(blocks)
+    }
+
     async fn get_header_by_hash(&mut self, block_hash: FixedHash) -> Result<BlockHeader, BaseNodeClientError> {
+        if let Some(header) = self.header_cache.as_ref().and_then(|cache| cache.get(&block_hash)) {
+            return Ok(header);
+        }
         let inner = self.connection().await?;
         let request = grpc::GetHeaderByHashRequest {
             hash: block_hash.to_vec(),
@@ -272,7 +512,19 @@ impl BaseNodeClient for GrpcBaseNodeClient {
         let header = result
             .header
             .ok_or_else(|| BaseNodeClientError::InvalidPeerMessage("Base node returned no header".to_string()))?;
-        let header = header.try_into().map_err(BaseNodeClientError::InvalidPeerMessage)?;
+        let header: BlockHeader = header
+            .try_into()
+            .map_err(|e| BaseNodeClientError::serialization("header", e))?;
+        // The header is cached by the requested hash, so a header for a different block must never be inserted
+        let header_hash = header.hash();
+        if header_hash != block_hash {
+            return Err(BaseNodeClientError::InvalidPeerMessage(format!(
+                "Base node returned header {header_hash} when header {block_hash} was requested"
+            )));
+        }
+        if let Some(cache) = &self.header_cache {
+            cache.insert(block_hash, header.clone());
+        }
         Ok(header)
     }
 
@@ -285,13 +537,7 @@ impl BaseNodeClient for GrpcBaseNodeClient {
         let request = grpc::BlockHeight { block_height };
         let result = inner.get_constants(request).await?.into_inner();
 
-        let consensus_constants = BaseLayerConsensusConstants {
-            epoch_length: result.epoch_length,
-            validator_node_registration_min_deposit_amount: result
-                .validator_node_registration_min_deposit_amount
-                .into(),
-        };
-        Ok(consensus_constants)
+        Ok(BaseLayerConsensusConstants::from(&result))
     }
 
     async fn get_sidechain_utxos(
@@ -315,18 +561,22 @@ impl BaseNodeClient for GrpcBaseNodeClient {
                     let resp = SideChainUtxos {
                         block_info: BlockInfo {
                             height: block_info.height,
-                            hash: block_info.hash.try_into()?,
+                            hash: block_info
+                                .hash
+                                .try_into()
+                                .map_err(|e| BaseNodeClientError::serialization("block_info.hash", e))?,
                             next_block_hash: Some(block_info.next_block_hash)
                                 .filter(|v| !v.is_empty())
                                 .map(TryInto::try_into)
-                                .transpose()?,
+                                .transpose()
+                                .map_err(|e| BaseNodeClientError::serialization("block_info.next_block_hash", e))?,
                         },
                         outputs: resp
                             .outputs
                             .into_iter()
                             .map(TryInto::try_into)
                             .collect::<Result<_, _>>()
-                            .map_err(BaseNodeClientError::InvalidPeerMessage)?,
+                            .map_err(|e| BaseNodeClientError::serialization("output", e))?,
                     };
                     responses.push(resp);
                 },
@@ -334,14 +584,249 @@ impl BaseNodeClient for GrpcBaseNodeClient {
                     break;
                 },
                 Err(e) => {
-                    return Err(BaseNodeClientError::InvalidPeerMessage(format!(
-                        "Error reading stream: {}",
-                        e
-                    )));
+                    return Err(e.into());
                 },
             }
         }
 
         Ok(responses)
     }
+
+    async fn get_utxo_by_commitment(
+        &mut self,
+        commitment: &PedersenCommitment,
+    ) -> Result<Option<TransactionOutput>, BaseNodeClientError> {
+        let inner = self.connection().await?;
+        let commitment = commitment.to_vec();
+        let request = grpc::SearchUtxosRequest {
+            commitments: vec![commitment.clone()],
+        };
+        let mut stream = inner.search_utxos(request).await?.into_inner();
+        loop {
+            match stream.message().await {
+                Ok(Some(historical_block)) => {
+                    // The base node returns the whole block containing the output, so we pick it out by commitment
+                    let maybe_output = historical_block
+                        .block
+                        .and_then(|block| block.body)
+                        .and_then(|body| body.outputs.into_iter().find(|output| output.commitment == commitment));
+                    if let Some(output) = maybe_output {
+                        let output = output
+                            .try_into()
+                            .map_err(|e| BaseNodeClientError::serialization("output", e))?;
+                        return Ok(Some(output));
+                    }
+                },
+                Ok(None) => {
+                    break;
+                },
+                Err(e) => {
+                    return Err(e.into());
+                },
+            }
+        }
+
+        Ok(None)
+    }
+
+    async fn get_mempool_fee_estimates(&mut self) -> Result<FeeEstimates, BaseNodeClientError> {
+        let inner = self.connection().await?;
+        let request = grpc::GetMempoolTransactionsRequest {};
+        let weighting = TransactionWeight::latest();
+
+        let mut fees_per_gram = vec![];
+        let mut stream = inner.get_mempool_transactions(request).await?.into_inner();
+        loop {
+            match stream.message().await {
+                Ok(Some(resp)) => {
+                    let Some(transaction) = resp.transaction else {
+                        continue;
+                    };
+                    let transaction = Transaction::try_from(transaction)
+                        .map_err(|e| BaseNodeClientError::serialization("transaction", e))?;
+                    let fee = transaction
+                        .body
+                        .get_total_fee()
+                        .map_err(|e| BaseNodeClientError::InvalidPeerMessage(e.to_string()))?;
+                    let weight = transaction
+                        .calculate_weight(&weighting)
+                        .map_err(|e| BaseNodeClientError::InvalidPeerMessage(e.to_string()))?;
+                    if weight > 0 {
+                        fees_per_gram.push(fee.as_u64() / weight);
+                    }
+                },
+                Ok(None) => {
+                    break;
+                },
+                Err(e) => {
+                    warn!(target: LOG_TARGET, "Error getting mempool transactions: {}", e);
+                    return Err(e.into());
+                },
+            }
+        }
+
+        Ok(FeeEstimates::from_fees_per_gram(fees_per_gram))
+    }
+}
+
+fn parse_validator_node(
+    val: &grpc::GetActiveValidatorNodesResponse,
+) -> Result<BaseLayerValidatorNode, BaseNodeClientError> {
+    Ok(BaseLayerValidatorNode {
+        public_key: PublicKey::from_canonical_bytes(&val.public_key)
+            .map_err(|e| BaseNodeClientError::serialization("public_key", e))?,
+        shard_key: {
+            let hash = FixedHash::try_from(val.shard_key.as_slice())
+                .map_err(|e| BaseNodeClientError::serialization("shard_key", e))?;
+            SubstateAddress::from_hash_and_version(hash, 0)
+        },
+        sidechain_id: if val.sidechain_id.is_empty() {
+            None
+        } else {
+            Some(
+                PublicKey::from_canonical_bytes(&val.sidechain_id)
+                    .map_err(|e| BaseNodeClientError::serialization("sidechain_id", e)),
+            )
+        }
+        .transpose()?,
+    })
+}
+
+/// Parses a validator node change. Changes without a registration cannot be applied, so they are skipped.
+fn parse_validator_node_change(
+    change: grpc::ValidatorNodeChange,
+) -> Result<Option<ValidatorNodeChange>, BaseNodeClientError> {
+    let state = change.state();
+    let Some(registration) = change.registration else {
+        warn!(
+            target: LOG_TARGET,
+            "Skipping validator node change for {} because it has no registration",
+            change.public_key.to_hex()
+        );
+        return Ok(None);
+    };
+    let registration = ValidatorNodeRegistration::try_from(registration)
+        .map_err(|e| BaseNodeClientError::serialization("validator node registration", e))?;
+    let public_key = PublicKey::from_canonical_bytes(&change.public_key)
+        .map_err(|e| BaseNodeClientError::serialization("public_key", e))?;
+    let sidechain_id = registration.sidechain_id().cloned();
+
+    let change = match state {
+        grpc::ValidatorNodeChangeState::Add => ValidatorNodeChange::Add {
+            public_key,
+            sidechain_id,
+            registration,
+            activation_height: change.start_height,
+            minimum_value_promise: change.minimum_value_promise.into(),
+        },
+        grpc::ValidatorNodeChangeState::Remove => ValidatorNodeChange::Remove {
+            public_key,
+            sidechain_id,
+        },
+    };
+    Ok(Some(change))
+}
+
+/// The base node may prune blocks between our metadata check and the request, in which case the status message reports
+/// that the block was pruned.
+fn pruned_or_status(status: tonic::Status, height: u64) -> BaseNodeClientError {
+    if status.message().to_lowercase().contains("pruned") {
+        BaseNodeClientError::Pruned { height }
+    } else {
+        status.into()
+    }
+}
+
+/// Connects to `endpoint` using the given transport rather than the one implied by its scheme. The scheme is rewritten
+/// to match the transport, since tonic infers whether to use TLS from it.
+async fn connect_with_transport(
+    endpoint: &Url,
+    transport: Transport,
+) -> Result<tonic::transport::Channel, BaseNodeClientError> {
+    let scheme = match transport {
+        Transport::Plaintext => "http",
+        Transport::Tls => "https",
+    };
+    let mut url = endpoint.clone();
+    url.set_scheme(scheme).map_err(|_| BaseNodeClientError::BadRequest {
+        message: format!("Cannot connect to base node endpoint {endpoint} using {transport:?} transport"),
+    })?;
+    let mut channel =
+        tonic::transport::Endpoint::from_shared(url.to_string()).map_err(|e| BaseNodeClientError::BadRequest {
+            message: format!("Invalid base node endpoint {url}: {e}"),
+        })?;
+    if transport == Transport::Tls {
+        channel = channel.tls_config(ClientTlsConfig::new().with_native_roots())?;
+    }
+    Ok(channel.connect().await?)
+}
+
+/// Returns the network info for the response to a request for the genesis header
+fn network_info_from_genesis_header(
+    response: Option<grpc::BlockHeaderResponse>,
+) -> Result<NetworkInfo, BaseNodeClientError> {
+    let header = response
+        .and_then(|response| response.header)
+        .ok_or_else(|| BaseNodeClientError::InvalidPeerMessage("Base node returned no genesis header".to_string()))?;
+    if header.height != 0 {
+        return Err(BaseNodeClientError::InvalidPeerMessage(format!(
+            "Base node returned the header at height {} instead of the genesis header",
+            header.height
+        )));
+    }
+    let genesis_hash = header
+        .hash
+        .try_into()
+        .map_err(|e| BaseNodeClientError::serialization("genesis header hash", e))?;
+    Ok(NetworkInfo { genesis_hash })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn genesis_header_response(height: u64) -> grpc::BlockHeaderResponse {
+        grpc::BlockHeaderResponse {
+            header: Some(grpc::BlockHeader {
+                hash: vec![1u8; 32],
+                height,
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn it_gets_the_network_info_from_the_genesis_header() {
+        let network_info = network_info_from_genesis_header(Some(genesis_header_response(0))).unwrap();
+        assert_eq!(network_info.genesis_hash, FixedHash::from([1u8; 32]));
+    }
+
+    #[test]
+    fn it_rejects_a_missing_or_non_genesis_header() {
+        let err = network_info_from_genesis_header(None).unwrap_err();
+        assert!(matches!(err, BaseNodeClientError::InvalidPeerMessage(_)));
+        let err = network_info_from_genesis_header(Some(genesis_header_response(1))).unwrap_err();
+        assert!(matches!(err, BaseNodeClientError::InvalidPeerMessage(_)));
+    }
+
+    #[tokio::test]
+    async fn it_does_not_cache_the_network_info_if_the_base_node_is_unreachable() {
+        let mut client = GrpcBaseNodeClient::new("http://127.0.0.1:1".parse().unwrap());
+        client.get_network_info().await.unwrap_err();
+        assert!(client.network_info.get().is_none());
+    }
+
+    #[tokio::test]
+    async fn it_shares_the_network_info_between_clones() {
+        let client = GrpcBaseNodeClient::new("http://127.0.0.1:1".parse().unwrap());
+        let mut clone = client.clone();
+        let network_info = NetworkInfo {
+            genesis_hash: FixedHash::from([1u8; 32]),
+        };
+        client.network_info.set(network_info).unwrap();
+
+        // The cached value is returned without connecting to the (unreachable) base node
+        assert_eq!(clone.get_network_info().await.unwrap(), network_info);
+    }
 }
@@ -1,11 +1,21 @@
 //   Copyright 2023 The Tari Project
 //   SPDX-License-Identifier: BSD-3-Clause
 
+mod batch;
+pub use batch::{BaseNodeClientBatch, BaseNodeClientBatchResults};
+
 mod error;
 pub use error::BaseNodeClientError;
 
 pub mod grpc;
+#[cfg(feature = "metrics")]
+pub mod metrics;
+#[cfg(feature = "recording")]
+pub mod recording;
 pub mod types;
 
 mod traits;
-pub use traits::BaseNodeClient;
+pub use traits::{BaseNodeClient, MAX_SHARD_KEY_HISTORY_HEIGHTS};
+
+mod template_cursor;
+pub use template_cursor::{ProcessedBlock, TemplateRegistrationCursor};
@@ -0,0 +1,227 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::future::Future;
+
+use async_trait::async_trait;
+use once_cell::sync::Lazy;
+use tari_common_types::types::{FixedHash, PedersenCommitment, PublicKey};
+use tari_core::{
+    blocks::{Block, BlockHeader},
+    transactions::transaction_components::{CodeTemplateRegistration, TransactionOutput},
+};
+use tari_dan_common_types::SubstateAddress;
+use tari_metrics::{Histogram, HistogramVec, IntCounter, IntCounterVec};
+
+use crate::{
+    types::{
+        BaseLayerConsensusConstants,
+        BaseLayerMetadata,
+        BaseLayerValidatorNode,
+        FeeEstimates,
+        NetworkInfo,
+        SideChainUtxos,
+        ValidatorNodeChange,
+    },
+    BaseNodeClient,
+    BaseNodeClientError,
+};
+
+pub fn call_counter(method: &str) -> IntCounter {
+    static METER: Lazy<IntCounterVec> = Lazy::new(|| {
+        tari_metrics::register_int_counter_vec(
+            "base_node_client::call_count",
+            "The number of base node client calls per method",
+            &["method"],
+        )
+        .unwrap()
+    });
+
+    METER.with_label_values(&[method])
+}
+
+pub fn call_latency(method: &str) -> Histogram {
+    static METER: Lazy<HistogramVec> = Lazy::new(|| {
+        tari_metrics::register_histogram_vec(
+            "base_node_client::call_latency",
+            "The latency in seconds of base node client calls per method",
+            &["method"],
+        )
+        .unwrap()
+    });
+
+    METER.with_label_values(&[method])
+}
+
+pub fn error_counter(method: &str, err: &BaseNodeClientError) -> IntCounter {
+    static METER: Lazy<IntCounterVec> = Lazy::new(|| {
+        tari_metrics::register_int_counter_vec(
+            "base_node_client::error_count",
+            "The number of base node client errors per method per error variant",
+            &["method", "error"],
+        )
+        .unwrap()
+    });
+
+    METER.with_label_values(&[method, error_label(err)])
+}
+
+fn error_label(err: &BaseNodeClientError) -> &'static str {
+    match err {
+        BaseNodeClientError::ConnectionError => "ConnectionError",
+        BaseNodeClientError::GrpcConnection(_) => "GrpcConnection",
+        BaseNodeClientError::NotFound { .. } => "NotFound",
+        BaseNodeClientError::BadRequest { .. } => "BadRequest",
+        BaseNodeClientError::Rpc { .. } => "Rpc",
+        BaseNodeClientError::InvalidPeerMessage(_) => "InvalidPeerMessage",
+        BaseNodeClientError::Serialization { .. } => "Serialization",
+        BaseNodeClientError::HashSizeError(_) => "HashSizeError",
+        BaseNodeClientError::Pruned { .. } => "Pruned",
+        BaseNodeClientError::Timeout { .. } => "Timeout",
+        BaseNodeClientError::StaleTip { .. } => "StaleTip",
+        BaseNodeClientError::Recording { .. } => "Recording",
+        BaseNodeClientError::InsufficientProofOfWork { .. } => "InsufficientProofOfWork",
+        BaseNodeClientError::UnverifiableProofOfWork { .. } => "UnverifiableProofOfWork",
+        BaseNodeClientError::NotSynced { .. } => "NotSynced",
+    }
+}
+
+async fn measure<T, F>(method: &'static str, fut: F) -> Result<T, BaseNodeClientError>
+where F: Future<Output = Result<T, BaseNodeClientError>> {
+    call_counter(method).inc();
+    let timer = call_latency(method).start_timer();
+    let result = fut.await;
+    timer.observe_duration();
+    if let Err(err) = &result {
+        error_counter(method, err).inc();
+    }
+    result
+}
+
+/// A [BaseNodeClient] that records call counts, latency and errors for each method of the wrapped client.
+#[derive(Debug, Clone)]
+pub struct MeteredBaseNodeClient<TClient> {
+    inner: TClient,
+}
+
+impl<TClient> MeteredBaseNodeClient<TClient> {
+    pub fn new(inner: TClient) -> Self {
+        Self { inner }
+    }
+
+    pub fn inner(&self) -> &TClient {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> TClient {
+        self.inner
+    }
+}
+
+#[async_trait]
+impl<TClient: BaseNodeClient> BaseNodeClient for MeteredBaseNodeClient<TClient> {
+    async fn test_connection(&mut self) -> Result<(), BaseNodeClientError> {
+        measure("test_connection", self.inner.test_connection()).await
+    }
+
+    async fn get_tip_info(&mut self) -> Result<BaseLayerMetadata, BaseNodeClientError> {
+        measure("get_tip_info", self.inner.get_tip_info()).await
+    }
+
+    async fn is_base_node_synced(&mut self) -> Result<bool, BaseNodeClientError> {
+        measure("is_base_node_synced", self.inner.is_base_node_synced()).await
+    }
+
+    async fn get_network_info(&mut self) -> Result<NetworkInfo, BaseNodeClientError> {
+        measure("get_network_info", self.inner.get_network_info()).await
+    }
+
+    async fn get_validator_node_changes(
+        &mut self,
+        start_height: u64,
+        end_height: u64,
+        sidechain_id: Option<&PublicKey>,
+    ) -> Result<Vec<ValidatorNodeChange>, BaseNodeClientError> {
+        measure(
+            "get_validator_node_changes",
+            self.inner
+                .get_validator_node_changes(start_height, end_height, sidechain_id),
+        )
+        .await
+    }
+
+    async fn get_validator_nodes(&mut self, height: u64) -> Result<Vec<BaseLayerValidatorNode>, BaseNodeClientError> {
+        measure("get_validator_nodes", self.inner.get_validator_nodes(height)).await
+    }
+
+    async fn get_active_validator_nodes_count(&mut self, height: u64) -> Result<usize, BaseNodeClientError> {
+        measure(
+            "get_active_validator_nodes_count",
+            self.inner.get_active_validator_nodes_count(height),
+        )
+        .await
+    }
+
+    async fn get_validator_nodes_for_sidechain(
+        &mut self,
+        height: u64,
+        sidechain_id: &PublicKey,
+    ) -> Result<Vec<BaseLayerValidatorNode>, BaseNodeClientError> {
+        measure(
+            "get_validator_nodes_for_sidechain",
+            self.inner.get_validator_nodes_for_sidechain(height, sidechain_id),
+        )
+        .await
+    }
+
+    async fn get_shard_key(
+        &mut self,
+        height: u64,
+        public_key: &PublicKey,
+    ) -> Result<Option<SubstateAddress>, BaseNodeClientError> {
+        measure("get_shard_key", self.inner.get_shard_key(height, public_key)).await
+    }
+
+    async fn get_template_registrations(
+        &mut self,
+        start_hash: Option<FixedHash>,
+        count: u64,
+    ) -> Result<Vec<CodeTemplateRegistration>, BaseNodeClientError> {
+        measure(
+            "get_template_registrations",
+            self.inner.get_template_registrations(start_hash, count),
+        )
+        .await
+    }
+
+    async fn get_blocks(&mut self, heights: Vec<u64>) -> Result<Vec<Block>, BaseNodeClientError> {
+        measure("get_blocks", self.inner.get_blocks(heights)).await
+    }
+
+    async fn get_header_by_hash(&mut self, block_hash: FixedHash) -> Result<BlockHeader, BaseNodeClientError> {
+        measure("get_header_by_hash", self.inner.get_header_by_hash(block_hash)).await
+    }
+
+    async fn get_consensus_constants(&mut self, tip: u64) -> Result<BaseLayerConsensusConstants, BaseNodeClientError> {
+        measure("get_consensus_constants", self.inner.get_consensus_constants(tip)).await
+    }
+
+    async fn get_sidechain_utxos(
+        &mut self,
+        start_hash: Option<FixedHash>,
+        count: u64,
+    ) -> Result<Vec<SideChainUtxos>, BaseNodeClientError> {
+        measure("get_sidechain_utxos", self.inner.get_sidechain_utxos(start_hash, count)).await
+    }
+
+    async fn get_utxo_by_commitment(
+        &mut self,
+        commitment: &PedersenCommitment,
+    ) -> Result<Option<TransactionOutput>, BaseNodeClientError> {
+        measure("get_utxo_by_commitment", self.inner.get_utxo_by_commitment(commitment)).await
+    }
+
+    async fn get_mempool_fee_estimates(&mut self) -> Result<FeeEstimates, BaseNodeClientError> {
+        measure("get_mempool_fee_estimates", self.inner.get_mempool_fee_estimates()).await
+    }
+}
@@ -0,0 +1,664 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::{
+    collections::{HashMap, VecDeque},
+    fs,
+    io,
+    io::Write,
+    path::Path,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{json, Value};
+use tari_common_types::types::{FixedHash, PedersenCommitment, PublicKey};
+use tari_core::{
+    blocks::{Block, BlockHeader},
+    transactions::transaction_components::{CodeTemplateRegistration, TransactionOutput},
+};
+use tari_dan_common_types::SubstateAddress;
+
+use crate::{
+    types::{
+        BaseLayerConsensusConstants,
+        BaseLayerMetadata,
+        BaseLayerValidatorNode,
+        FeeEstimates,
+        NetworkInfo,
+        SideChainUtxos,
+        ValidatorNodeChange,
+    },
+    BaseNodeClient,
+    BaseNodeClientError,
+};
+
+/// A [BaseNodeClient] that records the calls made to the wrapped client, or replays previously recorded calls without
+/// a base node.
+///
+/// In record mode, every call is forwarded to the wrapped client and the call and its response are appended to the
+/// recording file as a line of JSON. In replay mode, calls are answered from a recording. A call is matched on its
+/// method and arguments, and repeated calls with the same arguments are answered in the order they were recorded, so
+/// a test that makes the same calls as when it was recorded receives the same responses.
+#[derive(Debug, Clone)]
+pub struct RecordingBaseNodeClient<TClient> {
+    mode: Mode<TClient>,
+}
+
+#[derive(Debug, Clone)]
+enum Mode<TClient> {
+    Record {
+        inner: TClient,
+        file: Arc<Mutex<fs::File>>,
+    },
+    Replay {
+        responses: Arc<Mutex<HashMap<String, VecDeque<RecordedResult>>>>,
+    },
+}
+
+impl<TClient> RecordingBaseNodeClient<TClient> {
+    /// Wraps the client and appends each call to the recording at `path`. The file is created if it does not exist.
+    pub fn record<P: AsRef<Path>>(inner: TClient, path: P) -> io::Result<Self> {
+        let file = fs::OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self {
+            mode: Mode::Record {
+                inner,
+                file: Arc::new(Mutex::new(file)),
+            },
+        })
+    }
+
+    /// Loads the recording at `path` to answer calls from. Returns an error if the recording cannot be read or is
+    /// malformed.
+    pub fn replay<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let contents = fs::read_to_string(path)?;
+        let mut responses = HashMap::<_, VecDeque<_>>::new();
+        for line in contents.lines().filter(|line| !line.trim().is_empty()) {
+            let call: RecordedCall =
+                serde_json::from_str(line).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+            responses
+                .entry(call_key(&call.method, &call.request))
+                .or_default()
+                .push_back(call.response);
+        }
+
+        Ok(Self {
+            mode: Mode::Replay {
+                responses: Arc::new(Mutex::new(responses)),
+            },
+        })
+    }
+
+    /// Returns true if calls are answered from a recording
+    pub fn is_replaying(&self) -> bool {
+        matches!(self.mode, Mode::Replay { .. })
+    }
+}
+
+#[async_trait]
+impl<TClient: BaseNodeClient> BaseNodeClient for RecordingBaseNodeClient<TClient> {
+    async fn test_connection(&mut self) -> Result<(), BaseNodeClientError> {
+        let request = json!({});
+        match &mut self.mode {
+            Mode::Record { inner, file } => {
+                let result = inner.test_connection().await;
+                record(file, "test_connection", request, result.as_ref())?;
+                result
+            },
+            Mode::Replay { responses } => replay(responses, "test_connection", &request),
+        }
+    }
+
+    async fn get_tip_info(&mut self) -> Result<BaseLayerMetadata, BaseNodeClientError> {
+        let request = json!({});
+        match &mut self.mode {
+            Mode::Record { inner, file } => {
+                let result = inner.get_tip_info().await;
+                record(file, "get_tip_info", request, result.as_ref())?;
+                result
+            },
+            Mode::Replay { responses } => replay(responses, "get_tip_info", &request),
+        }
+    }
+
+    async fn is_base_node_synced(&mut self) -> Result<bool, BaseNodeClientError> {
+        let request = json!({});
+        match &mut self.mode {
+            Mode::Record { inner, file } => {
+                let result = inner.is_base_node_synced().await;
+                record(file, "is_base_node_synced", request, result.as_ref())?;
+                result
+            },
+            Mode::Replay { responses } => replay(responses, "is_base_node_synced", &request),
+        }
+    }
+
+    async fn get_network_info(&mut self) -> Result<NetworkInfo, BaseNodeClientError> {
+        let request = json!({});
+        match &mut self.mode {
+            Mode::Record { inner, file } => {
+                let result = inner.get_network_info().await;
+                record(file, "get_network_info", request, result.as_ref())?;
+                result
+            },
+            Mode::Replay { responses } => replay(responses, "get_network_info", &request),
+        }
+    }
+
+    async fn get_validator_node_changes(
+        &mut self,
+        start_height: u64,
+        end_height: u64,
+        sidechain_id: Option<&PublicKey>,
+    ) -> Result<Vec<ValidatorNodeChange>, BaseNodeClientError> {
+        let request = json!({ "start_height": start_height, "end_height": end_height, "sidechain_id": sidechain_id });
+        match &mut self.mode {
+            Mode::Record { inner, file } => {
+                let result = inner
+                    .get_validator_node_changes(start_height, end_height, sidechain_id)
+                    .await;
+                record(file, "get_validator_node_changes", request, result.as_ref())?;
+                result
+            },
+            Mode::Replay { responses } => replay(responses, "get_validator_node_changes", &request),
+        }
+    }
+
+    async fn get_validator_nodes(&mut self, height: u64) -> Result<Vec<BaseLayerValidatorNode>, BaseNodeClientError> {
+        let request = json!({ "height": height });
+        match &mut self.mode {
+            Mode::Record { inner, file } => {
+                let result = inner.get_validator_nodes(height).await;
+                record(file, "get_validator_nodes", request, result.as_ref())?;
+                result
+            },
+            Mode::Replay { responses } => replay(responses, "get_validator_nodes", &request),
+        }
+    }
+
+    async fn get_active_validator_nodes_count(&mut self, height: u64) -> Result<usize, BaseNodeClientError> {
+        let request = json!({ "height": height });
+        match &mut self.mode {
+            Mode::Record { inner, file } => {
+                let result = inner.get_active_validator_nodes_count(height).await;
+                record(file, "get_active_validator_nodes_count", request, result.as_ref())?;
+                result
+            },
+            Mode::Replay { responses } => replay(responses, "get_active_validator_nodes_count", &request),
+        }
+    }
+
+    async fn get_validator_nodes_for_sidechain(
+        &mut self,
+        height: u64,
+        sidechain_id: &PublicKey,
+    ) -> Result<Vec<BaseLayerValidatorNode>, BaseNodeClientError> {
+        let request = json!({ "height": height, "sidechain_id": sidechain_id });
+        match &mut self.mode {
+            Mode::Record { inner, file } => {
+                let result = inner.get_validator_nodes_for_sidechain(height, sidechain_id).await;
+                record(file, "get_validator_nodes_for_sidechain", request, result.as_ref())?;
+                result
+            },
+            Mode::Replay { responses } => replay(responses, "get_validator_nodes_for_sidechain", &request),
+        }
+    }
+
+    async fn get_shard_key(
+        &mut self,
+        height: u64,
+        public_key: &PublicKey,
+    ) -> Result<Option<SubstateAddress>, BaseNodeClientError> {
+        let request = json!({ "height": height, "public_key": public_key });
+        match &mut self.mode {
+            Mode::Record { inner, file } => {
+                let result = inner.get_shard_key(height, public_key).await;
+                record(file, "get_shard_key", request, result.as_ref())?;
+                result
+            },
+            Mode::Replay { responses } => replay(responses, "get_shard_key", &request),
+        }
+    }
+
+    async fn get_template_registrations(
+        &mut self,
+        start_hash: Option<FixedHash>,
+        count: u64,
+    ) -> Result<Vec<CodeTemplateRegistration>, BaseNodeClientError> {
+        let request = json!({ "start_hash": start_hash, "count": count });
+        match &mut self.mode {
+            Mode::Record { inner, file } => {
+                let result = inner.get_template_registrations(start_hash, count).await;
+                record(file, "get_template_registrations", request, result.as_ref())?;
+                result
+            },
+            Mode::Replay { responses } => replay(responses, "get_template_registrations", &request),
+        }
+    }
+
+    async fn get_blocks(&mut self, heights: Vec<u64>) -> Result<Vec<Block>, BaseNodeClientError> {
+        let request = json!({ "heights": heights });
+        match &mut self.mode {
+            Mode::Record { inner, file } => {
+                let result = inner.get_blocks(heights).await;
+                record(file, "get_blocks", request, result.as_ref())?;
+                result
+            },
+            Mode::Replay { responses } => replay(responses, "get_blocks", &request),
+        }
+    }
+
+    async fn get_header_by_hash(&mut self, block_hash: FixedHash) -> Result<BlockHeader, BaseNodeClientError> {
+        let request = json!({ "block_hash": block_hash });
+        match &mut self.mode {
+            Mode::Record { inner, file } => {
+                let result = inner.get_header_by_hash(block_hash).await;
+                record(file, "get_header_by_hash", request, result.as_ref())?;
+                result
+            },
+            Mode::Replay { responses } => replay(responses, "get_header_by_hash", &request),
+        }
+    }
+
+    async fn get_consensus_constants(&mut self, tip: u64) -> Result<BaseLayerConsensusConstants, BaseNodeClientError> {
+        let request = json!({ "tip": tip });
+        match &mut self.mode {
+            Mode::Record { inner, file } => {
+                let result = inner.get_consensus_constants(tip).await;
+                record(file, "get_consensus_constants", request, result.as_ref())?;
+                result
+            },
+            Mode::Replay { responses } => replay(responses, "get_consensus_constants", &request),
+        }
+    }
+
+    async fn get_sidechain_utxos(
+        &mut self,
+        start_hash: Option<FixedHash>,
+        count: u64,
+    ) -> Result<Vec<SideChainUtxos>, BaseNodeClientError> {
+        let request = json!({ "start_hash": start_hash, "count": count });
+        match &mut self.mode {
+            Mode::Record { inner, file } => {
+                let result = inner.get_sidechain_utxos(start_hash, count).await;
+                record(file, "get_sidechain_utxos", request, result.as_ref())?;
+                result
+            },
+            Mode::Replay { responses } => replay(responses, "get_sidechain_utxos", &request),
+        }
+    }
+
+    async fn get_utxo_by_commitment(
+        &mut self,
+        commitment: &PedersenCommitment,
+    ) -> Result<Option<TransactionOutput>, BaseNodeClientError> {
+        let request = json!({ "commitment": commitment });
+        match &mut self.mode {
+            Mode::Record { inner, file } => {
+                let result = inner.get_utxo_by_commitment(commitment).await;
+                record(file, "get_utxo_by_commitment", request, result.as_ref())?;
+                result
+            },
+            Mode::Replay { responses } => replay(responses, "get_utxo_by_commitment", &request),
+        }
+    }
+
+    async fn get_mempool_fee_estimates(&mut self) -> Result<FeeEstimates, BaseNodeClientError> {
+        let request = json!({});
+        match &mut self.mode {
+            Mode::Record { inner, file } => {
+                let result = inner.get_mempool_fee_estimates().await;
+                record(file, "get_mempool_fee_estimates", request, result.as_ref())?;
+                result
+            },
+            Mode::Replay { responses } => replay(responses, "get_mempool_fee_estimates", &request),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct RecordedCall {
+    method: String,
+    request: Value,
+    response: RecordedResult,
+}
+
+type RecordedResult = Result<Value, RecordedError>;
+
+/// A serializable copy of a [BaseNodeClientError] so that errors are replayed as well as successful responses
+#[derive(Debug, Clone, Serialize, Deserialize)]
+enum RecordedError {
+    ConnectionError,
+    NotFound {
+        message: String,
+    },
+    BadRequest {
+        message: String,
+    },
+    Rpc {
+        code: i32,
+        message: String,
+    },
+    InvalidPeerMessage(String),
+    Serialization {
+        context: String,
+        details: String,
+    },
+    Pruned {
+        height: u64,
+    },
+    Timeout {
+        target_height: u64,
+        tip_height: u64,
+    },
+    StaleTip {
+        tip_height: u64,
+        age: Duration,
+        max_age: Duration,
+    },
+    Recording {
+        details: String,
+    },
+    NotSynced {
+        tip_height: u64,
+    },
+}
+
+impl From<&BaseNodeClientError> for RecordedError {
+    fn from(err: &BaseNodeClientError) -> Self {
+        match err {
+            // Transport errors cannot be reconstructed, but are replayed as the equivalent retryable error
+            BaseNodeClientError::ConnectionError | BaseNodeClientError::GrpcConnection(_) => Self::ConnectionError,
+            BaseNodeClientError::NotFound { message } => Self::NotFound {
+                message: message.clone(),
+            },
+            BaseNodeClientError::BadRequest { message } => Self::BadRequest {
+                message: message.clone(),
+            },
+            BaseNodeClientError::Rpc { code, message } => Self::Rpc {
+                code: *code as i32,
+                message: message.clone(),
+            },
+            BaseNodeClientError::InvalidPeerMessage(message) => Self::InvalidPeerMessage(message.clone()),
+            // The source cannot be reconstructed, so its message is replayed instead
+            BaseNodeClientError::Serialization { context, source } => Self::Serialization {
+                context: context.clone(),
+                details: source.to_string(),
+            },
+            BaseNodeClientError::HashSizeError(err) => Self::InvalidPeerMessage(err.to_string()),
+            BaseNodeClientError::Pruned { height } => Self::Pruned { height: *height },
+            BaseNodeClientError::Timeout {
+                target_height,
+                tip_height,
+            } => Self::Timeout {
+                target_height: *target_height,
+                tip_height: *tip_height,
+            },
+            BaseNodeClientError::StaleTip {
+                tip_height,
+                age,
+                max_age,
+            } => Self::StaleTip {
+                tip_height: *tip_height,
+                age: *age,
+                max_age: *max_age,
+            },
+            BaseNodeClientError::Recording { details } => Self::Recording {
+                details: details.clone(),
+            },
+            BaseNodeClientError::NotSynced { tip_height } => Self::NotSynced {
+                tip_height: *tip_height,
+            },
+            // Proof of work is verified locally, so these are never returned by the recorded calls
+            err @ (BaseNodeClientError::InsufficientProofOfWork { .. } |
+            BaseNodeClientError::UnverifiableProofOfWork { .. }) => Self::InvalidPeerMessage(err.to_string()),
+        }
+    }
+}
+
+impl From<RecordedError> for BaseNodeClientError {
+    fn from(err: RecordedError) -> Self {
+        match err {
+            RecordedError::ConnectionError => Self::ConnectionError,
+            RecordedError::NotFound { message } => Self::NotFound { message },
+            RecordedError::BadRequest { message } => Self::BadRequest { message },
+            RecordedError::Rpc { code, message } => Self::Rpc {
+                code: tonic::Code::from(code),
+                message,
+            },
+            RecordedError::InvalidPeerMessage(message) => Self::InvalidPeerMessage(message),
+            RecordedError::Serialization { context, details } => Self::serialization(&context, details),
+            RecordedError::Pruned { height } => Self::Pruned { height },
+            RecordedError::Timeout {
+                target_height,
+                tip_height,
+            } => Self::Timeout {
+                target_height,
+                tip_height,
+            },
+            RecordedError::StaleTip {
+                tip_height,
+                age,
+                max_age,
+            } => Self::StaleTip {
+                tip_height,
+                age,
+                max_age,
+            },
+            RecordedError::Recording { details } => Self::Recording { details },
+            RecordedError::NotSynced { tip_height } => Self::NotSynced { tip_height },
+        }
+    }
+}
+
+fn call_key(method: &str, request: &Value) -> String {
+    format!("{method}:{request}")
+}
+
+fn record<T: Serialize>(
+    file: &Mutex<fs::File>,
+    method: &str,
+    request: Value,
+    result: Result<T, &BaseNodeClientError>,
+) -> Result<(), BaseNodeClientError> {
+    let response = match result {
+        Ok(response) => Ok(
+            serde_json::to_value(response).map_err(|err| BaseNodeClientError::Recording {
+                details: format!("Failed to serialize {method} response: {err}"),
+            })?,
+        ),
+        Err(err) => Err(RecordedError::from(err)),
+    };
+    let call = RecordedCall {
+        method: method.to_string(),
+        request,
+        response,
+    };
+    let line = serde_json::to_string(&call).map_err(|err| BaseNodeClientError::Recording {
+        details: format!("Failed to serialize {method} call: {err}"),
+    })?;
+
+    writeln!(file.lock().unwrap(), "{line}").map_err(|err| BaseNodeClientError::Recording {
+        details: format!("Failed to write {method} call to recording: {err}"),
+    })
+}
+
+fn replay<T: DeserializeOwned>(
+    responses: &Mutex<HashMap<String, VecDeque<RecordedResult>>>,
+    method: &str,
+    request: &Value,
+) -> Result<T, BaseNodeClientError> {
+    let response = responses
+        .lock()
+        .unwrap()
+        .get_mut(&call_key(method, request))
+        .and_then(|responses| responses.pop_front())
+        .ok_or_else(|| BaseNodeClientError::Recording {
+            details: format!("No recorded response remaining for {method}({request})"),
+        })?;
+
+    let response = response.map_err(BaseNodeClientError::from)?;
+    serde_json::from_value(response).map_err(|err| BaseNodeClientError::Recording {
+        details: format!("Failed to deserialize recorded {method} response: {err}"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Answers the tip info and fails to find any header. The other calls are not made by the tests.
+    #[derive(Debug, Clone)]
+    struct TestClient;
+
+    #[async_trait]
+    impl BaseNodeClient for TestClient {
+        async fn test_connection(&mut self) -> Result<(), BaseNodeClientError> {
+            unimplemented!()
+        }
+
+        async fn get_tip_info(&mut self) -> Result<BaseLayerMetadata, BaseNodeClientError> {
+            Ok(BaseLayerMetadata {
+                height_of_longest_chain: 123,
+                tip_hash: FixedHash::from([1u8; 32]),
+                tip_timestamp: 456,
+                initial_sync_achieved: true,
+            })
+        }
+
+        async fn is_base_node_synced(&mut self) -> Result<bool, BaseNodeClientError> {
+            unimplemented!()
+        }
+
+        async fn get_network_info(&mut self) -> Result<NetworkInfo, BaseNodeClientError> {
+            unimplemented!()
+        }
+
+        async fn get_validator_node_changes(
+            &mut self,
+            _start_height: u64,
+            _end_height: u64,
+            _sidechain_id: Option<&PublicKey>,
+        ) -> Result<Vec<ValidatorNodeChange>, BaseNodeClientError> {
+            unimplemented!()
+        }
+
+        async fn get_validator_nodes(
+            &mut self,
+            _height: u64,
+        ) -> Result<Vec<BaseLayerValidatorNode>, BaseNodeClientError> {
+            unimplemented!()
+        }
+
+        async fn get_active_validator_nodes_count(&mut self, _height: u64) -> Result<usize, BaseNodeClientError> {
+            unimplemented!()
+        }
+
+        async fn get_validator_nodes_for_sidechain(
+            &mut self,
+            _height: u64,
+            _sidechain_id: &PublicKey,
+        ) -> Result<Vec<BaseLayerValidatorNode>, BaseNodeClientError> {
+            unimplemented!()
+        }
+
+        async fn get_shard_key(
+            &mut self,
+            _height: u64,
+            _public_key: &PublicKey,
+        ) -> Result<Option<SubstateAddress>, BaseNodeClientError> {
+            unimplemented!()
+        }
+
+        async fn get_template_registrations(
+            &mut self,
+            _start_hash: Option<FixedHash>,
+            _count: u64,
+        ) -> Result<Vec<CodeTemplateRegistration>, BaseNodeClientError> {
+            unimplemented!()
+        }
+
+        async fn get_blocks(&mut self, _heights: Vec<u64>) -> Result<Vec<Block>, BaseNodeClientError> {
+            unimplemented!()
+        }
+
+        async fn get_header_by_hash(&mut self, block_hash: FixedHash) -> Result<BlockHeader, BaseNodeClientError> {
+            Err(BaseNodeClientError::NotFound {
+                message: format!("Header {block_hash} not found"),
+            })
+        }
+
+        async fn get_consensus_constants(
+            &mut self,
+            _tip: u64,
+        ) -> Result<BaseLayerConsensusConstants, BaseNodeClientError> {
+            unimplemented!()
+        }
+
+        async fn get_sidechain_utxos(
+            &mut self,
+            _start_hash: Option<FixedHash>,
+            _count: u64,
+        ) -> Result<Vec<SideChainUtxos>, BaseNodeClientError> {
+            unimplemented!()
+        }
+
+        async fn get_utxo_by_commitment(
+            &mut self,
+            _commitment: &PedersenCommitment,
+        ) -> Result<Option<TransactionOutput>, BaseNodeClientError> {
+            unimplemented!()
+        }
+
+        async fn get_mempool_fee_estimates(&mut self) -> Result<FeeEstimates, BaseNodeClientError> {
+            unimplemented!()
+        }
+    }
+
+    #[tokio::test]
+    async fn it_replays_recorded_responses_and_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("recording.jsonl");
+
+        let mut client = RecordingBaseNodeClient::record(TestClient, &path).unwrap();
+        assert!(!client.is_replaying());
+        let tip = client.get_tip_info().await.unwrap();
+        let err = client.get_header_by_hash(FixedHash::zero()).await.unwrap_err();
+        assert!(matches!(err, BaseNodeClientError::NotFound { .. }));
+
+        let mut client = RecordingBaseNodeClient::<TestClient>::replay(&path).unwrap();
+        assert!(client.is_replaying());
+        let replayed_tip = client.get_tip_info().await.unwrap();
+        assert_eq!(replayed_tip.height_of_longest_chain, tip.height_of_longest_chain);
+        assert_eq!(replayed_tip.tip_hash, tip.tip_hash);
+        assert_eq!(replayed_tip.tip_timestamp, tip.tip_timestamp);
+
+        let replayed_err = client.get_header_by_hash(FixedHash::zero()).await.unwrap_err();
+        match replayed_err {
+            BaseNodeClientError::NotFound { message } => {
+                assert_eq!(message, format!("Header {} not found", FixedHash::zero()));
+            },
+            err => panic!("Unexpected error {err}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn it_fails_if_a_call_was_not_recorded() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("recording.jsonl");
+
+        let mut client = RecordingBaseNodeClient::record(TestClient, &path).unwrap();
+        client.get_tip_info().await.unwrap();
+
+        let mut client = RecordingBaseNodeClient::<TestClient>::replay(&path).unwrap();
+        client.get_tip_info().await.unwrap();
+        // Each recorded response is only replayed once
+        let err = client.get_tip_info().await.unwrap_err();
+        assert!(matches!(err, BaseNodeClientError::Recording { .. }));
+        // A call with different arguments was never recorded
+        let err = client.get_header_by_hash(FixedHash::zero()).await.unwrap_err();
+        assert!(matches!(err, BaseNodeClientError::Recording { .. }));
+    }
+}
@@ -0,0 +1,165 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::collections::VecDeque;
+
+use log::*;
+use tari_common_types::types::FixedHash;
+use tari_core::transactions::transaction_components::{CodeTemplateRegistration, SideChainFeature};
+use tari_dan_common_types::optional::IsNotFoundError;
+
+use crate::{BaseNodeClient, BaseNodeClientError};
+
+const LOG_TARGET: &str = "tari::base_node_client::template_cursor";
+
+/// The number of processed blocks the cursor keeps to rewind to in the event of a reorg
+const MAX_CHECKPOINTS: usize = 100;
+
+/// A block whose template registrations have been returned by a [TemplateRegistrationCursor]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProcessedBlock {
+    pub hash: FixedHash,
+    pub height: u64,
+}
+
+/// A resumable cursor over the template registrations on the base layer.
+///
+/// Each call to [TemplateRegistrationCursor::next_batch] scans the blocks after the last block that was processed until
+/// at least `count` registrations are found or the tip is reached. Before scanning, the cursor checks that the last
+/// processed block is still the main chain block at its height and, if it was reorged out, rewinds to the most recent
+/// processed block that still is. Registrations in blocks that are scanned again after a rewind are returned again, so
+/// consumers should process registrations idempotently.
+#[derive(Debug, Clone)]
+pub struct TemplateRegistrationCursor {
+    count: u64,
+    /// The last block whose registrations were returned
+    last_processed: Option<ProcessedBlock>,
+    /// The hash of the next block to scan. This is the last processed block when we have scanned up to the tip.
+    next_hash: Option<FixedHash>,
+    checkpoints: VecDeque<ProcessedBlock>,
+}
+
+impl TemplateRegistrationCursor {
+    /// Creates a cursor that starts scanning from the genesis block
+    pub fn new(count: u64) -> Self {
+        Self {
+            count,
+            last_processed: None,
+            next_hash: None,
+            checkpoints: VecDeque::new(),
+        }
+    }
+
+    /// Creates a cursor that resumes scanning after the given, already processed, block
+    pub fn resume_from(last_processed: ProcessedBlock, count: u64) -> Self {
+        Self {
+            count,
+            last_processed: Some(last_processed),
+            next_hash: Some(last_processed.hash),
+            checkpoints: VecDeque::from([last_processed]),
+        }
+    }
+
+    /// The last block whose registrations were returned. This can be persisted and passed to
+    /// [TemplateRegistrationCursor::resume_from] to resume scanning later.
+    pub fn last_processed(&self) -> Option<ProcessedBlock> {
+        self.last_processed
+    }
+
+    /// Fetches the template registrations in the blocks following the last processed block. At least `count`
+    /// registrations are returned unless the tip is reached first. The registrations of a block are always returned
+    /// together, so the batch may contain more than `count` registrations. An empty batch is returned if there are no
+    /// new registrations.
+    pub async fn next_batch<TClient: BaseNodeClient>(
+        &mut self,
+        client: &mut TClient,
+    ) -> Result<Vec<CodeTemplateRegistration>, BaseNodeClientError> {
+        self.rewind_if_reorged(client).await?;
+
+        let mut registrations = vec![];
+        while (registrations.len() as u64) < self.count {
+            let blocks = client.get_sidechain_utxos(self.next_hash, self.count).await?;
+            let mut has_new_blocks = false;
+            for utxos in blocks {
+                let block_info = utxos.block_info;
+                // When we have scanned to the tip we don't know the next hash, so the last processed block is fetched
+                // again and skipped
+                if self.last_processed.map(|block| block.hash) == Some(block_info.hash) {
+                    self.next_hash = block_info.next_block_hash.or(Some(block_info.hash));
+                    continue;
+                }
+                has_new_blocks = true;
+
+                registrations.extend(utxos.outputs.into_iter().filter_map(|output| {
+                    match output.features.sidechain_feature {
+                        Some(SideChainFeature::CodeTemplateRegistration(reg)) => Some(reg),
+                        _ => None,
+                    }
+                }));
+
+                let processed = ProcessedBlock {
+                    hash: block_info.hash,
+                    height: block_info.height,
+                };
+                self.last_processed = Some(processed);
+                self.next_hash = block_info.next_block_hash.or(Some(block_info.hash));
+                self.checkpoints.push_back(processed);
+                if self.checkpoints.len() > MAX_CHECKPOINTS {
+                    self.checkpoints.pop_front();
+                }
+            }
+
+            if !has_new_blocks {
+                break;
+            }
+        }
+
+        Ok(registrations)
+    }
+
+    async fn rewind_if_reorged<TClient: BaseNodeClient>(
+        &mut self,
+        client: &mut TClient,
+    ) -> Result<(), BaseNodeClientError> {
+        let Some(last_processed) = self.last_processed else {
+            return Ok(());
+        };
+        if is_on_main_chain(client, last_processed).await? {
+            return Ok(());
+        }
+
+        warn!(
+            target: LOG_TARGET,
+            "Block {} was reorged out, rewinding template registration cursor", last_processed.hash
+        );
+        while let Some(checkpoint) = self.checkpoints.pop_back() {
+            if is_on_main_chain(client, checkpoint).await? {
+                self.last_processed = Some(checkpoint);
+                self.next_hash = Some(checkpoint.hash);
+                self.checkpoints.push_back(checkpoint);
+                return Ok(());
+            }
+        }
+
+        warn!(
+            target: LOG_TARGET,
+            "No processed blocks remain on the main chain, restarting template registration cursor from genesis"
+        );
+        self.last_processed = None;
+        self.next_hash = None;
+        Ok(())
+    }
+}
+
+/// Returns true if the main chain block at the height of the processed block is that block. A header fetched by hash
+/// can still be returned after its block is reorged out, so the hash is compared against the main chain instead.
+async fn is_on_main_chain<TClient: BaseNodeClient>(
+    client: &mut TClient,
+    block: ProcessedBlock,
+) -> Result<bool, BaseNodeClientError> {
+    match client.get_blocks(vec![block.height]).await {
+        Ok(blocks) => Ok(blocks.first().is_some_and(|b| b.header.hash() == block.hash)),
+        Err(err) if err.is_not_found_error() => Ok(false),
+        Err(err) => Err(err),
+    }
+}
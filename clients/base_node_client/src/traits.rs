@@ -1,21 +1,49 @@
 //   Copyright 2023 The Tari Project
 //   SPDX-License-Identifier: BSD-3-Clause
 
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
 use async_trait::async_trait;
-use minotari_app_grpc::tari_rpc::ValidatorNodeChange;
-use tari_common_types::types::{FixedHash, PublicKey};
-use tari_core::{blocks::BlockHeader, transactions::transaction_components::CodeTemplateRegistration};
-use tari_dan_common_types::SubstateAddress;
+use tari_common_types::types::{FixedHash, PedersenCommitment, PublicKey};
+use tari_core::{
+    blocks::{Block, BlockHeader},
+    proof_of_work::{sha3x_difficulty, PowAlgorithm},
+    transactions::transaction_components::{CodeTemplateRegistration, TransactionOutput},
+};
+use tari_dan_common_types::{Epoch, SubstateAddress};
+use tokio::time::{sleep, Instant};
 
 use crate::{
+    batch::BaseNodeClientBatch,
     error::BaseNodeClientError,
-    types::{BaseLayerConsensusConstants, BaseLayerMetadata, BaseLayerValidatorNode, SideChainUtxos},
+    types::{
+        BaseLayerConsensusConstants,
+        BaseLayerMetadata,
+        BaseLayerValidatorNode,
+        FeeEstimates,
+        NetworkInfo,
+        SideChainUtxos,
+        ValidatorNodeChange,
+    },
 };
 
+/// The maximum number of heights that can be requested from [BaseNodeClient::get_shard_key_history] at once. Each
+/// height is a separate request to the base node.
+pub const MAX_SHARD_KEY_HISTORY_HEIGHTS: u64 = 1000;
+
 #[async_trait]
 pub trait BaseNodeClient: Send + Sync + Clone {
     async fn test_connection(&mut self) -> Result<(), BaseNodeClientError>;
     async fn get_tip_info(&mut self) -> Result<BaseLayerMetadata, BaseNodeClientError>;
+    /// Returns true once the base node has completed its initial sync. Until then, the tip reported by
+    /// [BaseNodeClient::get_tip_info] is the base node's local progress and not the tip of the chain.
+    async fn is_base_node_synced(&mut self) -> Result<bool, BaseNodeClientError>;
+    /// Returns the network info of the base node. This never changes, so implementations fetch it once and cache it
+    /// for the lifetime of the client.
+    async fn get_network_info(&mut self) -> Result<NetworkInfo, BaseNodeClientError>;
+    /// Returns the validator node changes in the inclusive height range `start_height..=end_height`. Returns
+    /// [BaseNodeClientError::BadRequest] if `start_height` is greater than `end_height`. Changes without a
+    /// registration are skipped.
     async fn get_validator_node_changes(
         &mut self,
         start_height: u64,
@@ -23,6 +51,13 @@ pub trait BaseNodeClient: Send + Sync + Clone {
         sidechain_id: Option<&PublicKey>,
     ) -> Result<Vec<ValidatorNodeChange>, BaseNodeClientError>;
     async fn get_validator_nodes(&mut self, height: u64) -> Result<Vec<BaseLayerValidatorNode>, BaseNodeClientError>;
+    /// Returns the number of active validator nodes at the given height without parsing each validator node
+    async fn get_active_validator_nodes_count(&mut self, height: u64) -> Result<usize, BaseNodeClientError>;
+    async fn get_validator_nodes_for_sidechain(
+        &mut self,
+        height: u64,
+        sidechain_id: &PublicKey,
+    ) -> Result<Vec<BaseLayerValidatorNode>, BaseNodeClientError>;
     async fn get_shard_key(
         &mut self,
         height: u64,
@@ -33,6 +68,9 @@ pub trait BaseNodeClient: Send + Sync + Clone {
         start_hash: Option<FixedHash>,
         count: u64,
     ) -> Result<Vec<CodeTemplateRegistration>, BaseNodeClientError>;
+    /// Fetches the blocks at the given heights. Returns [BaseNodeClientError::Pruned] if any of the blocks are no
+    /// longer available because the base node has pruned them.
+    async fn get_blocks(&mut self, heights: Vec<u64>) -> Result<Vec<Block>, BaseNodeClientError>;
     async fn get_header_by_hash(&mut self, block_hash: FixedHash) -> Result<BlockHeader, BaseNodeClientError>;
     async fn get_consensus_constants(&mut self, tip: u64) -> Result<BaseLayerConsensusConstants, BaseNodeClientError>;
     async fn get_sidechain_utxos(
@@ -40,4 +78,159 @@ pub trait BaseNodeClient: Send + Sync + Clone {
         start_hash: Option<FixedHash>,
         count: u64,
     ) -> Result<Vec<SideChainUtxos>, BaseNodeClientError>;
+    async fn get_utxo_by_commitment(
+        &mut self,
+        commitment: &PedersenCommitment,
+    ) -> Result<Option<TransactionOutput>, BaseNodeClientError>;
+    /// Returns low, medium and high fee-per-gram estimates based on the transactions currently in the mempool
+    async fn get_mempool_fee_estimates(&mut self) -> Result<FeeEstimates, BaseNodeClientError>;
+
+    /// Returns a builder that issues several reads concurrently, see [BaseNodeClientBatch]
+    fn batch(&self) -> BaseNodeClientBatch<'_, Self> {
+        BaseNodeClientBatch::new(self)
+    }
+
+    /// Returns the validator nodes at the first base layer height of `epoch`, given the epoch length in blocks from
+    /// the base layer consensus constants. Returns [BaseNodeClientError::BadRequest] if that height overflows.
+    async fn get_validator_nodes_for_epoch(
+        &mut self,
+        epoch: Epoch,
+        epoch_length: u64,
+    ) -> Result<Vec<BaseLayerValidatorNode>, BaseNodeClientError> {
+        let height = epoch
+            .as_u64()
+            .checked_mul(epoch_length)
+            .ok_or_else(|| BaseNodeClientError::BadRequest {
+                message: format!(
+                    "Invalid epoch: the first height of epoch {} with epoch length {epoch_length} overflows",
+                    epoch.as_u64()
+                ),
+            })?;
+        self.get_validator_nodes(height).await
+    }
+
+    /// Fetches the headers with the given hashes, e.g. to walk the ancestry of a block when detecting reorgs. The
+    /// lookups are issued concurrently, each on its own clone of the client, so this takes about as long as a single
+    /// lookup. The headers are returned in the same order as `hashes`, and an error is returned if any lookup fails,
+    /// including when a hash is not found.
+    async fn get_headers_by_hashes(&mut self, hashes: &[FixedHash]) -> Result<Vec<BlockHeader>, BaseNodeClientError> {
+        let lookups = hashes.iter().map(|hash| {
+            let mut client = self.clone();
+            let hash = *hash;
+            async move { client.get_header_by_hash(hash).await }
+        });
+        futures::future::try_join_all(lookups).await
+    }
+
+    /// Returns the shard key of the validator node with `public_key` at each height in the inclusive range
+    /// `start_height..=end_height`, or None at heights where it is not registered. This is useful for investigating
+    /// why a validator node was assigned to a committee. Returns [BaseNodeClientError::BadRequest] if `start_height` is
+    /// greater than `end_height` or if the range spans more than [MAX_SHARD_KEY_HISTORY_HEIGHTS] heights.
+    async fn get_shard_key_history(
+        &mut self,
+        public_key: &PublicKey,
+        start_height: u64,
+        end_height: u64,
+    ) -> Result<Vec<(u64, Option<SubstateAddress>)>, BaseNodeClientError> {
+        if start_height > end_height {
+            return Err(BaseNodeClientError::BadRequest {
+                message: format!(
+                    "Invalid shard key history range: start height {start_height} is greater than end height \
+                     {end_height}"
+                ),
+            });
+        }
+        if end_height - start_height >= MAX_SHARD_KEY_HISTORY_HEIGHTS {
+            return Err(BaseNodeClientError::BadRequest {
+                message: format!(
+                    "Invalid shard key history range: {start_height}..={end_height} spans more than \
+                     {MAX_SHARD_KEY_HISTORY_HEIGHTS} heights"
+                ),
+            });
+        }
+        // The base node has no batch request for shard keys, so each height is requested separately
+        let mut history = Vec::new();
+        for height in start_height..=end_height {
+            let shard_key = self.get_shard_key(height, public_key).await?;
+            history.push((height, shard_key));
+        }
+        Ok(history)
+    }
+
+    /// Checks that the proof of work of `header` meets `target_difficulty`, so that headers returned by the base node
+    /// can be checked independently of it, e.g. to cross-check multiple base nodes. Only SHA3x proof of work can be
+    /// verified without a RandomX VM, so [BaseNodeClientError::UnverifiableProofOfWork] is returned for other
+    /// algorithms.
+    fn verify_header_pow(&self, header: &BlockHeader, target_difficulty: u64) -> Result<(), BaseNodeClientError> {
+        let hash = header.hash();
+        let achieved = match header.pow.pow_algo {
+            PowAlgorithm::Sha3x => sha3x_difficulty(header)
+                .map_err(|e| BaseNodeClientError::UnverifiableProofOfWork {
+                    hash,
+                    details: e.to_string(),
+                })?
+                .as_u64(),
+            pow_algo => {
+                return Err(BaseNodeClientError::UnverifiableProofOfWork {
+                    hash,
+                    details: format!("{pow_algo:?} proof of work cannot be verified without a RandomX VM"),
+                })
+            },
+        };
+        if achieved < target_difficulty {
+            return Err(BaseNodeClientError::InsufficientProofOfWork {
+                hash,
+                achieved,
+                target: target_difficulty,
+            });
+        }
+        Ok(())
+    }
+
+    /// Fetches the tip info and returns [BaseNodeClientError::StaleTip] if the tip block is older than `max_age`,
+    /// which indicates that the base node is not keeping up with the chain.
+    async fn get_tip_info_fresh(&mut self, max_age: Duration) -> Result<BaseLayerMetadata, BaseNodeClientError> {
+        let tip = self.get_tip_info().await?;
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+        let age = now.saturating_sub(Duration::from_secs(tip.tip_timestamp));
+        if age > max_age {
+            return Err(BaseNodeClientError::StaleTip {
+                tip_height: tip.height_of_longest_chain,
+                age,
+                max_age,
+            });
+        }
+        Ok(tip)
+    }
+
+    /// Polls the tip info every `poll_interval` until the base node is synced and the tip height reaches `target`.
+    /// Returns [BaseNodeClientError::NotSynced] if the base node has not completed its initial sync within `timeout`,
+    /// or [BaseNodeClientError::Timeout] if the target height is not reached within `timeout`.
+    async fn wait_for_tip_height(
+        &mut self,
+        target: u64,
+        poll_interval: Duration,
+        timeout: Duration,
+    ) -> Result<BaseLayerMetadata, BaseNodeClientError> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            // The tip of a syncing base node is not the tip of the chain, so it is not acted on
+            let tip = self.get_tip_info().await?;
+            if tip.initial_sync_achieved && tip.height_of_longest_chain >= target {
+                return Ok(tip);
+            }
+            if Instant::now() + poll_interval > deadline {
+                if !tip.initial_sync_achieved {
+                    return Err(BaseNodeClientError::NotSynced {
+                        tip_height: tip.height_of_longest_chain,
+                    });
+                }
+                return Err(BaseNodeClientError::Timeout {
+                    target_height: target,
+                    tip_height: tip.height_of_longest_chain,
+                });
+            }
+            sleep(poll_interval).await;
+        }
+    }
 }
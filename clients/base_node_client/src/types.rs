@@ -1,33 +1,93 @@
 //   Copyright 2023 The Tari Project
 //   SPDX-License-Identifier: BSD-3-Clause
 
+use minotari_app_grpc::tari_rpc as grpc;
 use serde::{Deserialize, Serialize};
 use tari_common_types::types::{FixedHash, PublicKey};
-use tari_core::transactions::{tari_amount::MicroMinotari, transaction_components::TransactionOutput};
+use tari_core::transactions::{
+    tari_amount::MicroMinotari,
+    transaction_components::{TransactionOutput, ValidatorNodeRegistration},
+};
 use tari_dan_common_types::{Epoch, SubstateAddress};
+use tari_utilities::hex::Hex;
 #[cfg(feature = "ts")]
 use ts_rs::TS;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BaseLayerMetadata {
     pub height_of_longest_chain: u64,
     pub tip_hash: FixedHash,
+    /// The timestamp of the tip block in seconds since the unix epoch
+    pub tip_timestamp: u64,
+    /// Whether the base node has completed its initial sync. Until it has, the tip is only its local sync progress and
+    /// not the tip of the chain.
+    // Defaults to true for metadata recorded before this field was added
+    #[serde(default = "return_true")]
+    pub initial_sync_achieved: bool,
+}
+
+const fn return_true() -> bool {
+    true
+}
+
+/// Immutable information about the network that a base node is on. The base node gRPC interface does not report the
+/// name of its network, so the genesis hash is used as the network identifier.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NetworkInfo {
+    /// The hash of the genesis block. This is unique to each network, so it can be compared against the expected
+    /// genesis hash to check that the base node is on the correct network.
+    pub genesis_hash: FixedHash,
+}
+
+/// Fee-per-gram estimates derived from the transactions currently in the base node mempool
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeeEstimates {
+    /// The 25th percentile fee per gram
+    pub low: MicroMinotari,
+    /// The median fee per gram
+    pub medium: MicroMinotari,
+    /// The 75th percentile fee per gram
+    pub high: MicroMinotari,
+    /// The number of mempool transactions the estimates are derived from. If this is zero, all estimates are zero and
+    /// the minimum fee is sufficient.
+    pub num_transactions: usize,
+}
+
+impl FeeEstimates {
+    /// Derives the estimates from the fee per gram of each mempool transaction
+    pub fn from_fees_per_gram(mut fees_per_gram: Vec<u64>) -> Self {
+        fees_per_gram.sort_unstable();
+        let percentile = |p: usize| {
+            if fees_per_gram.is_empty() {
+                return MicroMinotari::zero();
+            }
+            let index = (fees_per_gram.len() - 1) * p / 100;
+            MicroMinotari::from(fees_per_gram[index])
+        };
+
+        Self {
+            low: percentile(25),
+            medium: percentile(50),
+            high: percentile(75),
+            num_transactions: fees_per_gram.len(),
+        }
+    }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SideChainUtxos {
     pub block_info: BlockInfo,
     pub outputs: Vec<TransactionOutput>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BlockInfo {
     pub hash: FixedHash,
     pub height: u64,
     pub next_block_hash: Option<FixedHash>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(
     feature = "ts",
     derive(TS),
@@ -41,10 +101,57 @@ pub struct BaseLayerValidatorNode {
     pub sidechain_id: Option<PublicKey>,
 }
 
+impl BaseLayerValidatorNode {
+    /// Returns the public key as lowercase hex, for display and logging
+    pub fn public_key_hex(&self) -> String {
+        self.public_key.to_hex()
+    }
+
+    /// Returns the shard key as lowercase hex, for display and logging
+    pub fn shard_key_hex(&self) -> String {
+        self.shard_key.to_string()
+    }
+}
+
+/// A change to the validator node set in a base layer block
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ValidatorNodeChange {
+    Add {
+        public_key: PublicKey,
+        sidechain_id: Option<PublicKey>,
+        registration: ValidatorNodeRegistration,
+        /// The height at which the registration becomes active
+        activation_height: u64,
+        minimum_value_promise: MicroMinotari,
+    },
+    Remove {
+        public_key: PublicKey,
+        sidechain_id: Option<PublicKey>,
+    },
+}
+
+impl ValidatorNodeChange {
+    pub fn public_key(&self) -> &PublicKey {
+        match self {
+            Self::Add { public_key, .. } | Self::Remove { public_key, .. } => public_key,
+        }
+    }
+
+    pub fn sidechain_id(&self) -> Option<&PublicKey> {
+        match self {
+            Self::Add { sidechain_id, .. } | Self::Remove { sidechain_id, .. } => sidechain_id.as_ref(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BaseLayerConsensusConstants {
     pub epoch_length: u64,
     pub validator_node_registration_min_deposit_amount: MicroMinotari,
+    /// The number of epochs for which a validator node registration is valid after it is activated
+    // Defaults to 0 for constants persisted before this field was added
+    #[serde(default)]
+    pub validator_node_validity_period: u64,
 }
 
 impl BaseLayerConsensusConstants {
@@ -63,4 +170,91 @@ impl BaseLayerConsensusConstants {
     pub fn epoch_length(&self) -> u64 {
         self.epoch_length
     }
+
+    pub fn validator_node_validity_period(&self) -> u64 {
+        self.validator_node_validity_period
+    }
+
+    /// Returns the first height at which a registration activated at `activation_height` is no longer valid. The
+    /// activation height is the `activation_height` of the [ValidatorNodeChange::Add] that added the registration,
+    /// i.e. the start of the epoch following the one the registration was submitted in. A registration is valid for
+    /// `validator_node_validity_period` epochs from the start of the epoch it was activated in.
+    ///
+    /// Returns None if the epoch length or the validity period is 0, e.g. for constants persisted before the validity
+    /// period was added.
+    pub fn registration_expiry_height(&self, activation_height: u64) -> Option<u64> {
+        if self.epoch_length == 0 || self.validator_node_validity_period == 0 {
+            return None;
+        }
+        let activation_epoch = self.height_to_epoch(activation_height);
+        let expiry_epoch = activation_epoch
+            .as_u64()
+            .saturating_add(self.validator_node_validity_period);
+        Some(expiry_epoch.saturating_mul(self.epoch_length))
+    }
+}
+
+impl From<&grpc::ConsensusConstants> for BaseLayerConsensusConstants {
+    fn from(value: &grpc::ConsensusConstants) -> Self {
+        Self {
+            epoch_length: value.epoch_length,
+            validator_node_registration_min_deposit_amount: value.validator_node_registration_min_deposit_amount.into(),
+            validator_node_validity_period: value.validator_node_validity_period,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_derives_fee_estimates_from_percentiles() {
+        let estimates = FeeEstimates::from_fees_per_gram(vec![5, 1, 4, 2, 3]);
+        assert_eq!(estimates, FeeEstimates {
+            low: MicroMinotari::from(2),
+            medium: MicroMinotari::from(3),
+            high: MicroMinotari::from(4),
+            num_transactions: 5,
+        });
+    }
+
+    #[test]
+    fn it_derives_zero_fee_estimates_from_an_empty_mempool() {
+        let estimates = FeeEstimates::from_fees_per_gram(vec![]);
+        assert_eq!(estimates, FeeEstimates {
+            low: MicroMinotari::zero(),
+            medium: MicroMinotari::zero(),
+            high: MicroMinotari::zero(),
+            num_transactions: 0,
+        });
+    }
+
+    mod registration_expiry_height {
+        use super::*;
+
+        fn constants(epoch_length: u64, validator_node_validity_period: u64) -> BaseLayerConsensusConstants {
+            BaseLayerConsensusConstants {
+                epoch_length,
+                validator_node_registration_min_deposit_amount: MicroMinotari::zero(),
+                validator_node_validity_period,
+            }
+        }
+
+        #[test]
+        fn it_calculates_the_expiry_from_the_activation_height() {
+            let constants = constants(10, 2);
+            // Activated at the start of epoch 3, valid for epochs 3 and 4
+            assert_eq!(constants.registration_expiry_height(30), Some(50));
+            // Activation heights are epoch aligned in practice, but any height in the epoch gives the same result
+            assert_eq!(constants.registration_expiry_height(39), Some(50));
+            assert_eq!(constants.registration_expiry_height(u64::MAX), Some(u64::MAX));
+        }
+
+        #[test]
+        fn it_returns_none_without_an_epoch_length_or_validity_period() {
+            assert_eq!(constants(0, 2).registration_expiry_height(30), None);
+            assert_eq!(constants(10, 0).registration_expiry_height(30), None);
+        }
+    }
 }
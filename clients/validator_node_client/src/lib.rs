@@ -67,6 +67,13 @@ impl ValidatorNodeClient {
         self.send_request("get_epoch_manager_stats", json!({})).await
     }
 
+    pub async fn get_leader_schedule(
+        &mut self,
+        request: GetLeaderScheduleRequest,
+    ) -> Result<GetLeaderScheduleResponse, ValidatorNodeClientError> {
+        self.send_request("get_leader_schedule", request).await
+    }
+
     pub async fn get_active_templates(
         &mut self,
         request: GetTemplatesRequest,
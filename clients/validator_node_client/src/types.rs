@@ -30,7 +30,9 @@ use tari_dan_common_types::{
     committee::{Committee, CommitteeInfo},
     shard::Shard,
     Epoch,
+    NodeHeight,
     PeerAddress,
+    ShardGroup,
     SubstateAddress,
 };
 use tari_dan_storage::{
@@ -472,6 +474,31 @@ pub struct GetCommitteeResponse {
     pub committee: Committee<PeerAddress>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(
+    feature = "ts",
+    derive(TS),
+    ts(export, export_to = "../../bindings/src/types/validator-node-client/")
+)]
+pub struct GetLeaderScheduleRequest {
+    pub epoch: Epoch,
+    pub shard_group: ShardGroup,
+    pub from_height: NodeHeight,
+    pub to_height: NodeHeight,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(
+    feature = "ts",
+    derive(TS),
+    ts(export, export_to = "../../bindings/src/types/validator-node-client/")
+)]
+pub struct GetLeaderScheduleResponse {
+    /// The leader public key for each height in the requested range, ordered by height
+    #[cfg_attr(feature = "ts", ts(type = "Array<string>"))]
+    pub leaders: Vec<PublicKey>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(
     feature = "ts",
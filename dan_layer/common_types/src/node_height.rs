@@ -54,6 +54,12 @@ impl NodeHeight {
             None => None,
         }
     }
+
+    /// Returns an iterator over the heights from `start` to `end` inclusive. The iterator is empty if `start` is
+    /// greater than `end`.
+    pub fn range(start: Self, end: Self) -> impl DoubleEndedIterator<Item = Self> {
+        (start.0..=end.0).map(Self)
+    }
 }
 
 impl Add for NodeHeight {
@@ -88,3 +94,22 @@ impl Display for NodeHeight {
         write!(f, "NodeHeight({})", self.0)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn range_is_inclusive() {
+        let heights = NodeHeight::range(NodeHeight(3), NodeHeight(5)).collect::<Vec<_>>();
+        assert_eq!(heights, vec![NodeHeight(3), NodeHeight(4), NodeHeight(5)]);
+
+        let heights = NodeHeight::range(NodeHeight(3), NodeHeight(3)).collect::<Vec<_>>();
+        assert_eq!(heights, vec![NodeHeight(3)]);
+    }
+
+    #[test]
+    fn range_is_empty_if_start_is_greater_than_end() {
+        assert_eq!(NodeHeight::range(NodeHeight(4), NodeHeight(3)).count(), 0);
+    }
+}
@@ -8,7 +8,7 @@ use tari_dan_common_types::{
     DerivableFromPublicKey,
     ExtraFieldKey,
 };
-use tari_dan_storage::consensus_models::Block;
+use tari_dan_storage::consensus_models::{Block, QuorumCertificate};
 use tari_epoch_manager::EpochManagerReader;
 
 use crate::{
@@ -194,6 +194,16 @@ pub fn check_quorum_certificate<TConsensusSpec: ConsensusSpec>(
         .into());
     }
 
+    check_qc_signatures::<TConsensusSpec>(qc, committee, committee_info, vote_signing_service)
+}
+
+/// Checks that the QC is signed by at least a quorum of `committee` and that every signature is valid
+pub fn check_qc_signatures<TConsensusSpec: ConsensusSpec>(
+    qc: &QuorumCertificate,
+    committee: &Committee<TConsensusSpec::Addr>,
+    committee_info: &CommitteeInfo,
+    vote_signing_service: &TConsensusSpec::SignatureService,
+) -> Result<(), HotStuffError> {
     if qc.signatures().is_empty() {
         return Err(ProposalValidationError::QuorumWasNotReached { qc: *qc.id() }.into());
     }
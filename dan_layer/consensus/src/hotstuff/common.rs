@@ -36,12 +36,13 @@ use tari_dan_storage::{
     StorageError,
 };
 use tari_engine_types::substate::SubstateDiff;
-use tari_state_tree::{Hash, JellyfishMerkleTree, StateTreeError};
+use tari_state_tree::{Hash, JellyfishMerkleTree, StateTreeError, SubstateTreeChange, Version};
 
 use crate::{
     hotstuff::{
         substate_store::{ShardScopedTreeStoreReader, ShardedStateTree},
         HotStuffError,
+        ProposalValidationError,
     },
     traits::LeaderStrategy,
 };
@@ -65,8 +66,7 @@ pub fn calculate_last_dummy_block<TAddr: NodeAddressable, TLeaderStrategy: Leade
     parent_base_layer_block_height: u64,
     parent_base_layer_block_hash: FixedHash,
 ) -> Option<LeafBlock> {
-    let mut dummy = None;
-    with_dummy_blocks(
+    calculate_last_dummy_block_with_count(
         from_height,
         new_height,
         network,
@@ -80,16 +80,14 @@ pub fn calculate_last_dummy_block<TAddr: NodeAddressable, TLeaderStrategy: Leade
         parent_timestamp,
         parent_base_layer_block_height,
         parent_base_layer_block_hash,
-        |dummy_block| {
-            dummy = Some(dummy_block.as_leaf_block());
-            ControlFlow::Continue(())
-        },
-    );
-
-    dummy
+    )
+    .map(|(dummy, _)| dummy)
 }
 
-fn calculate_dummy_blocks<TAddr: NodeAddressable, TLeaderStrategy: LeaderStrategy<TAddr>>(
+/// Calculates the dummy blocks required to reach the new height and returns the last dummy block (parent for next
+/// proposal) along with the number of dummy blocks generated. Generates dummy blocks from from_height to new_height
+/// _exclusive_. Returns None if no dummy blocks are generated, including if the local committee is empty.
+pub fn calculate_last_dummy_block_with_count<TAddr: NodeAddressable, TLeaderStrategy: LeaderStrategy<TAddr>>(
     from_height: NodeHeight,
     new_height: NodeHeight,
     network: Network,
@@ -97,15 +95,15 @@ fn calculate_dummy_blocks<TAddr: NodeAddressable, TLeaderStrategy: LeaderStrateg
     shard_group: ShardGroup,
     parent_block_id: BlockId,
     qc: &QuorumCertificate,
-    expected_parent_block_id: &BlockId,
     parent_merkle_root: FixedHash,
     leader_strategy: &TLeaderStrategy,
     local_committee: &Committee<TAddr>,
     parent_timestamp: u64,
     parent_base_layer_block_height: u64,
     parent_base_layer_block_hash: FixedHash,
-) -> Vec<Block> {
-    let mut dummies = Vec::new();
+) -> Option<(LeafBlock, usize)> {
+    let mut dummy = None;
+    let mut count = 0;
     with_dummy_blocks(
         from_height,
         new_height,
@@ -121,27 +119,132 @@ fn calculate_dummy_blocks<TAddr: NodeAddressable, TLeaderStrategy: LeaderStrateg
         parent_base_layer_block_height,
         parent_base_layer_block_hash,
         |dummy_block| {
-            if dummy_block.id() == expected_parent_block_id {
-                dummies.push(dummy_block);
-                ControlFlow::Break(())
-            } else {
-                dummies.push(dummy_block);
-                ControlFlow::Continue(())
-            }
+            dummy = Some(dummy_block.as_leaf_block());
+            count += 1;
+            ControlFlow::Continue(())
         },
     );
 
-    dummies
+    dummy.map(|dummy| (dummy, count))
 }
 
-/// Calculates the dummy block required to reach the new height
+/// Calculates the dummy block required to reach the new height. Returns an error if the candidate and justify blocks
+/// are not in the same epoch and shard group.
 pub fn calculate_dummy_blocks_from_justify<TAddr: NodeAddressable, TLeaderStrategy: LeaderStrategy<TAddr>>(
     candidate_block: &Block,
     justify_block: &Block,
     leader_strategy: &TLeaderStrategy,
     local_committee: &Committee<TAddr>,
-) -> Vec<Block> {
-    calculate_dummy_blocks(
+) -> Result<Vec<Block>, ProposalValidationError> {
+    let mut dummies = Vec::new();
+    with_dummy_blocks_from_justify(
+        candidate_block,
+        justify_block,
+        leader_strategy,
+        local_committee,
+        |dummy_block| {
+            dummies.push(dummy_block);
+            ControlFlow::Continue(())
+        },
+    )?;
+
+    Ok(dummies)
+}
+
+/// Calculates the dummy blocks required to reach the new height without keeping them in memory, and returns the last
+/// dummy block along with the number of dummy blocks generated. Returns an error if the candidate and justify blocks
+/// are not in the same epoch and shard group.
+pub fn calculate_last_dummy_block_from_justify<TAddr: NodeAddressable, TLeaderStrategy: LeaderStrategy<TAddr>>(
+    candidate_block: &Block,
+    justify_block: &Block,
+    leader_strategy: &TLeaderStrategy,
+    local_committee: &Committee<TAddr>,
+) -> Result<Option<(LeafBlock, usize)>, ProposalValidationError> {
+    let mut dummy = None;
+    let mut count = 0;
+    with_dummy_blocks_from_justify(
+        candidate_block,
+        justify_block,
+        leader_strategy,
+        local_committee,
+        |dummy_block| {
+            dummy = Some(dummy_block.as_leaf_block());
+            count += 1;
+            ControlFlow::Continue(())
+        },
+    )?;
+
+    Ok(dummy.map(|dummy| (dummy, count)))
+}
+
+/// Calculates the dummy blocks required to reach the new height and saves each one in the given transaction as it is
+/// calculated, so that a long run of dummy blocks is never held in memory. Returns the number of dummy blocks saved, or
+/// an error if the candidate and justify blocks are not in the same epoch and shard group.
+pub fn save_dummy_blocks_from_justify<TTx, TAddr, TLeaderStrategy>(
+    tx: &mut TTx,
+    candidate_block: &Block,
+    justify_block: &Block,
+    leader_strategy: &TLeaderStrategy,
+    local_committee: &Committee<TAddr>,
+) -> Result<usize, HotStuffError>
+where
+    TTx: StateStoreWriteTransaction + Deref,
+    TTx::Target: StateStoreReadTransaction,
+    TAddr: NodeAddressable,
+    TLeaderStrategy: LeaderStrategy<TAddr>,
+{
+    let mut count = 0;
+    let mut result = Ok(());
+    with_dummy_blocks_from_justify(
+        candidate_block,
+        justify_block,
+        leader_strategy,
+        local_committee,
+        |dummy_block| match dummy_block.save(tx) {
+            Ok(_) => {
+                count += 1;
+                ControlFlow::Continue(())
+            },
+            Err(err) => {
+                result = Err(err);
+                ControlFlow::Break(())
+            },
+        },
+    )?;
+    result?;
+
+    Ok(count)
+}
+
+/// Calls the callback for each dummy block between the justify block and the candidate block, stopping at the
+/// candidate block's parent. The dummy blocks link the justify block to the candidate block, so both must be in the
+/// same epoch and shard group.
+fn with_dummy_blocks_from_justify<TAddr, TLeaderStrategy, F>(
+    candidate_block: &Block,
+    justify_block: &Block,
+    leader_strategy: &TLeaderStrategy,
+    local_committee: &Committee<TAddr>,
+    mut callback: F,
+) -> Result<(), ProposalValidationError>
+where
+    TAddr: NodeAddressable,
+    TLeaderStrategy: LeaderStrategy<TAddr>,
+    F: FnMut(Block) -> ControlFlow<()>,
+{
+    if candidate_block.epoch() != justify_block.epoch() || candidate_block.shard_group() != justify_block.shard_group()
+    {
+        return Err(ProposalValidationError::JustifyBlockMismatch {
+            candidate_block_id: *candidate_block.id(),
+            candidate_epoch: candidate_block.epoch(),
+            candidate_shard_group: candidate_block.shard_group(),
+            justify_block_id: *justify_block.id(),
+            justify_epoch: justify_block.epoch(),
+            justify_shard_group: justify_block.shard_group(),
+        });
+    }
+
+    let expected_parent_block_id = candidate_block.parent();
+    with_dummy_blocks(
         justify_block.height(),
         candidate_block.height(),
         candidate_block.network(),
@@ -149,18 +252,27 @@ pub fn calculate_dummy_blocks_from_justify<TAddr: NodeAddressable, TLeaderStrate
         candidate_block.shard_group(),
         *candidate_block.justify().block_id(),
         candidate_block.justify(),
-        candidate_block.parent(),
         *justify_block.state_merkle_root(),
         leader_strategy,
         local_committee,
         justify_block.timestamp(),
         justify_block.base_layer_block_height(),
         *justify_block.base_layer_block_hash(),
-    )
+        |dummy_block| {
+            let is_expected_parent = dummy_block.id() == expected_parent_block_id;
+            if callback(dummy_block).is_break() || is_expected_parent {
+                ControlFlow::Break(())
+            } else {
+                ControlFlow::Continue(())
+            }
+        },
+    );
+
+    Ok(())
 }
 
 fn with_dummy_blocks<TAddr, TLeaderStrategy, F>(
-    mut current_height: NodeHeight,
+    current_height: NodeHeight,
     new_height: NodeHeight,
     network: Network,
     epoch: Epoch,
@@ -189,6 +301,16 @@ fn with_dummy_blocks<TAddr, TLeaderStrategy, F>(
         return;
     }
 
+    // The leader strategy cannot choose a leader from an empty committee
+    if local_committee.is_empty() {
+        error!(
+            target: LOG_TARGET,
+            "BUG: 🍼 no dummy blocks to calculate. local committee in epoch {} is empty",
+            epoch,
+        );
+        return;
+    }
+
     debug!(
         target: LOG_TARGET,
         "🍼 calculating dummy blocks in epoch {} from {} to {}",
@@ -196,17 +318,14 @@ fn with_dummy_blocks<TAddr, TLeaderStrategy, F>(
         current_height,
         new_height,
     );
-    loop {
-        current_height += NodeHeight(1);
-        if current_height == new_height {
-            break;
-        }
-        let (_, leader) = leader_strategy.get_leader(local_committee, current_height);
+    // The dummy blocks are the heights strictly between the current and new heights
+    for height in NodeHeight::range(current_height + NodeHeight(1), new_height - NodeHeight(1)) {
+        let (_, leader) = leader_strategy.get_leader(local_committee, height);
         let dummy_header = BlockHeader::dummy_block(
             network,
             parent_block_id,
             leader.clone(),
-            current_height,
+            height,
             *qc.id(),
             epoch,
             shard_group,
@@ -235,16 +354,58 @@ pub fn calculate_state_merkle_root<'a, TTx: StateStoreReadTransaction, I: IntoIt
     pending_tree_diffs: HashMap<Shard, Vec<PendingShardStateTreeDiff>>,
     changes: I,
 ) -> Result<(Hash, IndexMap<Shard, VersionedStateHashTreeDiff>), StateTreeError> {
-    let mut change_map = IndexMap::new();
+    let change_map = group_changes_by_shard(changes);
+    let mut sharded_tree = ShardedStateTree::new(tx).with_pending_diffs(pending_tree_diffs);
+    let root_hash = sharded_tree.put_substate_tree_changes(shard_group, change_map)?;
+
+    Ok((root_hash, sharded_tree.into_shard_tree_diffs()))
+}
+
+/// Calculates the state merkle root like [calculate_state_merkle_root], and additionally returns the state root of
+/// each changed shard at every version, i.e. after each pending diff is applied and after the new changes, in version
+/// order. Publishing these roots allows an auditor to reconstruct the progression of the state roots.
+pub fn calculate_state_merkle_root_with_progression<
+    'a,
+    TTx: StateStoreReadTransaction,
+    I: IntoIterator<Item = &'a SubstateChange>,
+>(
+    tx: &TTx,
+    shard_group: ShardGroup,
+    pending_tree_diffs: HashMap<Shard, Vec<PendingShardStateTreeDiff>>,
+    changes: I,
+) -> Result<StateMerkleRootProgression, StateTreeError> {
+    let change_map = group_changes_by_shard(changes);
+    let mut sharded_tree = ShardedStateTree::new(tx)
+        .with_pending_diffs(pending_tree_diffs)
+        .with_root_progression();
+    let root_hash = sharded_tree.put_substate_tree_changes(shard_group, change_map)?;
+    let shard_roots = sharded_tree.take_root_progression().unwrap_or_default();
+
+    Ok(StateMerkleRootProgression {
+        root_hash,
+        shard_tree_diffs: sharded_tree.into_shard_tree_diffs(),
+        shard_roots,
+    })
+}
 
+fn group_changes_by_shard<'a, I: IntoIterator<Item = &'a SubstateChange>>(
+    changes: I,
+) -> IndexMap<Shard, Vec<SubstateTreeChange>> {
+    let mut change_map = IndexMap::new();
     changes.into_iter().for_each(|ch| {
-        // Group by shard
         change_map.entry(ch.shard()).or_insert_with(Vec::new).push(ch.into());
     });
-    let mut sharded_tree = ShardedStateTree::new(tx).with_pending_diffs(pending_tree_diffs);
-    let root_hash = sharded_tree.put_substate_tree_changes(shard_group, change_map)?;
+    change_map
+}
 
-    Ok((root_hash, sharded_tree.into_shard_tree_diffs()))
+/// The result of [calculate_state_merkle_root_with_progression]
+#[derive(Debug, Clone)]
+pub struct StateMerkleRootProgression {
+    /// The state merkle root of the shard group after the changes
+    pub root_hash: Hash,
+    pub shard_tree_diffs: IndexMap<Shard, VersionedStateHashTreeDiff>,
+    /// The state root of each changed shard at each version, in version order
+    pub shard_roots: IndexMap<Shard, Vec<(Version, Hash)>>,
 }
 
 pub(crate) fn create_epoch_checkpoint<TTx>(
@@ -1,6 +1,8 @@
 //   Copyright 2023 The Tari Project
 //   SPDX-License-Identifier: BSD-3-Clause
 
+use std::time::Duration;
+
 use tari_common::configuration::Network;
 use tari_crypto::ristretto::RistrettoPublicKey;
 
@@ -11,4 +13,21 @@ pub struct HotstuffConfig {
     pub network: Network,
     pub sidechain_id: Option<RistrettoPublicKey>,
     pub consensus_constants: ConsensusConstants,
+    /// The minimum delay between proposals sent to a peer that is catching up. If None, proposals are sent as fast
+    /// as the outbound messaging allows.
+    pub sync_response_message_interval: Option<Duration>,
+    /// The maximum time spent serving a single catch-up sync request. Any remaining blocks are not sent once this
+    /// has elapsed. If None, the request is served to completion.
+    pub sync_response_timeout: Option<Duration>,
+    /// The maximum time to wait for a single message to be sent to a peer that is catching up. If a send takes
+    /// longer, the peer is considered unresponsive and the sync response is aborted. If None, sends are not timed out.
+    pub sync_response_send_timeout: Option<Duration>,
+    /// The maximum number of blocks to request from a peer in a single catch-up page. Further pages are requested
+    /// until we have caught up to the peer's leaf. If 0, a single unpaginated request is made.
+    pub sync_request_max_blocks: u64,
+    /// If true, the high QC of a catch-up sync request must be for our shard group, signed by a quorum of our
+    /// committee in the current epoch and for a block in our store before any blocks are served, so that forged
+    /// requests cannot be used to probe our block store. Requests from older peers that do not send the certificate of
+    /// their high QC are rejected. If false, requests are served without verification.
+    pub sync_request_verify_qc: bool,
 }
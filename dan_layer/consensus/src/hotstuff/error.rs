@@ -3,7 +3,7 @@
 
 use tari_common_types::types::FixedHash;
 use tari_crypto::ristretto::RistrettoPublicKey;
-use tari_dan_common_types::{Epoch, NodeHeight, VersionedSubstateIdError};
+use tari_dan_common_types::{Epoch, NodeHeight, ShardGroup, VersionedSubstateIdError};
 use tari_dan_storage::{
     consensus_models::{BlockError, BlockId, LeafBlock, LockedBlock, QcId, TransactionPoolError},
     StorageError,
@@ -261,4 +261,17 @@ pub enum ProposalValidationError {
     DummyBlockWithSignature { block_id: BlockId },
     #[error("Dummy block {block_id} includes commands")]
     DummyBlockWithCommands { block_id: BlockId },
+    #[error(
+        "Justify block {justify_block_id} (epoch {justify_epoch}, shard group {justify_shard_group}) is not in the \
+         same epoch and shard group as candidate block {candidate_block_id} (epoch {candidate_epoch}, shard group \
+         {candidate_shard_group})"
+    )]
+    JustifyBlockMismatch {
+        candidate_block_id: BlockId,
+        candidate_epoch: Epoch,
+        candidate_shard_group: ShardGroup,
+        justify_block_id: BlockId,
+        justify_epoch: Epoch,
+        justify_shard_group: ShardGroup,
+    },
 }
@@ -37,5 +37,6 @@ pub use config::HotstuffConfig;
 pub use current_view::*;
 pub use error::*;
 pub use event::*;
+pub use on_catch_up_sync_request::check_sync_request_high_qc;
 pub use state_machine::*;
 pub use worker::*;
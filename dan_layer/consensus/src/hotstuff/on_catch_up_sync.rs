@@ -1,13 +1,18 @@
 //    Copyright 2024 The Tari Project
 //    SPDX-License-Identifier: BSD-3-Clause
 
+use std::cmp;
+
 use log::{info, warn};
-use tari_dan_common_types::Epoch;
-use tari_dan_storage::{consensus_models::HighQc, StateStore};
+use tari_dan_common_types::{Epoch, NodeHeight};
+use tari_dan_storage::{
+    consensus_models::{Block, HighQc},
+    StateStore,
+};
 
 use crate::{
     hotstuff::{pacemaker_handle::PaceMakerHandle, HotStuffError},
-    messages::{HotstuffMessage, SyncRequestMessage},
+    messages::{HotstuffMessage, SyncRequestMessage, SyncResponseMessage},
     traits::{ConsensusSpec, OutboundMessaging},
 };
 
@@ -17,6 +22,8 @@ pub struct OnCatchUpSync<TConsensusSpec: ConsensusSpec> {
     store: TConsensusSpec::StateStore,
     pacemaker: PaceMakerHandle,
     outbound_messaging: TConsensusSpec::OutboundMessaging,
+    max_blocks: u64,
+    in_progress: CatchUpInProgress<TConsensusSpec::Addr>,
 }
 
 impl<TConsensusSpec: ConsensusSpec> OnCatchUpSync<TConsensusSpec> {
@@ -24,15 +31,20 @@ impl<TConsensusSpec: ConsensusSpec> OnCatchUpSync<TConsensusSpec> {
         store: TConsensusSpec::StateStore,
         pacemaker: PaceMakerHandle,
         outbound_messaging: TConsensusSpec::OutboundMessaging,
+        max_blocks: u64,
     ) -> Self {
         Self {
             store,
             pacemaker,
             outbound_messaging,
+            max_blocks,
+            in_progress: CatchUpInProgress::new(),
         }
     }
 
     pub async fn request_sync(&mut self, epoch: Epoch, from: TConsensusSpec::Addr) -> Result<(), HotStuffError> {
+        // A new catch-up replaces any catch-up that is in progress, including one whose peer stopped responding
+        self.in_progress.clear();
         let high_qc = self.store.with_read_tx(|tx| HighQc::get(tx, epoch))?;
         info!(
             target: LOG_TARGET,
@@ -48,12 +60,88 @@ impl<TConsensusSpec: ConsensusSpec> OnCatchUpSync<TConsensusSpec> {
             .reset_view(epoch, high_qc.block_height(), high_qc.block_height)
             .await?;
 
+        self.send_sync_request(epoch, from, NodeHeight::zero()).await
+    }
+
+    /// Handles the end of a page of a paginated catch-up. If the peer has more blocks, the next page is requested from
+    /// our current high QC, so blocks the peer added while we were syncing are included. If the peer is behind us, it
+    /// responds with only its high QC and the catch-up ends.
+    pub async fn handle_sync_response(
+        &mut self,
+        epoch: Epoch,
+        from: TConsensusSpec::Addr,
+        msg: SyncResponseMessage,
+    ) -> Result<(), HotStuffError> {
+        if let Some(peer_high_qc) = msg.high_qc {
+            info!(
+                target: LOG_TARGET,
+                "🌐 Peer {} has nothing newer to offer (peer high QC: {}). Stopping catch-up.", from, peer_high_qc
+            );
+            self.in_progress.clear_for(&from);
+            return Ok(());
+        }
+
+        match self.in_progress.on_page_end(&from, msg.next_height) {
+            PageEnd::Unrequested => {
+                warn!(target: LOG_TARGET, "⚠️ Ignoring unrequested SyncResponse from {}", from);
+                Ok(())
+            },
+            PageEnd::WrongPeer { syncing_from } => {
+                warn!(
+                    target: LOG_TARGET,
+                    "⚠️ Ignoring SyncResponse from {} because we are syncing from {}", from, syncing_from
+                );
+                Ok(())
+            },
+            PageEnd::Complete => {
+                info!(target: LOG_TARGET, "🌐 Catch-up from {} complete", from);
+                Ok(())
+            },
+            PageEnd::NoProgress { next_height } => {
+                warn!(
+                    target: LOG_TARGET,
+                    "⚠️ Catch-up from {} made no progress (peer next height {}). Stopping catch-up.",
+                    from,
+                    next_height
+                );
+                Ok(())
+            },
+            PageEnd::RequestNext { next_height } => {
+                info!(
+                    target: LOG_TARGET,
+                    "🌐 Requesting next catch-up page from {} (peer next height {})", from, next_height
+                );
+                self.send_sync_request(epoch, from, next_height).await
+            },
+        }
+    }
+
+    async fn send_sync_request(
+        &mut self,
+        epoch: Epoch,
+        from: TConsensusSpec::Addr,
+        last_next_height: NodeHeight,
+    ) -> Result<(), HotStuffError> {
+        let (high_qc, has_high_qc_block) = self.store.with_read_tx(|tx| {
+            let high_qc = HighQc::get(tx, epoch)?.get_quorum_certificate(tx)?;
+            let has_high_qc_block = Block::record_exists(tx, high_qc.block_id())?;
+            Ok::<_, HotStuffError>((high_qc, has_high_qc_block))
+        })?;
+
+        let requested_height = high_qc.block_height();
         // Request a catch-up
         if self
             .outbound_messaging
             .send(
-                from,
-                HotstuffMessage::CatchUpSyncRequest(SyncRequestMessage { high_qc }),
+                from.clone(),
+                HotstuffMessage::CatchUpSyncRequest(SyncRequestMessage {
+                    high_qc: high_qc.as_high_qc(),
+                    high_qc_cert: Some(high_qc),
+                    accepts_compressed_proposals: true,
+                    // If we do not even have the block for our high QC, we're starting from scratch and need genesis
+                    include_genesis: !has_high_qc_block,
+                    max_blocks: self.max_blocks,
+                }),
             )
             .await
             .is_err()
@@ -62,6 +150,155 @@ impl<TConsensusSpec: ConsensusSpec> OnCatchUpSync<TConsensusSpec> {
             return Ok(());
         }
 
+        if self.max_blocks > 0 {
+            self.in_progress
+                .start(from, cmp::max(requested_height, last_next_height));
+        }
+
         Ok(())
     }
 }
+
+/// The outcome of receiving the end of a catch-up page
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum PageEnd<TAddr> {
+    /// We did not request a page
+    Unrequested,
+    /// We requested a page from another peer
+    WrongPeer { syncing_from: TAddr },
+    /// The peer has no more blocks
+    Complete,
+    /// The peer did not advance past the previous page, so requesting another page would not make progress
+    NoProgress { next_height: NodeHeight },
+    /// The peer has more blocks from the next height
+    RequestNext { next_height: NodeHeight },
+}
+
+/// The peer we are currently syncing from and the height that the peer's next page must start above.
+///
+/// Progress is measured using the next heights reported by the peer rather than our own high QC. The proposals in a
+/// page are processed asynchronously, so our high QC may not have advanced by the time the end of the page arrives.
+#[derive(Debug, Clone)]
+struct CatchUpInProgress<TAddr> {
+    current: Option<(TAddr, NodeHeight)>,
+}
+
+impl<TAddr: PartialEq + Clone> CatchUpInProgress<TAddr> {
+    fn new() -> Self {
+        Self { current: None }
+    }
+
+    fn start(&mut self, peer: TAddr, min_next_height: NodeHeight) {
+        self.current = Some((peer, min_next_height));
+    }
+
+    fn clear(&mut self) {
+        self.current = None;
+    }
+
+    fn clear_for(&mut self, peer: &TAddr) {
+        if self.current.as_ref().is_some_and(|(p, _)| p == peer) {
+            self.current = None;
+        }
+    }
+
+    /// Updates the catch-up state for the end of a page from `from`. The catch-up is no longer in progress unless
+    /// the page was from a different peer, in which case it is ignored.
+    fn on_page_end(&mut self, from: &TAddr, next_height: Option<NodeHeight>) -> PageEnd<TAddr> {
+        let Some((peer, min_next_height)) = self.current.take() else {
+            return PageEnd::Unrequested;
+        };
+        if peer != *from {
+            let syncing_from = peer.clone();
+            self.current = Some((peer, min_next_height));
+            return PageEnd::WrongPeer { syncing_from };
+        }
+
+        match next_height {
+            None => PageEnd::Complete,
+            Some(next_height) if next_height <= min_next_height => PageEnd::NoProgress { next_height },
+            Some(next_height) => PageEnd::RequestNext { next_height },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_requests_pages_while_the_peer_makes_progress() {
+        let mut in_progress = CatchUpInProgress::new();
+        in_progress.start("a", NodeHeight(10));
+
+        assert_eq!(
+            in_progress.on_page_end(&"a", Some(NodeHeight(15))),
+            PageEnd::RequestNext {
+                next_height: NodeHeight(15)
+            }
+        );
+        // The next page was requested while our high QC is still at 10
+        in_progress.start("a", NodeHeight(15));
+
+        assert_eq!(
+            in_progress.on_page_end(&"a", Some(NodeHeight(20))),
+            PageEnd::RequestNext {
+                next_height: NodeHeight(20)
+            }
+        );
+        in_progress.start("a", NodeHeight(20));
+
+        assert_eq!(in_progress.on_page_end(&"a", None), PageEnd::Complete);
+        assert_eq!(in_progress.on_page_end(&"a", None), PageEnd::Unrequested);
+    }
+
+    #[test]
+    fn it_stops_if_the_peer_repeats_a_page() {
+        let mut in_progress = CatchUpInProgress::new();
+        in_progress.start("a", NodeHeight(15));
+
+        assert_eq!(
+            in_progress.on_page_end(&"a", Some(NodeHeight(15))),
+            PageEnd::NoProgress {
+                next_height: NodeHeight(15)
+            }
+        );
+        assert_eq!(
+            in_progress.on_page_end(&"a", Some(NodeHeight(20))),
+            PageEnd::Unrequested
+        );
+    }
+
+    #[test]
+    fn it_ignores_pages_from_other_peers() {
+        let mut in_progress = CatchUpInProgress::new();
+        in_progress.start("a", NodeHeight(10));
+
+        assert_eq!(
+            in_progress.on_page_end(&"b", Some(NodeHeight(15))),
+            PageEnd::WrongPeer { syncing_from: "a" }
+        );
+        in_progress.clear_for(&"b");
+        assert_eq!(
+            in_progress.on_page_end(&"a", Some(NodeHeight(15))),
+            PageEnd::RequestNext {
+                next_height: NodeHeight(15)
+            }
+        );
+    }
+
+    #[test]
+    fn it_clears_the_catch_up_for_a_peer() {
+        let mut in_progress = CatchUpInProgress::new();
+        in_progress.start("a", NodeHeight(10));
+        in_progress.clear_for(&"a");
+        assert_eq!(
+            in_progress.on_page_end(&"a", Some(NodeHeight(15))),
+            PageEnd::Unrequested
+        );
+
+        in_progress.start("a", NodeHeight(10));
+        in_progress.clear();
+        assert_eq!(in_progress.on_page_end(&"a", None), PageEnd::Unrequested);
+    }
+}
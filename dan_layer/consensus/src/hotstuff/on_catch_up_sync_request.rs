@@ -1,33 +1,76 @@
 //   Copyright 2023 The Tari Project
 //   SPDX-License-Identifier: BSD-3-Clause
 
+use std::{
+    future::Future,
+    time::{Duration, Instant},
+};
+
 use log::*;
-use tari_dan_common_types::{committee::CommitteeInfo, optional::Optional, Epoch};
+use tari_dan_common_types::{
+    committee::{Committee, CommitteeInfo},
+    optional::Optional,
+    Epoch,
+    NodeHeight,
+};
 use tari_dan_storage::{
-    consensus_models::{Block, LastProposed, LastSentVote, LeafBlock},
+    consensus_models::{Block, HighQc, LastProposed, LastSentVote, LeafBlock, QuorumCertificate},
     StateStore,
 };
-use tokio::task;
+use tari_epoch_manager::EpochManagerReader;
+use tokio::{task, time};
 
 use crate::{
+    block_validations::check_qc_signatures,
     hotstuff::HotStuffError,
-    messages::{HotstuffMessage, ProposalMessage, SyncRequestMessage},
-    traits::{ConsensusSpec, OutboundMessaging},
+    messages::{HotstuffMessage, ProposalMessage, SyncRequestMessage, SyncResponseMessage},
+    traits::{
+        hooks::{ConsensusHooks, SyncRequestRejection},
+        ConsensusSpec,
+        OutboundMessaging,
+    },
 };
 
 const LOG_TARGET: &str = "tari::dan::consensus::hotstuff::on_sync_request";
 
+/// The maximum number of blocks returned for a single catch-up request
+const MAX_BLOCKS_PER_RESPONSE: u64 = 1000;
+
 #[derive(Debug)]
 pub struct OnSyncRequest<TConsensusSpec: ConsensusSpec> {
     store: TConsensusSpec::StateStore,
+    epoch_manager: TConsensusSpec::EpochManager,
+    signing_service: TConsensusSpec::SignatureService,
     outbound_messaging: TConsensusSpec::OutboundMessaging,
+    hooks: TConsensusSpec::Hooks,
+    message_interval: Option<Duration>,
+    timeout: Option<Duration>,
+    send_timeout: Option<Duration>,
+    verify_qc: bool,
 }
 
 impl<TConsensusSpec: ConsensusSpec> OnSyncRequest<TConsensusSpec> {
-    pub fn new(store: TConsensusSpec::StateStore, outbound_messaging: TConsensusSpec::OutboundMessaging) -> Self {
+    pub fn new(
+        store: TConsensusSpec::StateStore,
+        epoch_manager: TConsensusSpec::EpochManager,
+        signing_service: TConsensusSpec::SignatureService,
+        outbound_messaging: TConsensusSpec::OutboundMessaging,
+        hooks: TConsensusSpec::Hooks,
+        message_interval: Option<Duration>,
+        timeout: Option<Duration>,
+        send_timeout: Option<Duration>,
+        verify_qc: bool,
+    ) -> Self {
         Self {
             store,
+            epoch_manager,
+            signing_service,
             outbound_messaging,
+            hooks,
+            message_interval,
+            timeout,
+            send_timeout,
+            verify_qc,
         }
     }
 
@@ -47,127 +90,427 @@ impl<TConsensusSpec: ConsensusSpec> OnSyncRequest<TConsensusSpec> {
                 msg.high_qc.epoch(),
                 epoch
             );
+            self.hooks
+                .clone()
+                .on_sync_request_rejected(SyncRequestRejection::WrongEpoch);
             return;
         }
 
         let mut outbound_messaging = self.outbound_messaging.clone();
         let store = self.store.clone();
+        let epoch_manager = self.epoch_manager.clone();
+        let signing_service = self.signing_service.clone();
+        let verify_qc = self.verify_qc;
+        let mut hooks = self.hooks.clone();
+        let message_interval = self.message_interval;
+        let timeout = self.timeout;
+        let send_timeout = self.send_timeout;
+        let is_paginated = msg.max_blocks > 0;
+        let limit = if is_paginated {
+            msg.max_blocks.min(MAX_BLOCKS_PER_RESPONSE)
+        } else {
+            MAX_BLOCKS_PER_RESPONSE
+        };
 
         task::spawn(async move {
-            let result = store.with_read_tx(|tx| {
-                let mut leaf_block = LeafBlock::get(tx, epoch)?;
-                if let Some(last_proposed) = LastProposed::get(tx).optional()? {
-                    if last_proposed.height > leaf_block.height() {
-                        leaf_block = last_proposed.as_leaf_block();
+            let serve_from = from.clone();
+            // Abort the whole response if it takes too long. Dropping the future stops any in-flight send.
+            let served = with_timeout(timeout, async move {
+                let timer = Instant::now();
+                if verify_qc {
+                    let verified = verify_sync_request_high_qc::<TConsensusSpec>(
+                        &epoch_manager,
+                        &signing_service,
+                        &local_committee_info,
+                        epoch,
+                        &msg.high_qc,
+                        msg.high_qc_cert.as_ref(),
+                    )
+                    .await;
+                    if let Err(err) = verified {
+                        warn!(target: LOG_TARGET, "🌐 Rejecting catch up request from {}: {}", from, err);
+                        hooks.on_sync_request_rejected(SyncRequestRejection::InvalidQc);
+                        return;
                     }
                 }
 
-                if leaf_block.height.is_zero() {
-                    info!(target: LOG_TARGET, "This node is at height 0 so cannot return any syn blocks. Ignoring request");
-                    return Ok(vec![]);
-                }
+                // Reads are served from a snapshot, if the store supports it, so that serving a large catch-up does not
+                // stall our own block commits
+                let result = store.with_snapshot_read_tx(|tx| {
+                    let mut leaf_block = LeafBlock::get(tx, epoch)?;
+                    if let Some(last_proposed) = LastProposed::get(tx).optional()? {
+                        if last_proposed.height > leaf_block.height() {
+                            leaf_block = last_proposed.as_leaf_block();
+                        }
+                    }
 
-                if leaf_block.height() < msg.high_qc.block_height() {
-                    return Err(HotStuffError::InvalidSyncRequest {
-                        details: format!(
-                            "Received catch up request from {} for block {} but our leaf block is {}. Ignoring \
-                             request.",
-                            from, msg.high_qc, leaf_block
-                        ),
-                    });
-                }
+                    if leaf_block.height.is_zero() {
+                        info!(target: LOG_TARGET, "This node is at height 0 so cannot return any syn blocks. Ignoring request");
+                        hooks.on_sync_request_rejected(SyncRequestRejection::AtGenesis);
+                        return Ok(SyncRequestOutcome::Rejected);
+                    }
 
-                info!(
-                    target: LOG_TARGET,
-                    "🌐 Received catch up request from {} from block {} to {}",
-                    from,
-                    msg.high_qc,
-                    leaf_block
-                );
-                // NOTE: We have to send dummy blocks, because the messaging will ignore heights > current_view + 1,
-                // until eventually the syncing node's pacemaker leader-fails a few times.
-                let blocks = Block::get_all_blocks_between(
-                    tx,
-                    leaf_block.epoch(),
-                    local_committee_info.shard_group(),
-                    msg.high_qc.block_height(),
-                    leaf_block.height(),
-                    true,
-                    1000,
-                )?;
-
-                Ok::<_, HotStuffError>(blocks)
-            });
+                    // A high QC for a block we have must agree with our copy of the block
+                    if let Some(high_qc_block) = Block::get(tx, msg.high_qc.block_id()).optional()? {
+                        if high_qc_block.height() != msg.high_qc.block_height() {
+                            return Err(HotStuffError::InvalidSyncRequest {
+                                details: format!(
+                                    "Received catch up request from {} for block {} but that block is at height {}",
+                                    from,
+                                    msg.high_qc,
+                                    high_qc_block.height()
+                                ),
+                            });
+                        }
+                    }
 
-            let blocks = match result {
-                Ok(mut blocks) => {
-                    if let Some(pos) = blocks.iter().position(|b| b.is_genesis()) {
-                        blocks.remove(pos);
+                    if leaf_block.height() < msg.high_qc.block_height() {
+                        // The requester is ahead of us, so we have nothing newer to offer. This is not an error.
+                        info!(
+                            target: LOG_TARGET,
+                            "🌐 Received catch up request from {} for block {} but our leaf block is {}. Responding \
+                             with our high QC.",
+                            from,
+                            msg.high_qc,
+                            leaf_block
+                        );
+                        hooks.on_sync_request_rejected(SyncRequestRejection::LeafBehind);
+                        let high_qc = HighQc::get(tx, epoch)?;
+                        return Ok(SyncRequestOutcome::Behind { high_qc });
                     }
-                    blocks
-                },
-                Err(err) => {
-                    warn!(target: LOG_TARGET, "Failed to fetch blocks for sync request: {}", err);
-                    return;
-                },
-            };
-
-            info!(
-                target: LOG_TARGET,
-                "🌐 Sending {} block(s) ({} to {}) to {}",
-                blocks.len(),
-                blocks.first().map(|b| b.height()).unwrap_or_default(),
-                blocks.last().map(|b| b.height()).unwrap_or_default(),
-                from
-            );
 
-            for block in blocks {
-                info!(
-                    target: LOG_TARGET,
-                    "🌐 Sending block {} to {}",
-                    block,
-                    from
-                );
-                // TODO(perf): O(n) queries
-                let foreign_proposals = match store.with_read_tx(|tx| block.get_foreign_proposals(tx)) {
-                    Ok(foreign_proposals) => foreign_proposals,
+                    // The QC signatures do not cover its height, so a verified QC must also be for a block we have. We
+                    // have every block up to our leaf, so this is the case for any genuine QC that is not ahead of us.
+                    if verify_qc && !Block::record_exists(tx, msg.high_qc.block_id())? {
+                        hooks.on_sync_request_rejected(SyncRequestRejection::InvalidQc);
+                        return Err(HotStuffError::InvalidSyncRequest {
+                            details: format!(
+                                "Received catch up request from {} for block {} that we do not have",
+                                from, msg.high_qc
+                            ),
+                        });
+                    }
+
+                    info!(
+                        target: LOG_TARGET,
+                        "🌐 Received catch up request from {} from block {} to {}",
+                        from,
+                        msg.high_qc,
+                        leaf_block
+                    );
+                    // An unpaginated request that spans more blocks than a single response may contain is served up
+                    // to the limit
+                    let num_blocks_requested = (leaf_block.height() - msg.high_qc.block_height()).as_u64();
+                    if !is_paginated && num_blocks_requested > limit {
+                        hooks.on_sync_request_truncated(num_blocks_requested, limit);
+                    }
+                    // NOTE: We have to send dummy blocks, because the messaging will ignore heights > current_view + 1,
+                    // until eventually the syncing node's pacemaker leader-fails a few times.
+                    let blocks = Block::get_all_blocks_between(
+                        tx,
+                        leaf_block.epoch(),
+                        local_committee_info.shard_group(),
+                        msg.high_qc.block_height(),
+                        leaf_block.height(),
+                        true,
+                        limit,
+                    )?;
+
+                    Ok::<_, HotStuffError>(SyncRequestOutcome::Serve { blocks, leaf_height: leaf_block.height() })
+                });
+
+                let (blocks, leaf_height) = match result {
+                    Ok(SyncRequestOutcome::Serve { mut blocks, leaf_height }) => {
+                        // Peers that already have the genesis block do not need it
+                        if !msg.include_genesis {
+                            if let Some(pos) = blocks.iter().position(|b| b.is_genesis()) {
+                                blocks.remove(pos);
+                            }
+                        }
+                        (blocks, leaf_height)
+                    },
+                    Ok(SyncRequestOutcome::Behind { high_qc }) => {
+                        let response = HotstuffMessage::SyncResponse(SyncResponseMessage {
+                            epoch,
+                            blocks: vec![],
+                            next_height: None,
+                            high_qc: Some(high_qc),
+                        });
+                        match with_timeout(send_timeout, outbound_messaging.send(from.clone(), response)).await {
+                            Some(Ok(())) => {},
+                            Some(Err(err)) => {
+                                warn!(target: LOG_TARGET, "Error sending SyncResponse: {err}");
+                            },
+                            None => {
+                                warn!(target: LOG_TARGET, "🌐 Peer {} is unresponsive, SyncResponse was not sent", from);
+                            },
+                        }
+                        return;
+                    },
+                    Ok(SyncRequestOutcome::Rejected) => return,
                     Err(err) => {
-                        warn!(target: LOG_TARGET, "Failed to fetch foreign proposals for block {}: {}", block, err);
+                        warn!(target: LOG_TARGET, "Failed to fetch blocks for sync request: {}", err);
                         return;
                     },
                 };
 
-                if let Err(err) = outbound_messaging
-                    .send(
-                        from.clone(),
-                        HotstuffMessage::Proposal(ProposalMessage {
-                            block,
-                            foreign_proposals,
-                        }),
-                    )
-                    .await
-                {
-                    warn!(target: LOG_TARGET, "Error sending SyncResponse: {err}");
-                    return;
+                info!(
+                    target: LOG_TARGET,
+                    "🌐 Sending {} block(s) ({} to {}) to {}",
+                    blocks.len(),
+                    blocks.first().map(|b| b.height()).unwrap_or_default(),
+                    blocks.last().map(|b| b.height()).unwrap_or_default(),
+                    from
+                );
+
+                let num_blocks = blocks.len();
+                // The requester asks for the next page from this height if we have not reached our leaf yet
+                let next_height = blocks
+                    .last()
+                    .map(|b| b.height())
+                    .filter(|height| is_paginated && *height < leaf_height)
+                    .map(|height| height + NodeHeight(1));
+                let mut num_bytes = 0;
+                let mut num_sent = 0usize;
+                let mut pacer = message_interval.map(|interval| {
+                    let mut pacer = time::interval(interval);
+                    pacer.set_missed_tick_behavior(time::MissedTickBehavior::Delay);
+                    pacer
+                });
+                for block in blocks {
+                    if let Some(pacer) = pacer.as_mut() {
+                        // The first tick completes immediately
+                        pacer.tick().await;
+                    }
+                    info!(
+                        target: LOG_TARGET,
+                        "🌐 Sending block {} to {}",
+                        block,
+                        from
+                    );
+                    // TODO(perf): O(n) queries
+                    let foreign_proposals = match store.with_snapshot_read_tx(|tx| block.get_foreign_proposals(tx)) {
+                        Ok(foreign_proposals) => foreign_proposals,
+                        Err(err) => {
+                            warn!(target: LOG_TARGET, "Failed to fetch foreign proposals for block {}: {}", block, err);
+                            return;
+                        },
+                    };
+
+                    let proposal = ProposalMessage {
+                        block,
+                        foreign_proposals,
+                        allow_compression: msg.accepts_compressed_proposals,
+                    };
+                    // This is the uncompressed encoded size, which is a reasonable approximation of the cost of serving
+                    num_bytes += tari_bor::encoded_len(&proposal).unwrap_or(0);
+
+                    let send = outbound_messaging.send(from.clone(), HotstuffMessage::Proposal(proposal));
+                    match with_timeout(send_timeout, send).await {
+                        Some(Ok(())) => {
+                            num_sent += 1;
+                        },
+                        Some(Err(err)) => {
+                            warn!(target: LOG_TARGET, "Error sending SyncResponse: {err}");
+                            return;
+                        },
+                        None => {
+                            warn!(
+                                target: LOG_TARGET,
+                                "🌐 Peer {} is unresponsive, truncating catch-up after {}/{} block(s)",
+                                from,
+                                num_sent,
+                                num_blocks
+                            );
+                            return;
+                        },
+                    }
                 }
-            }
 
-            // Send last vote.
-            let maybe_last_vote = match store.with_read_tx(|tx| LastSentVote::get(tx)).optional() {
-                Ok(last_vote) => last_vote,
-                Err(err) => {
-                    warn!(target: LOG_TARGET, "Failed to fetch last vote for catch-up request: {}", err);
-                    return;
-                },
-            };
-            if let Some(last_vote) = maybe_last_vote {
-                if let Err(err) = outbound_messaging
-                    .send(from.clone(), HotstuffMessage::Vote(last_vote.into()))
-                    .await
-                {
-                    warn!(target: LOG_TARGET, "Failed to send LastVote {err}");
+                if is_paginated {
+                    let response = HotstuffMessage::SyncResponse(SyncResponseMessage {
+                        epoch,
+                        blocks: vec![],
+                        next_height,
+                        high_qc: None,
+                    });
+                    match with_timeout(send_timeout, outbound_messaging.send(from.clone(), response)).await {
+                        Some(Ok(())) => {},
+                        Some(Err(err)) => {
+                            warn!(target: LOG_TARGET, "Error sending SyncResponse: {err}");
+                            return;
+                        },
+                        None => {
+                            warn!(target: LOG_TARGET, "🌐 Peer {} is unresponsive, SyncResponse was not sent", from);
+                            return;
+                        },
+                    }
                 }
+
+                // Send last vote once the requester has all blocks up to our leaf
+                let maybe_last_vote = if next_height.is_none() {
+                    match store.with_snapshot_read_tx(|tx| LastSentVote::get(tx)).optional() {
+                        Ok(last_vote) => last_vote,
+                        Err(err) => {
+                            warn!(target: LOG_TARGET, "Failed to fetch last vote for catch-up request: {}", err);
+                            return;
+                        },
+                    }
+                } else {
+                    None
+                };
+                if let Some(last_vote) = maybe_last_vote {
+                    let send = outbound_messaging.send(from.clone(), HotstuffMessage::Vote(last_vote.into()));
+                    match with_timeout(send_timeout, send).await {
+                        Some(Ok(())) => {},
+                        Some(Err(err)) => {
+                            warn!(target: LOG_TARGET, "Failed to send LastVote {err}");
+                        },
+                        None => {
+                            warn!(target: LOG_TARGET, "🌐 Peer {} is unresponsive, LastVote was not sent", from);
+                        },
+                    }
+                }
+
+                let elapsed = timer.elapsed();
+                info!(
+                    target: LOG_TARGET,
+                    "🌐 Served catch-up to {}: {} block(s), {} bytes, {:.2?}",
+                    from,
+                    num_blocks,
+                    num_bytes,
+                    elapsed
+                );
+                hooks.on_sync_request_served(num_blocks, num_bytes, elapsed);
+            })
+            .await;
+
+            if served.is_none() {
+                warn!(
+                    target: LOG_TARGET,
+                    "🌐 Catch-up to {} did not complete within {:.2?}. Truncating response.",
+                    serve_from,
+                    timeout.unwrap_or_default()
+                );
             }
         });
     }
 }
+
+/// Checks that the high QC of a catch-up sync request is signed by a quorum of our committee in the current epoch
+async fn verify_sync_request_high_qc<TConsensusSpec: ConsensusSpec>(
+    epoch_manager: &TConsensusSpec::EpochManager,
+    signing_service: &TConsensusSpec::SignatureService,
+    local_committee_info: &CommitteeInfo,
+    epoch: Epoch,
+    high_qc: &HighQc,
+    high_qc_cert: Option<&QuorumCertificate>,
+) -> Result<(), HotStuffError> {
+    let committee = epoch_manager.get_local_committee(epoch).await?;
+    check_sync_request_high_qc::<TConsensusSpec>(
+        epoch,
+        high_qc,
+        high_qc_cert,
+        &committee,
+        local_committee_info,
+        signing_service,
+    )
+}
+
+/// Checks the high QC of a catch-up sync request against the committee of the current `epoch`. The request must
+/// include the certificate of its high QC, which must be for our shard group and signed by a quorum of `committee`, so
+/// that a peer cannot get blocks served with a QC it made up. Peers that do not send the certificate are rejected.
+///
+/// The genesis QC is not signed, so it is accepted if it is our own genesis QC for the current epoch. A peer that has
+/// only the genesis block cannot prove that it is a member of the committee, so these requests remain
+/// unauthenticated and are served the blocks of the current epoch for our shard group.
+pub fn check_sync_request_high_qc<TConsensusSpec: ConsensusSpec>(
+    epoch: Epoch,
+    high_qc: &HighQc,
+    high_qc_cert: Option<&QuorumCertificate>,
+    committee: &Committee<TConsensusSpec::Addr>,
+    local_committee_info: &CommitteeInfo,
+    signing_service: &TConsensusSpec::SignatureService,
+) -> Result<(), HotStuffError> {
+    let Some(high_qc_cert) = high_qc_cert else {
+        return Err(HotStuffError::InvalidSyncRequest {
+            details: format!(
+                "Request for high QC {} does not include the certificate, which is required to verify it",
+                high_qc
+            ),
+        });
+    };
+
+    if high_qc_cert.as_high_qc() != *high_qc {
+        return Err(HotStuffError::InvalidSyncRequest {
+            details: format!(
+                "High QC certificate {} does not match high QC {}",
+                high_qc_cert, high_qc
+            ),
+        });
+    }
+
+    if high_qc_cert.is_zero() {
+        let genesis = QuorumCertificate::genesis(epoch, local_committee_info.shard_group());
+        if high_qc_cert.calculate_id() != *genesis.id() {
+            return Err(HotStuffError::InvalidSyncRequest {
+                details: format!(
+                    "Zero high QC {} is not our genesis QC for epoch {} and shard group {}",
+                    high_qc_cert,
+                    epoch,
+                    local_committee_info.shard_group()
+                ),
+            });
+        }
+        return Ok(());
+    }
+
+    if high_qc_cert.epoch() != epoch {
+        return Err(HotStuffError::InvalidSyncRequest {
+            details: format!(
+                "High QC {} is for epoch {} but our epoch is {}",
+                high_qc_cert,
+                high_qc_cert.epoch(),
+                epoch
+            ),
+        });
+    }
+
+    if high_qc_cert.shard_group() != local_committee_info.shard_group() {
+        return Err(HotStuffError::InvalidSyncRequest {
+            details: format!(
+                "High QC {} is for shard group {} but our shard group is {}",
+                high_qc_cert,
+                high_qc_cert.shard_group(),
+                local_committee_info.shard_group()
+            ),
+        });
+    }
+
+    check_qc_signatures::<TConsensusSpec>(high_qc_cert, committee, local_committee_info, signing_service).map_err(
+        |err| HotStuffError::InvalidSyncRequest {
+            details: format!("High QC {} is not validly signed: {}", high_qc_cert, err),
+        },
+    )
+}
+
+enum SyncRequestOutcome {
+    /// Send these blocks, ending at our leaf height
+    Serve {
+        blocks: Vec<Block>,
+        leaf_height: NodeHeight,
+    },
+    /// The requester is ahead of us, so we respond with only our high QC
+    Behind { high_qc: HighQc },
+    /// The request was rejected and nothing is sent
+    Rejected,
+}
+
+/// Awaits the future, returning None if it did not complete within the timeout. If no timeout is given, the future
+/// is awaited to completion.
+async fn with_timeout<F: Future>(timeout: Option<Duration>, fut: F) -> Option<F::Output> {
+    match timeout {
+        Some(timeout) => time::timeout(timeout, fut).await.ok(),
+        None => Some(fut.await),
+    }
+}
@@ -189,6 +189,7 @@ impl<TConsensusSpec: ConsensusSpec> OnMessageValidate<TConsensusSpec> {
                     unparked_blocks.push(ProposalMessage {
                         block: unparked_block,
                         foreign_proposals,
+                        allow_compression: false,
                     });
                 }
 
@@ -242,6 +242,7 @@ where TConsensusSpec: ConsensusSpec
         let msg = HotstuffMessage::Proposal(ProposalMessage {
             block: next_block,
             foreign_proposals,
+            allow_compression: false,
         });
         // Broadcast to local and foreign committees
         self.outbound_messaging.send_self(msg.clone()).await?;
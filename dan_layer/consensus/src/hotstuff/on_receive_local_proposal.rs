@@ -33,12 +33,14 @@ use crate::{
     hotstuff::{
         block_change_set::ProposedBlockChangeSet,
         calculate_dummy_blocks_from_justify,
+        calculate_last_dummy_block_from_justify,
         create_epoch_checkpoint,
         error::HotStuffError,
         get_next_block_height_and_leader,
         on_ready_to_vote_on_local_block::OnReadyToVoteOnLocalBlock,
         on_receive_foreign_proposal::OnReceiveForeignProposalHandler,
         pacemaker_handle::PaceMakerHandle,
+        save_dummy_blocks_from_justify,
         transaction_manager::ConsensusTransactionManager,
         HotstuffConfig,
         HotstuffEvent,
@@ -58,6 +60,10 @@ use crate::{
 
 const LOG_TARGET: &str = "tari::dan::consensus::hotstuff::on_receive_local_proposal";
 
+/// Gaps of more than this many dummy blocks (e.g. when catching up after a long absence) are not materialized in
+/// memory. Instead, the dummy blocks are generated again and saved one at a time when the proposal is saved.
+const MAX_MATERIALIZED_DUMMY_BLOCKS: u64 = 100;
+
 pub struct OnReceiveLocalProposalHandler<TConsensusSpec: ConsensusSpec> {
     config: HotstuffConfig,
     store: TConsensusSpec::StateStore,
@@ -135,6 +141,7 @@ impl<TConsensusSpec: ConsensusSpec> OnReceiveLocalProposalHandler<TConsensusSpec
         let ProposalMessage {
             block,
             foreign_proposals,
+            ..
         } = msg;
 
         let maybe_valid_block = self.store.with_read_tx(|tx| {
@@ -207,7 +214,7 @@ impl<TConsensusSpec: ConsensusSpec> OnReceiveLocalProposalHandler<TConsensusSpec
                 )?;
             }
 
-            self.save_block(tx, &valid_block)?;
+            self.save_block(tx, &valid_block, local_committee)?;
             info!(target: LOG_TARGET, "✅ Block {} is valid and persisted.", valid_block);
             Ok::<_, HotStuffError>(())
         })?;
@@ -504,6 +511,7 @@ impl<TConsensusSpec: ConsensusSpec> OnReceiveLocalProposalHandler<TConsensusSpec
         &self,
         tx: &mut <TConsensusSpec::StateStore as StateStore>::WriteTransaction<'_>,
         valid_block: &ValidBlock,
+        local_committee: &Committee<TConsensusSpec::Addr>,
     ) -> Result<(), HotStuffError> {
         valid_block.block().save_foreign_send_counters(tx)?;
         valid_block.block().justify().save(tx)?;
@@ -511,6 +519,21 @@ impl<TConsensusSpec: ConsensusSpec> OnReceiveLocalProposalHandler<TConsensusSpec
             info!(target: LOG_TARGET, "Saving {} dummy block(s)", valid_block.dummy_blocks().len());
             valid_block.save_all_dummy_blocks(tx)?;
         }
+        if valid_block.num_deferred_dummy_blocks() > 0 {
+            info!(
+                target: LOG_TARGET,
+                "Saving {} deferred dummy block(s)",
+                valid_block.num_deferred_dummy_blocks()
+            );
+            let justify_block = valid_block.justify().get_block(&**tx)?;
+            save_dummy_blocks_from_justify(
+                tx,
+                valid_block.block(),
+                &justify_block,
+                &self.leader_strategy,
+                local_committee,
+            )?;
+        }
         valid_block.block().save(tx)?;
 
         Ok(())
@@ -748,12 +771,48 @@ impl<TConsensusSpec: ConsensusSpec> OnReceiveLocalProposalHandler<TConsensusSpec
         // if the block parent is not the justify parent, then we have experienced a leader failure
         // and should make dummy blocks to fill in the gaps.
         if !high_qc.block_id().is_zero() && !candidate_block.justifies_parent() {
+            let num_dummy_blocks = candidate_block
+                .height()
+                .saturating_sub(justify_block.height())
+                .as_u64()
+                .saturating_sub(1);
+            if num_dummy_blocks > MAX_MATERIALIZED_DUMMY_BLOCKS {
+                // Only the last dummy block is kept to validate the candidate block, the dummy blocks are generated
+                // again and saved with the block
+                let Some((last_dummy, count)) = calculate_last_dummy_block_from_justify(
+                    &candidate_block,
+                    &justify_block,
+                    &self.leader_strategy,
+                    local_committee,
+                )?
+                else {
+                    warn!(target: LOG_TARGET, "❌ Bad proposal, does not justify parent for candidate block {}", candidate_block);
+                    return Err(ProposalValidationError::CandidateBlockDoesNotExtendJustify {
+                        justify_block_height: justify_block.height(),
+                        candidate_block_height: candidate_block.height(),
+                    }
+                    .into());
+                };
+
+                if candidate_block.parent() != last_dummy.block_id() {
+                    warn!(target: LOG_TARGET, "❌ Bad proposal, unable to find dummy blocks (last dummy: {}) for candidate block {}", last_dummy, candidate_block);
+                    return Err(ProposalValidationError::CandidateBlockDoesNotExtendJustify {
+                        justify_block_height: justify_block.height(),
+                        candidate_block_height: candidate_block.height(),
+                    }
+                    .into());
+                }
+
+                // See below for why is_safe is not checked
+                return Ok(ValidBlock::with_deferred_dummy_blocks(candidate_block, count));
+            }
+
             let dummy_blocks = calculate_dummy_blocks_from_justify(
                 &candidate_block,
                 &justify_block,
                 &self.leader_strategy,
                 local_committee,
-            );
+            )?;
 
             let Some(last_dummy) = dummy_blocks.last() else {
                 warn!(target: LOG_TARGET, "❌ Bad proposal, does not justify parent for candidate block {}", candidate_block);
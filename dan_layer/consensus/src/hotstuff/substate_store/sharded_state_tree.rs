@@ -34,6 +34,7 @@ pub struct ShardedStateTree<TTx> {
     tx: TTx,
     pending_diffs: HashMap<Shard, Vec<PendingShardStateTreeDiff>>,
     shard_tree_diffs: IndexMap<Shard, VersionedStateHashTreeDiff>,
+    root_progression: Option<IndexMap<Shard, Vec<(Version, Hash)>>>,
 }
 
 impl<TTx> ShardedStateTree<TTx> {
@@ -42,6 +43,7 @@ impl<TTx> ShardedStateTree<TTx> {
             tx,
             pending_diffs: HashMap::new(),
             shard_tree_diffs: IndexMap::new(),
+            root_progression: None,
         }
     }
 
@@ -49,6 +51,21 @@ impl<TTx> ShardedStateTree<TTx> {
         Self { pending_diffs, ..self }
     }
 
+    /// Records the state root of each changed shard at every version, i.e. after each pending diff and after the new
+    /// changes, so that the progression of roots can be audited. See [ShardedStateTree::take_root_progression].
+    pub fn with_root_progression(self) -> Self {
+        Self {
+            root_progression: Some(IndexMap::new()),
+            ..self
+        }
+    }
+
+    /// Returns the recorded state roots of each changed shard in version order, or None if recording was not enabled
+    /// with [ShardedStateTree::with_root_progression]
+    pub fn take_root_progression(&mut self) -> Option<IndexMap<Shard, Vec<(Version, Hash)>>> {
+        self.root_progression.take()
+    }
+
     pub fn transaction(&self) -> &TTx {
         &self.tx
     }
@@ -95,11 +112,16 @@ impl<TTx: StateStoreReadTransaction> ShardedStateTree<&TTx> {
             // Staged store that tracks changes to the state tree
             let mut store = StagedTreeStore::new(&scoped_store);
             // Apply pending (not yet committed) diffs to the staged store
+            let mut roots = self.root_progression.as_ref().map(|_| Vec::new());
             if let Some(diffs) = self.pending_diffs.get(&shard) {
                 let mut num_changes = 0usize;
                 for diff in diffs {
                     num_changes += diff.diff.new_nodes.len() + diff.diff.stale_tree_nodes.len();
                     store.apply_pending_diff(diff.diff.clone());
+                    if let Some(roots) = roots.as_mut() {
+                        let root = SpreadPrefixStateTree::new(&mut store).get_root_hash(diff.version)?;
+                        roots.push((diff.version, root));
+                    }
                 }
                 debug!(
                     target: LOG_TARGET,
@@ -114,6 +136,12 @@ impl<TTx: StateStoreReadTransaction> ShardedStateTree<&TTx> {
             debug!(target: LOG_TARGET, "v{next_version} contains {} tree change(s) for shard {shard}", changes.len());
             let shard_state_hash = state_tree.put_substate_changes(current_version, next_version, changes)?;
             shard_state_roots.insert(shard, shard_state_hash);
+            if let Some(mut roots) = roots {
+                roots.push((next_version, shard_state_hash));
+                self.root_progression
+                    .get_or_insert_with(IndexMap::new)
+                    .insert(shard, roots);
+            }
             self.shard_tree_diffs
                 .insert(shard, VersionedStateHashTreeDiff::new(next_version, store.into_diff()));
         }
@@ -187,6 +187,23 @@ impl<TConsensusSpec: ConsensusSpec> HotstuffWorker<TConsensusSpec> {
                 transaction_executor.clone(),
                 tx_missing_transactions,
             ),
+            on_sync_request: OnSyncRequest::new(
+                state_store.clone(),
+                epoch_manager.clone(),
+                signing_service.clone(),
+                outbound_messaging.clone(),
+                hooks.clone(),
+                config.sync_response_message_interval,
+                config.sync_response_timeout,
+                config.sync_response_send_timeout,
+                config.sync_request_verify_qc,
+            ),
+            on_catch_up_sync: OnCatchUpSync::new(
+                state_store.clone(),
+                pacemaker.clone_handle(),
+                outbound_messaging.clone(),
+                config.sync_request_max_blocks,
+            ),
             on_propose: OnPropose::new(
                 config,
                 state_store.clone(),
@@ -194,12 +211,9 @@ impl<TConsensusSpec: ConsensusSpec> HotstuffWorker<TConsensusSpec> {
                 transaction_pool.clone(),
                 transaction_manager,
                 signing_service,
-                outbound_messaging.clone(),
+                outbound_messaging,
             ),
 
-            on_sync_request: OnSyncRequest::new(state_store.clone(), outbound_messaging.clone()),
-            on_catch_up_sync: OnCatchUpSync::new(state_store.clone(), pacemaker.clone_handle(), outbound_messaging),
-
             state_store,
             leader_strategy,
             epoch_manager,
@@ -834,13 +848,12 @@ impl<TConsensusSpec: ConsensusSpec> HotstuffWorker<TConsensusSpec> {
                     .handle(from, *local_committee_info, current_epoch, msg);
                 Ok(())
             },
-            HotstuffMessage::SyncResponse(_) => {
-                warn!(
-                    target: LOG_TARGET,
-                    "⚠️ Ignoring unrequested SyncResponse from {}",from
-                );
-                Ok(())
-            },
+            HotstuffMessage::SyncResponse(msg) => log_err(
+                "on_catch_up_sync_response",
+                self.on_catch_up_sync
+                    .handle_sync_response(current_epoch, from, msg)
+                    .await,
+            ),
         }
     }
 
@@ -5,5 +5,7 @@ mod block_validations;
 pub mod consensus_constants;
 pub mod hotstuff;
 pub mod messages;
+#[cfg(feature = "test-helpers")]
+pub mod test_helpers;
 mod tracing;
 pub mod traits;
@@ -101,7 +101,15 @@ impl Display for HotstuffMessage {
                 msg.epoch
             ),
             HotstuffMessage::CatchUpSyncRequest(msg) => write!(f, "SyncRequest({})", msg.high_qc),
-            HotstuffMessage::SyncResponse(msg) => write!(f, "SyncResponse({} block(s))", msg.blocks.len()),
+            HotstuffMessage::SyncResponse(msg) => match msg.next_height {
+                Some(next_height) => write!(
+                    f,
+                    "SyncResponse({} block(s), next height {})",
+                    msg.blocks.len(),
+                    next_height
+                ),
+                None => write!(f, "SyncResponse({} block(s))", msg.blocks.len()),
+            },
         }
     }
 }
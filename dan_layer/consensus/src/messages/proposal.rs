@@ -16,6 +16,10 @@ use tari_dan_storage::consensus_models::{
 pub struct ProposalMessage {
     pub block: Block,
     pub foreign_proposals: Vec<ForeignProposal>,
+    /// Hint to the transport that the proposal may be compressed on the wire. This is only set for catch-up sync
+    /// responses to peers that indicated that they accept compressed proposals.
+    #[serde(skip)]
+    pub allow_compression: bool,
 }
 
 impl Display for ProposalMessage {
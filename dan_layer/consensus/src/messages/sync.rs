@@ -2,19 +2,38 @@
 //   SPDX-License-Identifier: BSD-3-Clause
 
 use serde::Serialize;
-use tari_dan_common_types::Epoch;
+use tari_dan_common_types::{Epoch, NodeHeight};
 use tari_dan_storage::consensus_models::{Block, HighQc, QuorumCertificate};
 use tari_transaction::Transaction;
 
 #[derive(Debug, Clone, Serialize)]
 pub struct SyncRequestMessage {
+    /// The requester's high QC
     pub high_qc: HighQc,
+    /// The certificate of `high_qc`, so that the responder can check its signatures before serving any blocks. This is
+    /// None if the requester does not send it, e.g. older peers.
+    pub high_qc_cert: Option<QuorumCertificate>,
+    /// True if the requesting node is able to decompress catch-up proposals
+    pub accepts_compressed_proposals: bool,
+    /// True if the requesting node does not have the genesis block (e.g. it is bootstrapping from scratch) and
+    /// requires it to be included in the response
+    pub include_genesis: bool,
+    /// The maximum number of blocks to send in response to this request. If more blocks are available, the responder
+    /// ends the page with a [SyncResponseMessage] containing the next height to request. If zero, the responder sends
+    /// as many blocks as it is willing to in a single response.
+    pub max_blocks: u64,
 }
 
 #[derive(Debug, Clone, Serialize)]
 pub struct SyncResponseMessage {
     pub epoch: Epoch,
     pub blocks: Vec<FullBlock>,
+    /// For a paginated sync request, the height from which the next page should be requested, or None if the
+    /// responder has no more blocks
+    pub next_height: Option<NodeHeight>,
+    /// The responder's high QC if it has no blocks newer than the requester's high QC, so that the requester learns
+    /// that the responder is behind
+    pub high_qc: Option<HighQc>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -0,0 +1,61 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use tari_dan_common_types::{committee::Committee, NodeHeight};
+
+pub use crate::block_validations::check_quorum_certificate;
+use crate::traits::LeaderStrategy;
+
+/// A leader strategy that selects the same leader for every height, so that tests can pin the leader without
+/// reasoning about leader rotation.
+///
+/// Panics if the leader is not a member of the committee.
+#[derive(Debug, Clone)]
+pub struct FixedLeaderStrategy<TAddr> {
+    leader: TAddr,
+}
+
+impl<TAddr> FixedLeaderStrategy<TAddr> {
+    pub fn new(leader: TAddr) -> Self {
+        Self { leader }
+    }
+
+    pub fn leader(&self) -> &TAddr {
+        &self.leader
+    }
+}
+
+impl<TAddr: PartialEq> LeaderStrategy<TAddr> for FixedLeaderStrategy<TAddr> {
+    fn calculate_leader(&self, committee: &Committee<TAddr>, _height: NodeHeight) -> u32 {
+        let position = committee
+            .members
+            .iter()
+            .position(|(addr, _)| *addr == self.leader)
+            .expect("FixedLeaderStrategy: leader is not a member of the committee");
+        position as u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tari_common_types::types::PublicKey;
+
+    use super::*;
+
+    fn new_member(seed: &'static str) -> (String, PublicKey) {
+        (seed.to_string(), PublicKey::new_generator(seed).unwrap())
+    }
+
+    #[test]
+    fn it_selects_the_fixed_leader_at_every_height() {
+        let strategy = FixedLeaderStrategy::new("2".to_string());
+        let committee = Committee::from_iter([new_member("1"), new_member("2"), new_member("3")]);
+
+        for height in 0..10 {
+            let (addr, _) = strategy.get_leader(&committee, NodeHeight(height));
+            assert_eq!(addr, "2");
+        }
+        assert!(strategy.is_leader(&"2".to_string(), &committee, NodeHeight(5)));
+        assert!(!strategy.is_leader(&"1".to_string(), &committee, NodeHeight(5)));
+    }
+}
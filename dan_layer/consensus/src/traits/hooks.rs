@@ -1,6 +1,8 @@
 //   Copyright 2024 The Tari Project
 //   SPDX-License-Identifier: BSD-3-Clause
 
+use std::{fmt::Display, time::Duration};
+
 use tari_dan_common_types::NodeHeight;
 use tari_dan_storage::consensus_models::{QuorumDecision, TransactionAtom, ValidBlock};
 use tari_transaction::TransactionId;
@@ -17,11 +19,45 @@ pub trait ConsensusHooks {
     fn on_leader_timeout(&mut self, new_height: NodeHeight);
 
     fn on_needs_sync(&mut self, local_height: NodeHeight, remote_qc_height: NodeHeight);
+    fn on_sync_request_served(&mut self, num_blocks: usize, num_bytes: usize, elapsed: Duration);
+    fn on_sync_request_rejected(&mut self, reason: SyncRequestRejection);
+    fn on_sync_request_truncated(&mut self, num_blocks_requested: u64, num_blocks_served: u64);
 
     fn on_transaction_ready(&mut self, tx_id: &TransactionId);
     fn on_transaction_finalized(&mut self, transaction: &TransactionAtom);
 }
 
+/// The reason a catch-up sync request was not served in full
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncRequestRejection {
+    /// The request is for an epoch other than our current epoch
+    WrongEpoch,
+    /// The requester's high QC is ahead of our leaf block, so we respond with only our high QC
+    LeafBehind,
+    /// We are at height 0 and have no blocks to return
+    AtGenesis,
+    /// The requester did not send the certificate of its high QC, or the high QC is not for our shard group, is not
+    /// signed by a quorum of our committee in the current epoch, or is for a block that we do not have
+    InvalidQc,
+}
+
+impl SyncRequestRejection {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::WrongEpoch => "wrong_epoch",
+            Self::LeafBehind => "leaf_behind",
+            Self::AtGenesis => "at_genesis",
+            Self::InvalidQc => "invalid_qc",
+        }
+    }
+}
+
+impl Display for SyncRequestRejection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct OptionalHooks<T> {
     inner: Option<T>,
@@ -80,6 +116,24 @@ impl<T: ConsensusHooks> ConsensusHooks for OptionalHooks<T> {
         }
     }
 
+    fn on_sync_request_served(&mut self, num_blocks: usize, num_bytes: usize, elapsed: Duration) {
+        if let Some(inner) = self.inner.as_mut() {
+            inner.on_sync_request_served(num_blocks, num_bytes, elapsed);
+        }
+    }
+
+    fn on_sync_request_rejected(&mut self, reason: SyncRequestRejection) {
+        if let Some(inner) = self.inner.as_mut() {
+            inner.on_sync_request_rejected(reason);
+        }
+    }
+
+    fn on_sync_request_truncated(&mut self, num_blocks_requested: u64, num_blocks_served: u64) {
+        if let Some(inner) = self.inner.as_mut() {
+            inner.on_sync_request_truncated(num_blocks_requested, num_blocks_served);
+        }
+    }
+
     fn on_transaction_ready(&mut self, tx_id: &TransactionId) {
         if let Some(inner) = self.inner.as_mut() {
             inner.on_transaction_ready(tx_id);
@@ -117,6 +171,12 @@ impl ConsensusHooks for NoopHooks {
 
     fn on_needs_sync(&mut self, _local_height: NodeHeight, _remote_qc_height: NodeHeight) {}
 
+    fn on_sync_request_served(&mut self, _num_blocks: usize, _num_bytes: usize, _elapsed: Duration) {}
+
+    fn on_sync_request_rejected(&mut self, _reason: SyncRequestRejection) {}
+
+    fn on_sync_request_truncated(&mut self, _num_blocks_requested: u64, _num_blocks_served: u64) {}
+
     fn on_transaction_ready(&mut self, _tx_id: &TransactionId) {}
 
     fn on_transaction_finalized(&mut self, _transaction: &TransactionAtom) {}
@@ -61,4 +61,20 @@ pub trait LeaderStrategy<TAddr> {
     ) -> (&'b TAddr, &'b PublicKey) {
         self.get_leader(committee, height + NodeHeight(1))
     }
+
+    /// Returns the public keys of the leaders for each height from `from_height` to `to_height` (inclusive), ordered by
+    /// height.
+    fn get_leader_schedule<'b>(
+        &self,
+        committee: &'b Committee<TAddr>,
+        from_height: NodeHeight,
+        to_height: NodeHeight,
+    ) -> Vec<&'b PublicKey> {
+        (from_height.as_u64()..=to_height.as_u64())
+            .map(|height| {
+                let (_, pk) = self.get_leader(committee, NodeHeight(height));
+                pk
+            })
+            .collect()
+    }
 }
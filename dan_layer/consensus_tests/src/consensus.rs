@@ -353,6 +353,40 @@ async fn multi_validator_propose_blocks_with_new_transactions_until_all_committe
     test.assert_clean_shutdown().await;
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn multi_validator_commits_with_paginated_catch_up_sync() {
+    setup_logger();
+    // Every validator requests a catch-up when it starts, so this exercises the paginated request and response
+    let mut test = Test::builder()
+        .with_sync_request_max_blocks(2)
+        .add_committee(0, vec!["1", "2", "3", "4"])
+        .start()
+        .await;
+    let mut remaining_txs = 5u32;
+
+    test.start_epoch(Epoch(1)).await;
+    loop {
+        if remaining_txs > 0 {
+            test.send_transaction_to_all(Decision::Commit, 1, 5, 1).await;
+        }
+        test.on_block_committed().await;
+        remaining_txs = remaining_txs.saturating_sub(1);
+
+        if remaining_txs == 0 && test.is_transaction_pool_empty() {
+            break;
+        }
+        let leaf = test.get_validator(&TestAddress::new("1")).get_leaf_block();
+        if leaf.height > NodeHeight(20) {
+            panic!("Not all transaction committed after {} blocks", leaf.height);
+        }
+    }
+
+    test.assert_all_validators_at_same_height().await;
+    test.assert_all_validators_committed();
+
+    test.assert_clean_shutdown().await;
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
 async fn multi_shard_propose_blocks_with_new_transactions_until_all_committed() {
     setup_logger();
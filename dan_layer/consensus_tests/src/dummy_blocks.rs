@@ -0,0 +1,169 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use tari_common::configuration::Network;
+use tari_common_types::types::{FixedHash, PublicKey};
+use tari_consensus::{
+    hotstuff::{calculate_dummy_blocks_from_justify, calculate_last_dummy_block_with_count, ProposalValidationError},
+    test_helpers::FixedLeaderStrategy,
+};
+use tari_dan_common_types::{committee::Committee, Epoch, ExtraData, NodeHeight, NumPreshards, ShardGroup};
+use tari_dan_storage::consensus_models::{Block, QuorumCertificate};
+
+use crate::support::{helpers, TestAddress};
+
+const NUM_VALIDATORS: usize = 4;
+
+#[test]
+fn it_returns_no_dummy_block_for_an_empty_committee() {
+    let committee = Committee::<TestAddress>::new(vec![]);
+    let qc = QuorumCertificate::genesis(Epoch(0), shard_group());
+
+    let dummy = calculate_last_dummy_block_with_count(
+        NodeHeight(0),
+        NodeHeight(4),
+        Network::LocalNet,
+        Epoch(0),
+        shard_group(),
+        *qc.block_id(),
+        &qc,
+        FixedHash::zero(),
+        &FixedLeaderStrategy::new(TestAddress::new("0")),
+        &committee,
+        0,
+        0,
+        FixedHash::zero(),
+    );
+    assert!(dummy.is_none());
+}
+
+#[test]
+fn it_returns_the_last_dummy_block_and_count() {
+    let (committee, leader_public_key) = create_committee();
+    let qc = QuorumCertificate::genesis(Epoch(0), shard_group());
+
+    let (dummy, count) = calculate_last_dummy_block_with_count(
+        NodeHeight(0),
+        NodeHeight(4),
+        Network::LocalNet,
+        Epoch(0),
+        shard_group(),
+        *qc.block_id(),
+        &qc,
+        FixedHash::zero(),
+        &FixedLeaderStrategy::new(TestAddress::new("1")),
+        &committee,
+        0,
+        0,
+        FixedHash::zero(),
+    )
+    .unwrap();
+    assert_eq!(count, 3);
+    assert_eq!(dummy.height(), NodeHeight(3));
+
+    let justify_block = create_block(Epoch(0), shard_group(), NodeHeight(0), qc);
+    let candidate_block = create_block(Epoch(0), shard_group(), NodeHeight(4), justify_block.justify().clone());
+    let dummies = calculate_dummy_blocks_from_justify(
+        &candidate_block,
+        &justify_block,
+        &FixedLeaderStrategy::new(TestAddress::new("1")),
+        &committee,
+    )
+    .unwrap();
+    assert_eq!(dummies.len(), 3);
+    assert!(dummies
+        .iter()
+        .all(|dummy| dummy.is_dummy() && *dummy.proposed_by() == leader_public_key));
+}
+
+#[test]
+fn it_rejects_a_justify_block_from_another_epoch() {
+    let (committee, _) = create_committee();
+    let justify_block = create_block(
+        Epoch(0),
+        shard_group(),
+        NodeHeight(0),
+        QuorumCertificate::genesis(Epoch(0), shard_group()),
+    );
+    let candidate_block = create_block(
+        Epoch(1),
+        shard_group(),
+        NodeHeight(4),
+        QuorumCertificate::genesis(Epoch(1), shard_group()),
+    );
+
+    let err = calculate_dummy_blocks_from_justify(
+        &candidate_block,
+        &justify_block,
+        &FixedLeaderStrategy::new(TestAddress::new("0")),
+        &committee,
+    )
+    .unwrap_err();
+    assert!(matches!(err, ProposalValidationError::JustifyBlockMismatch { .. }));
+}
+
+#[test]
+fn it_rejects_a_justify_block_from_another_shard_group() {
+    let (committee, _) = create_committee();
+    let other_shard_group = ShardGroup::new(0, 31);
+    let justify_block = create_block(
+        Epoch(0),
+        other_shard_group,
+        NodeHeight(0),
+        QuorumCertificate::genesis(Epoch(0), other_shard_group),
+    );
+    let candidate_block = create_block(
+        Epoch(0),
+        shard_group(),
+        NodeHeight(4),
+        QuorumCertificate::genesis(Epoch(0), shard_group()),
+    );
+
+    let err = calculate_dummy_blocks_from_justify(
+        &candidate_block,
+        &justify_block,
+        &FixedLeaderStrategy::new(TestAddress::new("0")),
+        &committee,
+    )
+    .unwrap_err();
+    assert!(matches!(err, ProposalValidationError::JustifyBlockMismatch { .. }));
+}
+
+fn shard_group() -> ShardGroup {
+    ShardGroup::all_shards(NumPreshards::P64)
+}
+
+/// Returns a committee and the public key of the member with address "1"
+fn create_committee() -> (Committee<TestAddress>, PublicKey) {
+    let members = (0..NUM_VALIDATORS)
+        .map(|i| {
+            let addr = TestAddress::new(i.to_string());
+            let (_, public_key) = helpers::derive_keypair_from_address(&addr);
+            (addr, public_key)
+        })
+        .collect::<Vec<_>>();
+    let leader_public_key = members[1].1.clone();
+    (Committee::new(members), leader_public_key)
+}
+
+fn create_block(epoch: Epoch, shard_group: ShardGroup, height: NodeHeight, justify: QuorumCertificate) -> Block {
+    Block::create(
+        Network::LocalNet,
+        *justify.block_id(),
+        justify,
+        height,
+        epoch,
+        shard_group,
+        Default::default(),
+        Default::default(),
+        Default::default(),
+        0,
+        Default::default(),
+        None,
+        0,
+        0,
+        FixedHash::zero(),
+        ExtraData::default(),
+    )
+    .unwrap()
+}
@@ -3,6 +3,12 @@
 #[cfg(test)]
 mod consensus;
 #[cfg(test)]
+mod dummy_blocks;
+#[cfg(test)]
+mod quorum_certificate;
+#[cfg(test)]
 mod substate_store;
 #[cfg(test)]
 mod support;
+#[cfg(test)]
+mod sync_request;
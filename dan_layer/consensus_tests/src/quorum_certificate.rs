@@ -0,0 +1,99 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use tari_common_types::types::{FixedHash, PrivateKey};
+use tari_consensus::{
+    hotstuff::{HotStuffError, ProposalValidationError},
+    test_helpers::check_quorum_certificate,
+};
+use tari_dan_common_types::{
+    committee::{Committee, CommitteeInfo},
+    Epoch,
+    ExtraData,
+    NodeHeight,
+    NumPreshards,
+    ShardGroup,
+};
+use tari_dan_storage::consensus_models::{Block, QuorumCertificate, QuorumCertificateBuilder};
+
+use crate::support::{helpers, TestAddress, TestConsensusSpec, TestVoteSignatureService};
+
+const NUM_VALIDATORS: usize = 4;
+
+#[test]
+fn it_builds_a_qc_that_passes_validation() {
+    let (committee, signers) = create_committee();
+    let qc = QuorumCertificateBuilder::new(Epoch(0), shard_group())
+        .with_height(NodeHeight(1))
+        .with_signers(signers)
+        .build();
+
+    check_quorum_certificate::<TestConsensusSpec>(
+        &create_block_justified_by(qc),
+        &committee,
+        &committee_info(),
+        &TestVoteSignatureService::new(TestAddress::new("0")),
+    )
+    .unwrap();
+}
+
+#[test]
+fn it_builds_a_qc_signed_by_random_validators_by_default() {
+    let (committee, _) = create_committee();
+    let qc = QuorumCertificateBuilder::new(Epoch(0), shard_group())
+        .with_height(NodeHeight(1))
+        .build();
+
+    let err = check_quorum_certificate::<TestConsensusSpec>(
+        &create_block_justified_by(qc),
+        &committee,
+        &committee_info(),
+        &TestVoteSignatureService::new(TestAddress::new("0")),
+    )
+    .unwrap_err();
+    assert!(matches!(
+        err,
+        HotStuffError::ProposalValidationError(ProposalValidationError::ValidatorNotInCommittee { .. })
+    ));
+}
+
+fn shard_group() -> ShardGroup {
+    ShardGroup::all_shards(NumPreshards::P64)
+}
+
+fn committee_info() -> CommitteeInfo {
+    CommitteeInfo::new(NumPreshards::P64, NUM_VALIDATORS as u32, 1, shard_group())
+}
+
+fn create_committee() -> (Committee<TestAddress>, Vec<PrivateKey>) {
+    let (members, signers) = (0..NUM_VALIDATORS)
+        .map(|i| {
+            let addr = TestAddress::new(i.to_string());
+            let (secret_key, public_key) = helpers::derive_keypair_from_address(&addr);
+            ((addr, public_key), secret_key)
+        })
+        .unzip();
+    (Committee::new(members), signers)
+}
+
+fn create_block_justified_by(qc: QuorumCertificate) -> Block {
+    Block::create(
+        Default::default(),
+        *qc.block_id(),
+        qc.clone(),
+        qc.block_height() + NodeHeight(1),
+        qc.epoch(),
+        qc.shard_group(),
+        Default::default(),
+        Default::default(),
+        Default::default(),
+        0,
+        Default::default(),
+        None,
+        0,
+        0,
+        FixedHash::zero(),
+        ExtraData::default(),
+    )
+    .unwrap()
+}
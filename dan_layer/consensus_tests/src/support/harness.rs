@@ -539,6 +539,11 @@ impl TestBuilder {
                     fee_exhaust_divisor: 20,
                     epochs_per_era: Epoch(10),
                 },
+                sync_response_message_interval: None,
+                sync_response_timeout: None,
+                sync_response_send_timeout: None,
+                sync_request_max_blocks: 0,
+                sync_request_verify_qc: true,
             },
         }
     }
@@ -595,6 +600,11 @@ impl TestBuilder {
         self
     }
 
+    pub fn with_sync_request_max_blocks(mut self, max_blocks: u64) -> Self {
+        self.config.sync_request_max_blocks = max_blocks;
+        self
+    }
+
     async fn build_validators(
         leader_strategy: &RoundRobinLeaderStrategy,
         epoch_manager: &TestEpochManager,
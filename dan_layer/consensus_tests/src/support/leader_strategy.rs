@@ -40,4 +40,17 @@ mod tests {
         let (addr, _) = strategy.get_leader(&committee, NodeHeight(3));
         assert_eq!(addr, "1");
     }
+
+    #[test]
+    fn it_returns_the_leader_schedule_for_a_height_range() {
+        let strategy = RoundRobinLeaderStrategy::new();
+        let committee = Committee::from_iter([new_member("1"), new_member("2"), new_member("3")]);
+
+        let schedule = strategy.get_leader_schedule(&committee, NodeHeight(1), NodeHeight(4));
+        let expected = [1, 2, 0, 1]
+            .into_iter()
+            .map(|i| &committee.members[i].1)
+            .collect::<Vec<_>>();
+        assert_eq!(schedule, expected);
+    }
 }
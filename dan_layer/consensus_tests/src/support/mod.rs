@@ -27,6 +27,7 @@ pub use executions_store::ExecuteSpec;
 pub use harness::*;
 pub use leader_strategy::*;
 pub use network::*;
+pub use signing_service::*;
 pub use spec::*;
 use tari_dan_common_types::NumPreshards;
 pub use transaction::*;
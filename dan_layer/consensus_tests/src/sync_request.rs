@@ -0,0 +1,166 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use tari_common_types::types::PrivateKey;
+use tari_consensus::hotstuff::{check_sync_request_high_qc, HotStuffError};
+use tari_dan_common_types::{
+    committee::{Committee, CommitteeInfo},
+    Epoch,
+    NodeHeight,
+    NumPreshards,
+    ShardGroup,
+};
+use tari_dan_storage::consensus_models::{HighQc, QuorumCertificate, QuorumCertificateBuilder};
+
+use crate::support::{helpers, TestAddress, TestConsensusSpec, TestVoteSignatureService};
+
+const NUM_VALIDATORS: usize = 4;
+
+#[test]
+fn it_accepts_a_qc_signed_by_the_committee() {
+    let (committee, signers) = create_committee();
+    let qc = QuorumCertificateBuilder::new(Epoch(0), shard_group())
+        .with_height(NodeHeight(1))
+        .with_signers(signers)
+        .build();
+
+    check(&qc, &committee).unwrap();
+}
+
+#[test]
+fn it_accepts_the_genesis_qc() {
+    let (committee, _) = create_committee();
+    let qc = QuorumCertificate::genesis(Epoch(0), shard_group());
+
+    check(&qc, &committee).unwrap();
+}
+
+#[test]
+fn it_rejects_a_qc_signed_by_validators_outside_the_committee() {
+    let (committee, _) = create_committee();
+    let qc = QuorumCertificateBuilder::new(Epoch(0), shard_group())
+        .with_height(NodeHeight(1))
+        .build();
+
+    let err = check(&qc, &committee).unwrap_err();
+    assert!(matches!(err, HotStuffError::InvalidSyncRequest { .. }));
+}
+
+#[test]
+fn it_rejects_a_qc_without_a_quorum() {
+    let (committee, mut signers) = create_committee();
+    signers.truncate(1);
+    let qc = QuorumCertificateBuilder::new(Epoch(0), shard_group())
+        .with_height(NodeHeight(1))
+        .with_signers(signers)
+        .build();
+
+    let err = check(&qc, &committee).unwrap_err();
+    assert!(matches!(err, HotStuffError::InvalidSyncRequest { .. }));
+}
+
+#[test]
+fn it_rejects_a_qc_for_another_shard_group() {
+    let (committee, signers) = create_committee();
+    let qc = QuorumCertificateBuilder::new(Epoch(0), ShardGroup::new(0, 31))
+        .with_height(NodeHeight(1))
+        .with_signers(signers)
+        .build();
+
+    let err = check(&qc, &committee).unwrap_err();
+    assert!(matches!(err, HotStuffError::InvalidSyncRequest { .. }));
+}
+
+#[test]
+fn it_rejects_a_genesis_qc_for_another_shard_group() {
+    let (committee, _) = create_committee();
+    let qc = QuorumCertificate::genesis(Epoch(0), ShardGroup::new(0, 31));
+
+    let err = check(&qc, &committee).unwrap_err();
+    assert!(matches!(err, HotStuffError::InvalidSyncRequest { .. }));
+}
+
+#[test]
+fn it_rejects_a_genesis_qc_for_another_epoch() {
+    let (committee, _) = create_committee();
+    let qc = QuorumCertificate::genesis(Epoch(1), shard_group());
+
+    let err = check(&qc, &committee).unwrap_err();
+    assert!(matches!(err, HotStuffError::InvalidSyncRequest { .. }));
+}
+
+#[test]
+fn it_rejects_a_request_without_the_qc_certificate() {
+    let (committee, _) = create_committee();
+    let high_qc = QuorumCertificate::genesis(Epoch(0), shard_group()).as_high_qc();
+
+    let err = check_sync_request_high_qc::<TestConsensusSpec>(
+        Epoch(0),
+        &high_qc,
+        None,
+        &committee,
+        &committee_info(),
+        &signing_service(),
+    )
+    .unwrap_err();
+    assert!(matches!(err, HotStuffError::InvalidSyncRequest { .. }));
+}
+
+#[test]
+fn it_rejects_a_qc_certificate_for_another_high_qc() {
+    let (committee, signers) = create_committee();
+    let qc = QuorumCertificateBuilder::new(Epoch(0), shard_group())
+        .with_height(NodeHeight(1))
+        .with_signers(signers)
+        .build();
+    let high_qc = HighQc {
+        block_height: NodeHeight(2),
+        ..qc.as_high_qc()
+    };
+
+    let err = check_sync_request_high_qc::<TestConsensusSpec>(
+        Epoch(0),
+        &high_qc,
+        Some(&qc),
+        &committee,
+        &committee_info(),
+        &signing_service(),
+    )
+    .unwrap_err();
+    assert!(matches!(err, HotStuffError::InvalidSyncRequest { .. }));
+}
+
+/// Checks a request for `qc` that includes its certificate, in epoch 0
+fn check(qc: &QuorumCertificate, committee: &Committee<TestAddress>) -> Result<(), HotStuffError> {
+    check_sync_request_high_qc::<TestConsensusSpec>(
+        Epoch(0),
+        &qc.as_high_qc(),
+        Some(qc),
+        committee,
+        &committee_info(),
+        &signing_service(),
+    )
+}
+
+fn shard_group() -> ShardGroup {
+    ShardGroup::all_shards(NumPreshards::P64)
+}
+
+fn committee_info() -> CommitteeInfo {
+    CommitteeInfo::new(NumPreshards::P64, NUM_VALIDATORS as u32, 1, shard_group())
+}
+
+fn signing_service() -> TestVoteSignatureService {
+    TestVoteSignatureService::new(TestAddress::new("0"))
+}
+
+fn create_committee() -> (Committee<TestAddress>, Vec<PrivateKey>) {
+    let (members, signers) = (0..NUM_VALIDATORS)
+        .map(|i| {
+            let addr = TestAddress::new(i.to_string());
+            let (secret_key, public_key) = helpers::derive_keypair_from_address(&addr);
+            ((addr, public_key), secret_key)
+        })
+        .unzip();
+    (Committee::new(members), signers)
+}
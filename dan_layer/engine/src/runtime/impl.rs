@@ -815,7 +815,7 @@ impl<TTemplateProvider: TemplateProvider<Template = LoadedTemplate>> RuntimeInte
                     let mut output_bucket = None;
                     if let Some(mint_arg) = arg.mint_arg {
                         let bucket_id = state.id_provider()?.new_bucket_id();
-                        let container = state.mint_resource(&resource_lock, mint_arg)?;
+                        let container = state.mint_resource(&resource_lock, mint_arg, self.network)?;
                         state.new_bucket(bucket_id, container)?;
                         output_bucket = Some(tari_template_lib::models::Bucket::from_id(bucket_id));
                     }
@@ -893,7 +893,7 @@ impl<TTemplateProvider: TemplateProvider<Template = LoadedTemplate>> RuntimeInte
                 }
 
                 self.tracker.write_with(|state| {
-                    let resource = state.mint_resource(&resource_lock, mint_resource.mint_arg)?;
+                    let resource = state.mint_resource(&resource_lock, mint_resource.mint_arg, self.network)?;
                     let bucket_id = state.id_provider()?.new_bucket_id();
                     state.new_bucket(bucket_id, resource)?;
 
@@ -1262,7 +1262,8 @@ impl<TTemplateProvider: TemplateProvider<Template = LoadedTemplate>> RuntimeInte
                         },
                         VaultWithdrawArg::Confidential { proof } => {
                             let amount = proof.revealed_input_amount();
-                            let container = vault_mut.withdraw_confidential(*proof, maybe_view_key.as_ref())?;
+                            let container =
+                                vault_mut.withdraw_confidential(*proof, maybe_view_key.as_ref(), self.network)?;
                             (container, amount)
                         },
                     };
@@ -1397,7 +1398,8 @@ impl<TTemplateProvider: TemplateProvider<Template = LoadedTemplate>> RuntimeInte
                     let view_key = resource.view_key().cloned();
 
                     let vault_mut = state.get_vault_mut(&vault_lock)?;
-                    let resource_container = vault_mut.reveal_confidential(arg.proof, view_key.as_ref())?;
+                    let resource_container =
+                        vault_mut.reveal_confidential(arg.proof, view_key.as_ref(), self.network)?;
                     let bucket_id = state.id_provider()?.new_bucket_id();
                     state.new_bucket(bucket_id, resource_container)?;
 
@@ -1452,7 +1454,7 @@ impl<TTemplateProvider: TemplateProvider<Template = LoadedTemplate>> RuntimeInte
                         container.deposit(withdrawn)?;
                     }
                     if let Some(proof) = arg.proof {
-                        let revealed = vault_mut.reveal_confidential(proof, view_key.as_ref())?;
+                        let revealed = vault_mut.reveal_confidential(proof, view_key.as_ref(), self.network)?;
                         container.deposit(revealed)?;
                     }
                     if container.amount().is_zero() {
@@ -1704,7 +1706,7 @@ impl<TTemplateProvider: TemplateProvider<Template = LoadedTemplate>> RuntimeInte
                     let resource = state.get_resource(&resource_lock)?;
                     let view_key = resource.view_key().cloned();
                     let bucket_mut = state.get_bucket_mut(bucket_id)?;
-                    let resource = bucket_mut.take_confidential(proof, view_key.as_ref())?;
+                    let resource = bucket_mut.take_confidential(proof, view_key.as_ref(), self.network)?;
                     let bucket_id = state.id_provider()?.new_bucket_id();
                     state.new_bucket(bucket_id, resource)?;
                     state.unlock_substate(resource_lock)?;
@@ -1737,7 +1739,7 @@ impl<TTemplateProvider: TemplateProvider<Template = LoadedTemplate>> RuntimeInte
                     let resource = state.get_resource(&resource_lock)?;
                     let view_key = resource.view_key().cloned();
                     let bucket = state.get_bucket_mut(bucket_id)?;
-                    let resource = bucket.reveal_confidential(proof, view_key.as_ref())?;
+                    let resource = bucket.reveal_confidential(proof, view_key.as_ref(), self.network)?;
                     let bucket_id = state.id_provider()?.new_bucket_id();
                     state.new_bucket(bucket_id, resource)?;
                     state.unlock_substate(resource_lock)?;
@@ -2233,7 +2235,7 @@ impl<TTemplateProvider: TemplateProvider<Template = LoadedTemplate>> RuntimeInte
         // If a withdraw proof is provided, we execute it and deposit back into the resource
         // This allows some funds to be revealed and/or reblinded within a single instruction
         if let Some(proof) = withdraw_proof {
-            let withdraw = resource.withdraw_confidential(proof, None)?;
+            let withdraw = resource.withdraw_confidential(proof, None, self.network)?;
             resource.deposit(withdraw)?;
         }
 
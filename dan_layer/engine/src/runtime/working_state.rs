@@ -9,6 +9,7 @@ use std::{
 
 use indexmap::IndexMap;
 use log::*;
+use tari_common::configuration::Network;
 use tari_common_types::types::PublicKey;
 use tari_crypto::tari_utilities::ByteArray;
 use tari_dan_common_types::{optional::Optional, Epoch};
@@ -468,6 +469,7 @@ impl WorkingState {
         &mut self,
         locked_resource: &LockedSubstate,
         mint_arg: MintArg,
+        network: Network,
     ) -> Result<ResourceContainer, RuntimeError> {
         let resource_address =
             locked_resource
@@ -539,7 +541,7 @@ impl WorkingState {
                     target: LOG_TARGET,
                     "Minting confidential tokens on resource: {}", resource_address
                 );
-                ResourceContainer::mint_confidential(resource_address, *proof, resource.view_key())?
+                ResourceContainer::mint_confidential(resource_address, *proof, resource.view_key(), network)?
             },
         };
 
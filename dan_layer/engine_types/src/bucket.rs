@@ -23,6 +23,7 @@
 use std::collections::BTreeSet;
 
 use serde::{Deserialize, Serialize};
+use tari_common::configuration::Network;
 use tari_common_types::types::PublicKey;
 use tari_template_lib::{
     models::{Amount, BucketId, ConfidentialWithdrawProof, NonFungibleId, ResourceAddress},
@@ -88,8 +89,9 @@ impl Bucket {
         &mut self,
         proof: ConfidentialWithdrawProof,
         view_key: Option<&PublicKey>,
+        network: Network,
     ) -> Result<ResourceContainer, ResourceError> {
-        self.resource_container.withdraw_confidential(proof, view_key)
+        self.resource_container.withdraw_confidential(proof, view_key, network)
     }
 
     pub fn join(&mut self, other: Bucket) -> Result<(), ResourceError> {
@@ -100,8 +102,9 @@ impl Bucket {
         &mut self,
         proof: ConfidentialWithdrawProof,
         view_key: Option<&PublicKey>,
+        network: Network,
     ) -> Result<ResourceContainer, ResourceError> {
-        self.resource_container.reveal_confidential(proof, view_key)
+        self.resource_container.reveal_confidential(proof, view_key, network)
     }
 
     pub fn lock_all(&mut self) -> Result<LockedResource, ResourceError> {
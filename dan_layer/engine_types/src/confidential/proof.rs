@@ -15,14 +15,31 @@ lazy_static! {
         BulletproofsPlusService::init(64, 2, CommitmentFactory::default()).unwrap();
 }
 
+/// The largest aggregation factor for which a range proof service is available
+pub const MAX_RANGE_PROOF_AGGREGATION_FACTOR: usize = 2;
+
+/// Returns the range proof service for the aggregation factor.
+///
+/// # Panics
+/// If the aggregation factor is not supported. Use [try_get_range_proof_service] if the aggregation factor is not
+/// known to be valid.
 pub fn get_range_proof_service(aggregation_factor: usize) -> &'static BulletproofsPlusService {
+    try_get_range_proof_service(aggregation_factor).unwrap_or_else(|| {
+        panic!(
+            "Unsupported BP aggregation factor {}. Expected 1 to {}",
+            aggregation_factor, MAX_RANGE_PROOF_AGGREGATION_FACTOR
+        )
+    })
+}
+
+/// Returns the range proof service for the aggregation factor, or None if it is not between 1 and
+/// [MAX_RANGE_PROOF_AGGREGATION_FACTOR]. The supported aggregation factors are all powers of two, so witnesses never
+/// need to be padded.
+pub fn try_get_range_proof_service(aggregation_factor: usize) -> Option<&'static BulletproofsPlusService> {
     match aggregation_factor {
-        1 => &RANGE_PROOF_AGG_1_SERVICE,
-        2 => &RANGE_PROOF_AGG_2_SERVICE,
-        _ => panic!(
-            "Unsupported BP aggregation factor {}. Expected 1 or 2",
-            aggregation_factor
-        ),
+        1 => Some(&RANGE_PROOF_AGG_1_SERVICE),
+        2 => Some(&RANGE_PROOF_AGG_2_SERVICE),
+        _ => None,
     }
 }
 
@@ -31,6 +48,7 @@ pub fn get_commitment_factory() -> &'static CommitmentFactory {
 }
 
 pub mod challenges {
+    use tari_common::configuration::Network;
     use tari_common_types::types::{Commitment, PublicKey};
     use tari_template_lib::{
         models::{Amount, ViewableBalanceProofChallengeFields},
@@ -65,18 +83,30 @@ pub mod challenges {
             .result()
     }
 
+    /// The challenge is bound to the network so that a viewable balance proof created for one network is invalid on
+    /// any other network
     pub fn viewable_balance_proof_challenge64(
+        network: Network,
         commitment: &Commitment,
         view_key: &PublicKey,
         challenge_fields: ViewableBalanceProofChallengeFields<'_>,
     ) -> [u8; 64] {
         hasher64(EngineHashDomainLabel::ViewKey)
+            .chain(&network.as_byte())
             .chain(commitment)
             .chain(view_key)
             .chain(&challenge_fields)
             .result()
     }
 
+    pub fn commitment_opening_proof64(commitment: &Commitment, value: u64, public_nonce: &PublicKey) -> [u8; 64] {
+        hasher64(EngineHashDomainLabel::CommitmentOpening)
+            .chain(commitment)
+            .chain(&value)
+            .chain(public_nonce)
+            .result()
+    }
+
     pub fn confidential_commitment_proof32(
         public_key: &PublicKey,
         public_nonce: &PublicKey,
@@ -1,6 +1,7 @@
 //   Copyright 2023 The Tari Project
 //   SPDX-License-Identifier: BSD-3-Clause
 
+use tari_common::configuration::Network;
 use tari_common_types::types::{Commitment, PrivateKey, PublicKey};
 use tari_crypto::{
     commitment::HomomorphicCommitmentFactory,
@@ -11,7 +12,7 @@ use tari_crypto::{
 };
 use tari_template_lib::models::{Amount, ConfidentialOutputStatement, ViewableBalanceProof};
 
-use super::{challenges, get_commitment_factory, get_range_proof_service};
+use super::{challenges, get_commitment_factory, try_get_range_proof_service, MAX_RANGE_PROOF_AGGREGATION_FACTOR};
 use crate::{
     confidential::{elgamal::ElgamalVerifiableBalance, ConfidentialOutput},
     resource_container::ResourceError,
@@ -28,6 +29,7 @@ pub struct ValidatedConfidentialProof {
 pub fn validate_confidential_proof(
     proof: &ConfidentialOutputStatement,
     view_key: Option<&PublicKey>,
+    network: Network,
 ) -> Result<ValidatedConfidentialProof, ResourceError> {
     if proof.output_revealed_amount.is_negative() || proof.change_revealed_amount.is_negative() {
         return Err(ResourceError::InvalidConfidentialProof {
@@ -55,6 +57,7 @@ pub fn validate_confidential_proof(
                 &output_commitment,
                 view_key,
                 statement.viewable_balance_proof.as_ref(),
+                network,
             )?;
 
             Ok(ConfidentialOutput {
@@ -83,8 +86,12 @@ pub fn validate_confidential_proof(
                 }
             })?;
 
-            let viewable_balance =
-                validate_elgamal_verifiable_balance_proof(&commitment, view_key, stmt.viewable_balance_proof.as_ref())?;
+            let viewable_balance = validate_elgamal_verifiable_balance_proof(
+                &commitment,
+                view_key,
+                stmt.viewable_balance_proof.as_ref(),
+                network,
+            )?;
 
             Ok(ConfidentialOutput {
                 commitment,
@@ -120,6 +127,7 @@ pub fn validate_elgamal_verifiable_balance_proof(
     commitment: &Commitment,
     view_key: Option<&PublicKey>,
     viewable_balance_proof: Option<&ViewableBalanceProof>,
+    network: Network,
 ) -> Result<Option<ElgamalVerifiableBalance>, ResourceError> {
     // Check that if a view key is provided, then a viewable balance proof is also provided and vice versa
     let Some(view_key) = view_key else {
@@ -137,48 +145,61 @@ pub fn validate_elgamal_verifiable_balance_proof(
         });
     };
 
-    // Decode and check that each field is well-formed
-    let encrypted = PublicKey::from_canonical_bytes(&*proof.elgamal_encrypted).map_err(|_| {
-        ResourceError::InvalidConfidentialProof {
-            details: "Invalid value for E".to_string(),
-        }
-    })?;
-
-    let elgamal_public_nonce = PublicKey::from_canonical_bytes(&*proof.elgamal_public_nonce).map_err(|_| {
+    let balance = verify_viewable_balance_proof(commitment, view_key, proof, network).map_err(|failure| {
         ResourceError::InvalidConfidentialProof {
-            details: "Invalid public key for R".to_string(),
+            details: failure.to_string(),
         }
     })?;
+    Ok(Some(balance))
+}
 
-    let c_prime =
-        Commitment::from_canonical_bytes(&*proof.c_prime).map_err(|_| ResourceError::InvalidConfidentialProof {
-            details: "Invalid commitment for C'".to_string(),
-        })?;
-
-    let e_prime =
-        Commitment::from_canonical_bytes(&*proof.e_prime).map_err(|_| ResourceError::InvalidConfidentialProof {
-            details: "Invalid commitment for E'".to_string(),
-        })?;
-
-    let r_prime =
-        PublicKey::from_canonical_bytes(&*proof.r_prime).map_err(|_| ResourceError::InvalidConfidentialProof {
-            details: "Invalid public key for R'".to_string(),
-        })?;
-
-    let s_v = PrivateKey::from_canonical_bytes(&*proof.s_v).map_err(|_| ResourceError::InvalidConfidentialProof {
-        details: "Invalid private key for s_v".to_string(),
-    })?;
-
-    let s_m = PrivateKey::from_canonical_bytes(&*proof.s_m).map_err(|_| ResourceError::InvalidConfidentialProof {
-        details: "Invalid private key for s_m".to_string(),
-    })?;
+/// The reason a viewable balance proof is invalid
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum ViewableProofFailure {
+    #[error("Invalid point for {field}")]
+    MalformedPoint { field: &'static str },
+    #[error("Invalid scalar for {field}")]
+    MalformedScalar { field: &'static str },
+    /// The commitment check, proving knowledge of the value and mask of the commitment, failed
+    #[error("Invalid viewable balance proof (eC + C' != s_m.G + s_v.H)")]
+    MaskCheck,
+    /// The ElGamal check, proving that the encrypted value is the committed value, failed
+    #[error("Invalid viewable balance proof (eE + E' != s_v.G + s_r.P)")]
+    ValueCheck,
+    /// The ElGamal nonce check failed
+    #[error("Invalid viewable balance proof (eR + R' != s_r.G)")]
+    NonceCheck,
+}
 
-    let s_r = &PrivateKey::from_canonical_bytes(&*proof.s_r).map_err(|_| ResourceError::InvalidConfidentialProof {
-        details: "Invalid private key for s_r".to_string(),
-    })?;
+/// Verifies a single viewable balance proof for the commitment and view key, returning which check failed if it is
+/// invalid. This is useful for diagnosing proofs produced by other implementations.
+pub fn verify_viewable_balance_proof(
+    commitment: &Commitment,
+    view_key: &PublicKey,
+    proof: &ViewableBalanceProof,
+    network: Network,
+) -> Result<ElgamalVerifiableBalance, ViewableProofFailure> {
+    // Decode and check that each field is well-formed
+    let point = |bytes: &[u8], field| {
+        PublicKey::from_canonical_bytes(bytes).map_err(|_| ViewableProofFailure::MalformedPoint { field })
+    };
+    let scalar = |bytes: &[u8], field| {
+        PrivateKey::from_canonical_bytes(bytes).map_err(|_| ViewableProofFailure::MalformedScalar { field })
+    };
+    let encrypted = point(&*proof.elgamal_encrypted, "E")?;
+    let elgamal_public_nonce = point(&*proof.elgamal_public_nonce, "R")?;
+    let c_prime = Commitment::from_canonical_bytes(&*proof.c_prime)
+        .map_err(|_| ViewableProofFailure::MalformedPoint { field: "C'" })?;
+    let e_prime = Commitment::from_canonical_bytes(&*proof.e_prime)
+        .map_err(|_| ViewableProofFailure::MalformedPoint { field: "E'" })?;
+    let r_prime = point(&*proof.r_prime, "R'")?;
+    let s_v = scalar(&*proof.s_v, "s_v")?;
+    let s_m = scalar(&*proof.s_m, "s_m")?;
+    let s_r = &scalar(&*proof.s_r, "s_r")?;
 
     // Fiat-Shamir challenge
     let e = &RistrettoSecretKey::from_uniform_bytes(&challenges::viewable_balance_proof_challenge64(
+        network,
         commitment,
         view_key,
         proof.as_challenge_fields(),
@@ -190,33 +211,27 @@ pub fn validate_elgamal_verifiable_balance_proof(
     let left = e * commitment.as_public_key() + c_prime.as_public_key();
     let right = get_commitment_factory().commit(&s_m, &s_v);
     if left != *right.as_public_key() {
-        return Err(ResourceError::InvalidConfidentialProof {
-            details: "Invalid viewable balance proof (eC + C' != s_m.G + s_v.H)".to_string(),
-        });
+        return Err(ViewableProofFailure::MaskCheck);
     }
 
     // Check eE + E' ?= s_v.G + s_r.P
     let left = e * &encrypted + e_prime.as_public_key();
     let right = PublicKey::from_secret_key(&s_v) + s_r * view_key;
     if left != right {
-        return Err(ResourceError::InvalidConfidentialProof {
-            details: "Invalid viewable balance proof (eE + E' != s_v.G + s_r.P)".to_string(),
-        });
+        return Err(ViewableProofFailure::ValueCheck);
     }
 
     // Check eR + R' ?= s_r.G
     let left = e * &elgamal_public_nonce + r_prime;
     let right = PublicKey::from_secret_key(s_r);
     if left != right {
-        return Err(ResourceError::InvalidConfidentialProof {
-            details: "Invalid viewable balance proof (eR + R' != s_r.G)".to_string(),
-        });
+        return Err(ViewableProofFailure::NonceCheck);
     }
 
-    Ok(Some(ElgamalVerifiableBalance {
+    Ok(ElgamalVerifiableBalance {
         encrypted,
         public_nonce: elgamal_public_nonce,
-    }))
+    })
 }
 
 fn validate_bullet_proof(proof: &ConfidentialOutputStatement) -> Result<(), ResourceError> {
@@ -251,8 +266,15 @@ fn validate_bullet_proof(proof: &ConfidentialOutputStatement) -> Result<(), Reso
 
     let public_statement = RistrettoAggregatedPublicStatement::init(statements).unwrap();
 
+    let range_proof_service =
+        try_get_range_proof_service(agg_factor).ok_or_else(|| ResourceError::InvalidConfidentialProof {
+            details: format!(
+                "Range proof aggregation factor {agg_factor} exceeds the maximum of \
+                 {MAX_RANGE_PROOF_AGGREGATION_FACTOR}"
+            ),
+        })?;
     let proofs = vec![&proof.range_proof];
-    get_range_proof_service(agg_factor)
+    range_proof_service
         .verify_batch(proofs, vec![&public_statement])
         .map_err(|e| ResourceError::InvalidConfidentialProof {
             details: format!("Invalid range proof: {}", e),
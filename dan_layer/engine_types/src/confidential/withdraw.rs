@@ -2,6 +2,7 @@
 //   SPDX-License-Identifier: BSD-3-Clause
 
 use serde::{Deserialize, Serialize};
+use tari_common::configuration::Network;
 use tari_common_types::types::{BulletRangeProof, Commitment, PrivateKey, PublicKey, Signature};
 use tari_crypto::{commitment::HomomorphicCommitmentFactory, tari_utilities::ByteArray};
 use tari_template_lib::{
@@ -50,13 +51,19 @@ pub(crate) fn validate_confidential_withdraw<'a, I: IntoIterator<Item = &'a Comm
     inputs: I,
     view_key: Option<&PublicKey>,
     withdraw_proof: ConfidentialWithdrawProof,
+    network: Network,
 ) -> Result<ValidatedConfidentialWithdrawProof, ResourceError> {
-    let validated_proof = validate_confidential_proof(&withdraw_proof.output_proof, view_key)?;
+    let validated_proof = validate_confidential_proof(&withdraw_proof.output_proof, view_key, network)?;
 
     let input_revealed_amount = withdraw_proof.input_revealed_amount;
     // We expect the revealed amount to be excluded from the output commitment.
     let total_output_revealed_amount =
-        withdraw_proof.output_proof.output_revealed_amount + withdraw_proof.output_proof.change_revealed_amount;
+        withdraw_proof
+            .output_proof
+            .revealed_total()
+            .ok_or_else(|| ResourceError::InvalidBalanceProof {
+                details: "Revealed output and change amounts overflow".to_string(),
+            })?;
 
     // Balance proof not required if only revealed funds are transferred
     if withdraw_proof.is_revealed_only() {
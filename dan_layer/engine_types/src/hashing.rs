@@ -183,6 +183,7 @@ pub enum EngineHashDomainLabel {
     QuorumCertificate,
     SubstateValue,
     ViewKey,
+    CommitmentOpening,
 }
 
 impl EngineHashDomainLabel {
@@ -206,6 +207,7 @@ impl EngineHashDomainLabel {
             Self::QuorumCertificate => "QuorumCertificate",
             Self::SubstateValue => "SubstateValue",
             Self::ViewKey => "ViewKey",
+            Self::CommitmentOpening => "CommitmentOpening",
         }
     }
 }
@@ -8,6 +8,7 @@
 use std::{collections::BTreeMap, mem};
 
 use serde::{Deserialize, Serialize};
+use tari_common::configuration::Network;
 use tari_common_types::types::{Commitment, PublicKey};
 use tari_crypto::tari_utilities::ByteArray;
 use tari_template_abi::rust::collections::BTreeSet;
@@ -93,6 +94,7 @@ impl ResourceContainer {
         address: ResourceAddress,
         proof: ConfidentialOutputStatement,
         view_key: Option<&PublicKey>,
+        network: Network,
     ) -> Result<ResourceContainer, ResourceError> {
         if proof.change_statement.is_some() {
             return Err(ResourceError::InvalidConfidentialMintWithChange);
@@ -102,7 +104,7 @@ impl ResourceContainer {
                 details: "Change revealed amount must be zero for minting".to_string(),
             });
         }
-        let validated_proof = validate_confidential_proof(&proof, view_key)?;
+        let validated_proof = validate_confidential_proof(&proof, view_key, network)?;
         assert!(
             validated_proof.change_output.is_none(),
             "invariant failed: validate_confidential_proof returned change with no change in input proof"
@@ -367,6 +369,7 @@ impl ResourceContainer {
         &mut self,
         proof: ConfidentialWithdrawProof,
         view_key: Option<&PublicKey>,
+        network: Network,
     ) -> Result<ResourceContainer, ResourceError> {
         match self {
             ResourceContainer::Fungible { .. } => Err(ResourceError::OperationNotAllowed(
@@ -401,7 +404,7 @@ impl ResourceContainer {
                     })
                     .collect::<Result<Vec<_>, ResourceError>>()?;
 
-                let validated_proof = validate_confidential_withdraw(&inputs, view_key, proof)?;
+                let validated_proof = validate_confidential_withdraw(&inputs, view_key, proof, network)?;
 
                 // Withdraw revealed amount
                 if validated_proof.input_revealed_amount > *revealed_amount {
@@ -506,8 +509,9 @@ impl ResourceContainer {
         &mut self,
         proof: ConfidentialWithdrawProof,
         view_key: Option<&PublicKey>,
+        network: Network,
     ) -> Result<ResourceContainer, ResourceError> {
-        self.withdraw_confidential(proof, view_key)
+        self.withdraw_confidential(proof, view_key, network)
     }
 
     /// Returns all confidential commitments. If the resource is not confidential, None is returned.
@@ -22,6 +22,7 @@
 
 use std::{
     fmt::{Display, Formatter},
+    iter,
     str::FromStr,
 };
 
@@ -713,6 +714,23 @@ impl SubstateDiff {
     pub fn is_empty(&self) -> bool {
         self.len() == 0
     }
+
+    /// Splits the diff into smaller diffs containing at most `max_changes` changes each, so that a very large diff can
+    /// be applied to the state tree in bounded steps. All downs are yielded before any ups, as when the whole diff is
+    /// applied, so applying each chunk in order results in the same state (and state root) as applying the whole
+    /// diff at once. A `max_changes` of 0 is treated as 1.
+    pub fn chunked(self, max_changes: usize) -> impl Iterator<Item = SubstateDiff> {
+        let max_changes = max_changes.max(1);
+        let mut down_substates = self.down_substates.into_iter();
+        let mut up_substates = self.up_substates.into_iter();
+        iter::from_fn(move || {
+            let mut chunk = SubstateDiff::new();
+            chunk.extend_down(down_substates.by_ref().take(max_changes));
+            let remaining = max_changes - chunk.down_len();
+            chunk.extend_up(up_substates.by_ref().take(remaining));
+            (!chunk.is_empty()).then_some(chunk)
+        })
+    }
 }
 
 #[cfg(test)]
@@ -771,4 +789,51 @@ mod tests {
             check("commitment_7cbfe29101c24924b1b6ccefbfff98986d648622272ae24f7585dab5ffffffff");
         }
     }
+
+    mod chunked {
+        use tari_template_lib::models::{Amount, ObjectKey};
+
+        use super::*;
+        use crate::fee_claim::FeeClaim;
+
+        fn id(seed: u8) -> SubstateId {
+            SubstateId::Component(ComponentAddress::new(ObjectKey::from_array([seed; ObjectKey::LENGTH])))
+        }
+
+        fn substate(version: u32) -> Substate {
+            Substate::new(version, FeeClaim {
+                epoch: 0,
+                validator_public_key: Default::default(),
+                amount: Amount(1),
+            })
+        }
+
+        #[test]
+        fn it_yields_downs_before_ups_in_bounded_chunks() {
+            let mut diff = SubstateDiff::new();
+            for i in 0..3 {
+                diff.down(id(i), 0);
+                diff.up(id(i), substate(1));
+            }
+            diff.up(id(3), substate(0));
+
+            let chunks = diff.chunked(2).collect::<Vec<_>>();
+            assert_eq!(chunks.len(), 4);
+            assert!(chunks.iter().all(|chunk| chunk.len() <= 2));
+            assert_eq!(chunks[0].down_len(), 2);
+            assert_eq!(chunks[1].down_len(), 1);
+            assert_eq!(chunks[1].up_len(), 1);
+            assert_eq!(chunks[3].up_len(), 1);
+
+            let downs = chunks.iter().flat_map(|c| c.down_iter()).map(|(id, _)| id.clone());
+            assert!(downs.eq((0..3).map(id)));
+            let ups = chunks.iter().flat_map(|c| c.up_iter()).map(|(id, _)| id.clone());
+            assert!(ups.eq((0..4).map(id)));
+        }
+
+        #[test]
+        fn it_yields_nothing_for_an_empty_diff() {
+            assert_eq!(SubstateDiff::new().chunked(10).count(), 0);
+        }
+    }
 }
@@ -23,6 +23,7 @@
 use std::collections::{BTreeMap, BTreeSet};
 
 use serde::{Deserialize, Serialize};
+use tari_common::configuration::Network;
 use tari_common_types::types::{Commitment, PrivateKey, PublicKey};
 use tari_template_lib::{
     crypto::PedersonCommitmentBytes,
@@ -72,8 +73,9 @@ impl Vault {
         &mut self,
         proof: ConfidentialWithdrawProof,
         view_key: Option<&PublicKey>,
+        network: Network,
     ) -> Result<ResourceContainer, ResourceError> {
-        self.resource_container.withdraw_confidential(proof, view_key)
+        self.resource_container.withdraw_confidential(proof, view_key, network)
     }
 
     pub fn recall_all(&mut self) -> Result<ResourceContainer, ResourceError> {
@@ -146,8 +148,9 @@ impl Vault {
         &mut self,
         proof: ConfidentialWithdrawProof,
         view_key: Option<&PublicKey>,
+        network: Network,
     ) -> Result<ResourceContainer, ResourceError> {
-        self.resource_container.reveal_confidential(proof, view_key)
+        self.resource_container.reveal_confidential(proof, view_key, network)
     }
 
     pub fn resource_container_mut(&mut self) -> &mut ResourceContainer {
@@ -26,6 +26,7 @@ use std::{
 };
 
 use anyhow::anyhow;
+use prost::Message;
 use tari_bor::{decode_exact, encode};
 use tari_common_types::types::PublicKey;
 use tari_consensus::messages::{
@@ -89,7 +90,7 @@ impl From<&HotstuffMessage> for proto::consensus::HotStuffMessage {
     fn from(source: &HotstuffMessage) -> Self {
         let message = match source {
             HotstuffMessage::NewView(msg) => proto::consensus::hot_stuff_message::Message::NewView(msg.into()),
-            HotstuffMessage::Proposal(msg) => proto::consensus::hot_stuff_message::Message::Proposal(msg.into()),
+            HotstuffMessage::Proposal(msg) => proposal_to_proto(msg),
             HotstuffMessage::ForeignProposal(msg) => {
                 proto::consensus::hot_stuff_message::Message::ForeignProposal(msg.into())
             },
@@ -135,6 +136,9 @@ impl TryFrom<proto::consensus::HotStuffMessage> for HotstuffMessage {
             proto::consensus::hot_stuff_message::Message::SyncResponse(msg) => {
                 HotstuffMessage::SyncResponse(msg.try_into()?)
             },
+            proto::consensus::hot_stuff_message::Message::CompressedProposal(msg) => {
+                HotstuffMessage::Proposal(msg.try_into()?)
+            },
         })
     }
 }
@@ -188,10 +192,43 @@ impl TryFrom<proto::consensus::ProposalMessage> for ProposalMessage {
                 .into_iter()
                 .map(TryInto::try_into)
                 .collect::<Result<_, _>>()?,
+            allow_compression: false,
         })
     }
 }
 
+// -------------------------------- CompressedProposalMessage -------------------------------- //
+
+/// The maximum size of a decompressed proposal. This prevents a malicious peer from sending a small payload that
+/// decompresses to an excessive size.
+const MAX_DECOMPRESSED_PROPOSAL_SIZE: usize = 32 * 1024 * 1024;
+const PROPOSAL_COMPRESSION_LEVEL: i32 = 3;
+
+fn proposal_to_proto(msg: &ProposalMessage) -> proto::consensus::hot_stuff_message::Message {
+    let proposal = proto::consensus::ProposalMessage::from(msg);
+    if !msg.allow_compression {
+        return proto::consensus::hot_stuff_message::Message::Proposal(proposal);
+    }
+
+    match zstd::bulk::compress(&proposal.encode_to_vec(), PROPOSAL_COMPRESSION_LEVEL) {
+        Ok(data) => proto::consensus::hot_stuff_message::Message::CompressedProposal(
+            proto::consensus::CompressedProposalMessage { data },
+        ),
+        // Compression should not fail, but if it does, the peer can still receive the uncompressed proposal
+        Err(_) => proto::consensus::hot_stuff_message::Message::Proposal(proposal),
+    }
+}
+
+impl TryFrom<proto::consensus::CompressedProposalMessage> for ProposalMessage {
+    type Error = anyhow::Error;
+
+    fn try_from(value: proto::consensus::CompressedProposalMessage) -> Result<Self, Self::Error> {
+        let data = zstd::bulk::decompress(&value.data, MAX_DECOMPRESSED_PROPOSAL_SIZE)
+            .map_err(|e| anyhow!("Failed to decompress proposal: {e}"))?;
+        proto::consensus::ProposalMessage::decode(data.as_slice())?.try_into()
+    }
+}
+
 // -------------------------------- ForeignProposalMessage -------------------------------- //
 
 impl From<&ForeignProposalMessage> for proto::consensus::ForeignProposalMessage {
@@ -1029,6 +1066,10 @@ impl From<&SyncRequestMessage> for proto::consensus::SyncRequest {
                 epoch: value.high_qc.epoch.as_u64(),
                 qc_id: value.high_qc.qc_id.as_bytes().to_vec(),
             }),
+            accepts_compressed_proposals: value.accepts_compressed_proposals,
+            include_genesis: value.include_genesis,
+            max_blocks: value.max_blocks,
+            high_qc_cert: value.high_qc_cert.as_ref().map(Into::into),
         }
     }
 }
@@ -1050,6 +1091,10 @@ impl TryFrom<proto::consensus::SyncRequest> for SyncRequestMessage {
                 })
                 .transpose()?
                 .ok_or_else(|| anyhow!("High QC not provided"))?,
+            accepts_compressed_proposals: value.accepts_compressed_proposals,
+            include_genesis: value.include_genesis,
+            max_blocks: value.max_blocks,
+            high_qc_cert: value.high_qc_cert.map(TryInto::try_into).transpose()?,
         })
     }
 }
@@ -1061,6 +1106,14 @@ impl From<&SyncResponseMessage> for proto::consensus::SyncResponse {
         Self {
             epoch: value.epoch.as_u64(),
             blocks: value.blocks.iter().map(|block| block.into()).collect::<Vec<_>>(),
+            // A page never starts at the genesis height, so zero is used to indicate that there are no more blocks
+            next_height: value.next_height.map(|height| height.as_u64()).unwrap_or(0),
+            high_qc: value.high_qc.as_ref().map(|high_qc| proto::consensus::HighQc {
+                block_id: high_qc.block_id.as_bytes().to_vec(),
+                block_height: high_qc.block_height.as_u64(),
+                epoch: high_qc.epoch.as_u64(),
+                qc_id: high_qc.qc_id.as_bytes().to_vec(),
+            }),
         }
     }
 }
@@ -1076,6 +1129,18 @@ impl TryFrom<proto::consensus::SyncResponse> for SyncResponseMessage {
                 .into_iter()
                 .map(TryInto::try_into)
                 .collect::<Result<_, _>>()?,
+            next_height: Some(value.next_height).filter(|h| *h > 0).map(NodeHeight),
+            high_qc: value
+                .high_qc
+                .map(|value| {
+                    Ok::<_, anyhow::Error>(HighQc {
+                        block_id: BlockId::try_from(value.block_id)?,
+                        block_height: NodeHeight(value.block_height),
+                        epoch: Epoch(value.epoch),
+                        qc_id: QcId::try_from(value.qc_id)?,
+                    })
+                })
+                .transpose()?,
         })
     }
 }
@@ -8,7 +8,7 @@ use std::{
     time::{Duration, Instant},
 };
 
-use diesel::{sql_query, Connection, RunQueryDsl, SqliteConnection};
+use diesel::{connection::SimpleConnection, sql_query, Connection, RunQueryDsl, SqliteConnection};
 use diesel_migrations::{embed_migrations, EmbeddedMigrations, MigrationHarness};
 use log::log;
 use serde::{de::DeserializeOwned, Serialize};
@@ -26,6 +26,8 @@ const LOG_TARGET: &str = "tari::dan::storage::sqlite::state_store";
 
 pub struct SqliteStateStore<TAddr> {
     connection: Arc<Mutex<SqliteConnection>>,
+    /// A separate read-only connection for snapshot read transactions, if enabled
+    snapshot_connection: Option<Arc<Mutex<SqliteConnection>>>,
     _addr: PhantomData<TAddr>,
 }
 
@@ -47,10 +49,43 @@ impl<TAddr> SqliteStateStore<TAddr> {
 
         Ok(Self {
             connection: Arc::new(Mutex::new(connection)),
+            snapshot_connection: None,
             _addr: PhantomData,
         })
     }
 
+    /// Connects to the database and opens a second, read-only connection for snapshot read transactions. The database
+    /// is switched to WAL mode so that a snapshot read sees a consistent view of the database without blocking
+    /// writes on the main connection. The URL must refer to a database file, since each connection to an in-memory
+    /// database is a separate database.
+    pub fn connect_with_snapshot_reads(url: &str) -> Result<Self, StorageError> {
+        if url.contains(":memory:") {
+            return Err(StorageError::General {
+                details: "Snapshot reads are not supported for in-memory databases".to_string(),
+            });
+        }
+        let mut store = Self::connect(url)?;
+        store
+            .connection
+            .lock()
+            .unwrap()
+            .batch_execute("PRAGMA journal_mode = WAL;")
+            .map_err(|source| SqliteStorageError::DieselError {
+                source,
+                operation: "set pragma",
+            })?;
+
+        let mut snapshot_connection = SqliteConnection::establish(url).map_err(SqliteStorageError::from)?;
+        snapshot_connection
+            .batch_execute("PRAGMA query_only = ON;")
+            .map_err(|source| SqliteStorageError::DieselError {
+                source,
+                operation: "set pragma",
+            })?;
+        store.snapshot_connection = Some(Arc::new(Mutex::new(snapshot_connection)));
+        Ok(store)
+    }
+
     pub fn foreign_keys_off(&self) -> Result<(), StorageError> {
         sql_query("PRAGMA foreign_keys = OFF;")
             .execute(&mut *self.connection.lock().unwrap())
@@ -83,6 +118,15 @@ impl<TAddr: NodeAddressable + Serialize + DeserializeOwned> StateStore for Sqlit
         Ok(SqliteStateStoreReadTransaction::new(tx))
     }
 
+    fn create_snapshot_read_tx(&self) -> Result<Self::ReadTransaction<'_>, StorageError> {
+        let Some(snapshot_connection) = &self.snapshot_connection else {
+            return self.create_read_tx();
+        };
+        // In WAL mode, the snapshot is taken at the first read and is unaffected by later commits
+        let tx = SqliteTransaction::begin(snapshot_connection.lock().unwrap())?;
+        Ok(SqliteStateStoreReadTransaction::new(tx))
+    }
+
     fn create_write_tx(&self) -> Result<Self::WriteTransaction<'_>, StorageError> {
         let timer = Instant::now();
         let tx = SqliteTransaction::begin(self.connection.lock().unwrap())?;
@@ -106,6 +150,7 @@ impl<TAddr> Clone for SqliteStateStore<TAddr> {
     fn clone(&self) -> Self {
         Self {
             connection: self.connection.clone(),
+            snapshot_connection: self.snapshot_connection.clone(),
             _addr: PhantomData,
         }
     }
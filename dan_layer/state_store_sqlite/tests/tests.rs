@@ -143,3 +143,40 @@ mod confirm_all_transitions {
         tx.rollback().unwrap();
     }
 }
+
+mod snapshot_reads {
+    use std::fs;
+
+    use tari_dan_common_types::NumPreshards;
+
+    use super::*;
+
+    #[test]
+    fn it_rejects_in_memory_databases() {
+        SqliteStateStore::<String>::connect_with_snapshot_reads(":memory:").unwrap_err();
+    }
+
+    #[test]
+    fn it_reads_a_consistent_snapshot_while_writes_are_committed() {
+        let path = std::env::temp_dir().join(format!("state_store_snapshot_{}.db", OsRng.next_u64()));
+        let db = SqliteStateStore::<String>::connect_with_snapshot_reads(path.to_str().unwrap()).unwrap();
+        db.foreign_keys_off().unwrap();
+        let zero_block = Block::zero_block(Default::default(), NumPreshards::P64);
+
+        let snapshot = db.create_snapshot_read_tx().unwrap();
+        // The snapshot is taken at the first read
+        assert!(!Block::record_exists(&snapshot, zero_block.id()).unwrap());
+        db.with_write_tx(|tx| zero_block.insert(tx)).unwrap();
+        assert!(!Block::record_exists(&snapshot, zero_block.id()).unwrap());
+        drop(snapshot);
+
+        let snapshot = db.create_snapshot_read_tx().unwrap();
+        assert!(Block::record_exists(&snapshot, zero_block.id()).unwrap());
+        drop(snapshot);
+
+        drop(db);
+        for suffix in ["", "-wal", "-shm"] {
+            let _ignore = fs::remove_file(format!("{}{}", path.display(), suffix));
+        }
+    }
+}
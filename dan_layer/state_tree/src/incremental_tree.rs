@@ -0,0 +1,104 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::marker::PhantomData;
+
+use tari_engine_types::substate::SubstateId;
+
+use crate::{
+    key_mapper::{DbKeyMapper, SpreadPrefixKeyMapper},
+    tree::calculate_substate_changes,
+    Hash,
+    StagedTreeStore,
+    StateHashTreeDiff,
+    StateTreeError,
+    SubstateTreeChange,
+    TreeStoreReader,
+    Version,
+};
+
+pub type SpreadPrefixIncrementalStateTree<'s, S> = IncrementalStateTree<'s, S, SpreadPrefixKeyMapper>;
+
+/// A state tree over a read-only store that keeps the uncommitted tree nodes of each applied version, so that
+/// speculative changes (e.g. for a chain of candidate blocks) can be applied one version at a time without restaging
+/// the preceding versions each time.
+pub struct IncrementalStateTree<'s, S, M> {
+    readable_store: &'s S,
+    staged_store: StagedTreeStore<'s, S, Version>,
+    base_version: Option<Version>,
+    base_diffs: Vec<StateHashTreeDiff<Version>>,
+    applied_diffs: Vec<(Version, StateHashTreeDiff<Version>)>,
+    _mapper: PhantomData<M>,
+}
+
+impl<'s, S: TreeStoreReader<Version>, M: DbKeyMapper<SubstateId>> IncrementalStateTree<'s, S, M> {
+    /// Creates an incremental tree on top of the given store, where `base_version` is the current version of the tree
+    pub fn new(readable_store: &'s S, base_version: Option<Version>) -> Self {
+        Self {
+            readable_store,
+            staged_store: StagedTreeStore::new(readable_store),
+            base_version,
+            base_diffs: Vec::new(),
+            applied_diffs: Vec::new(),
+            _mapper: PhantomData,
+        }
+    }
+
+    /// Stages diffs that are pending (not yet committed to the store) below the base version. These are kept across
+    /// rollbacks.
+    pub fn with_pending_diffs<I: IntoIterator<Item = StateHashTreeDiff<Version>>>(mut self, diffs: I) -> Self {
+        for diff in diffs {
+            self.staged_store.apply_pending_diff(diff.clone());
+            self.base_diffs.push(diff);
+        }
+        self
+    }
+
+    /// The version of the last applied changes, or the base version if no changes have been applied
+    pub fn current_version(&self) -> Option<Version> {
+        self.applied_diffs
+            .last()
+            .map(|(version, _)| *version)
+            .or(self.base_version)
+    }
+
+    /// Applies the changes on top of the current version as `next_version` and returns the new root hash along with
+    /// the diff for this version only.
+    pub fn apply<I: IntoIterator<Item = SubstateTreeChange>>(
+        &mut self,
+        next_version: Version,
+        changes: I,
+    ) -> Result<(Hash, StateHashTreeDiff<Version>), StateTreeError> {
+        let current_version = self.current_version();
+        let (root_hash, update_batch) =
+            calculate_substate_changes::<_, M, _>(&mut self.staged_store, current_version, next_version, changes)?;
+        let diff = StateHashTreeDiff::from(update_batch);
+        self.staged_store.apply_pending_diff(diff.clone());
+        self.applied_diffs.push((next_version, diff.clone()));
+        Ok((root_hash, diff))
+    }
+
+    /// Discards all changes applied after `version`, e.g. for an aborted branch. Rolling back to a version at or below
+    /// the base version discards all applied changes.
+    pub fn rollback_to(&mut self, version: Version) {
+        let num_retained = self.applied_diffs.partition_point(|(v, _)| *v <= version);
+        if num_retained == self.applied_diffs.len() {
+            return;
+        }
+        self.applied_diffs.truncate(num_retained);
+
+        // Staged nodes cannot be unstaged individually, so the retained diffs are restaged from the base
+        self.staged_store = StagedTreeStore::new(self.readable_store);
+        for diff in &self.base_diffs {
+            self.staged_store.apply_pending_diff(diff.clone());
+        }
+        for (_, diff) in &self.applied_diffs {
+            self.staged_store.apply_pending_diff(diff.clone());
+        }
+    }
+
+    /// Returns the diffs for each applied version in order, e.g. to commit them once the versions are final
+    pub fn into_diffs(self) -> Vec<(Version, StateHashTreeDiff<Version>)> {
+        self.applied_diffs
+    }
+}
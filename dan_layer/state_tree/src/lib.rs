@@ -4,6 +4,9 @@
 mod error;
 pub use error::*;
 
+mod incremental_tree;
+pub use incremental_tree::*;
+
 mod jellyfish;
 pub use jellyfish::*;
 pub mod key_mapper;
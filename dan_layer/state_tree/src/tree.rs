@@ -1,7 +1,7 @@
 //   Copyright 2024 The Tari Project
 //   SPDX-License-Identifier: BSD-3-Clause
 
-use std::{iter::Peekable, marker::PhantomData};
+use std::{iter::Peekable, marker::PhantomData, mem::size_of};
 
 use serde::{Deserialize, Serialize};
 use tari_engine_types::substate::SubstateId;
@@ -54,6 +54,37 @@ impl<'a, S: TreeStoreReader<Version>, M: DbKeyMapper<SubstateId>> StateTree<'a,
         let root_hash = smt.get_root_hash(version)?;
         Ok(root_hash)
     }
+
+    /// Returns the changes that are not already reflected in the tree at `current_version`. An `Up` for a substate
+    /// that is present with the same value hash and a `Down` for a substate that is not present are dropped.
+    pub fn filter_applied_changes<I: IntoIterator<Item = SubstateTreeChange>>(
+        &self,
+        current_version: Option<Version>,
+        changes: I,
+    ) -> Result<Vec<SubstateTreeChange>, StateTreeError> {
+        let Some(version) = current_version else {
+            // Nothing has been applied to an empty tree, so only downs are no-ops
+            return Ok(changes
+                .into_iter()
+                .filter(|ch| matches!(ch, SubstateTreeChange::Up { .. }))
+                .collect());
+        };
+
+        let mut remaining = Vec::new();
+        for change in changes {
+            let (existing, _) = self.get_proof(version, change.id())?;
+            let is_applied = match &change {
+                SubstateTreeChange::Up { value_hash, .. } => {
+                    existing.is_some_and(|(existing_hash, _, _)| existing_hash == *value_hash)
+                },
+                SubstateTreeChange::Down { .. } => existing.is_none(),
+            };
+            if !is_applied {
+                remaining.push(change);
+            }
+        }
+        Ok(remaining)
+    }
 }
 
 impl<'a, S: TreeStore<Version>, M: DbKeyMapper<SubstateId>> StateTree<'a, S, M> {
@@ -80,6 +111,52 @@ impl<'a, S: TreeStore<Version>, M: DbKeyMapper<SubstateId>> StateTree<'a, S, M>
         Ok(root_hash)
     }
 
+    /// Stores the substate changes in batches of at most `batch_size` so that the tree updates for only one batch are
+    /// held in memory at a time. Each batch is committed as a new version, starting at `next_version`. Returns the
+    /// final root hash and the last version that was written.
+    pub fn put_substate_changes_streamed<I: IntoIterator<Item = SubstateTreeChange>>(
+        &mut self,
+        current_version: Option<Version>,
+        next_version: Version,
+        changes: I,
+        batch_size: usize,
+    ) -> Result<(Hash, Version), StateTreeError> {
+        let batch_size = batch_size.max(1);
+        let mut changes = changes.into_iter().peekable();
+        let mut current_version = current_version;
+        let mut version = next_version;
+        loop {
+            let root_hash = self.put_substate_changes(current_version, version, changes.by_ref().take(batch_size))?;
+            if changes.peek().is_none() {
+                return Ok((root_hash, version));
+            }
+            current_version = Some(version);
+            version += 1;
+        }
+    }
+
+    /// Stores the substate changes that are not already reflected in the tree (see
+    /// [StateTree::filter_applied_changes]) and returns the new root hash. If every change has already been applied,
+    /// e.g. when a diff is replayed, nothing is written and the root hash at `current_version` is returned.
+    pub fn put_substate_changes_idempotent<I: IntoIterator<Item = SubstateTreeChange>>(
+        &mut self,
+        current_version: Option<Version>,
+        next_version: Version,
+        changes: I,
+    ) -> Result<IdempotentPutResult, StateTreeError> {
+        let changes = self.filter_applied_changes(current_version, changes)?;
+        if changes.is_empty() {
+            let root_hash = match current_version {
+                Some(version) => self.get_root_hash(version)?,
+                None => SPARSE_MERKLE_PLACEHOLDER_HASH,
+            };
+            return Ok(IdempotentPutResult::AlreadyApplied { root_hash });
+        }
+
+        let root_hash = self.put_substate_changes(current_version, next_version, changes)?;
+        Ok(IdempotentPutResult::Applied { root_hash })
+    }
+
     pub fn commit_diff(&mut self, diff: StateHashTreeDiff<Version>) -> Result<(), StateTreeError> {
         for (key, node) in diff.new_nodes {
             log::debug!("Inserting node: {}", key);
@@ -134,7 +211,7 @@ impl<'a, S: TreeStore<()>, M: DbKeyMapper<Hash>> StateTree<'a, S, M> {
 }
 
 /// Calculates the new root hash and tree updates for the given substate changes.
-fn calculate_substate_changes<
+pub(crate) fn calculate_substate_changes<
     S: TreeStoreReader<Version>,
     M: DbKeyMapper<SubstateId>,
     I: IntoIterator<Item = SubstateTreeChange>,
@@ -170,6 +247,27 @@ impl SubstateTreeChange {
     }
 }
 
+/// The result of [StateTree::put_substate_changes_idempotent]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IdempotentPutResult {
+    /// The changes were stored at the next version
+    Applied { root_hash: Hash },
+    /// All changes were already reflected in the tree, so the current version was not advanced
+    AlreadyApplied { root_hash: Hash },
+}
+
+impl IdempotentPutResult {
+    pub fn root_hash(&self) -> Hash {
+        match self {
+            Self::Applied { root_hash } | Self::AlreadyApplied { root_hash } => *root_hash,
+        }
+    }
+
+    pub fn is_applied(&self) -> bool {
+        matches!(self, Self::Applied { .. })
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct StateHashTreeDiff<P> {
     pub new_nodes: Vec<(NodeKey, Node<P>)>,
@@ -183,6 +281,51 @@ impl<P> StateHashTreeDiff<P> {
             stale_tree_nodes: Vec::new(),
         }
     }
+
+    /// The number of node writes and deletes in the diff
+    pub fn len(&self) -> usize {
+        self.new_nodes.len() + self.stale_tree_nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.new_nodes.is_empty() && self.stale_tree_nodes.is_empty()
+    }
+
+    /// Estimates the number of bytes written to the store when the diff is committed, e.g. to throttle proposals with
+    /// abnormally large state changes. This is based on the size of the node fields rather than the encoding used by
+    /// the store, and a stale subtree is counted as a single node key.
+    pub fn byte_size_estimate(&self) -> usize {
+        let new_nodes = self
+            .new_nodes
+            .iter()
+            .map(|(key, node)| node_key_size_estimate(key) + node_size_estimate(node))
+            .sum::<usize>();
+        let stale_nodes = self
+            .stale_tree_nodes
+            .iter()
+            .map(|node| match node {
+                StaleTreeNode::Node(key) | StaleTreeNode::Subtree(key) => node_key_size_estimate(key),
+            })
+            .sum::<usize>();
+        new_nodes + stale_nodes
+    }
+}
+
+fn node_key_size_estimate(key: &NodeKey) -> usize {
+    size_of::<Version>() + key.nibble_path().bytes().len()
+}
+
+fn node_size_estimate<P>(node: &Node<P>) -> usize {
+    match node {
+        // Each child has a hash, version and node type (leaf count)
+        Node::Internal(node) => {
+            size_of::<usize>() +
+                node.children_sorted().count() * (size_of::<Hash>() + size_of::<Version>() + size_of::<usize>())
+        },
+        // The leaf key, value hash, payload and version
+        Node::Leaf(_) => size_of::<Hash>() * 2 + size_of::<P>() + size_of::<Version>(),
+        Node::Null => 0,
+    }
 }
 
 impl<P> From<TreeUpdateBatch<P>> for StateHashTreeDiff<P> {
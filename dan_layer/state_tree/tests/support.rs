@@ -6,6 +6,7 @@ use tari_state_tree::{
     key_mapper::DbKeyMapper,
     memory_store::MemoryTreeStore,
     Hash,
+    IdempotentPutResult,
     LeafKey,
     StateTree,
     SubstateTreeChange,
@@ -63,6 +64,20 @@ impl<S: TreeStore<Version>> HashTreeTester<S> {
         self.put_changes_at_version(current_version, next_version, changes)
     }
 
+    pub fn put_substate_changes_idempotent(
+        &mut self,
+        changes: impl IntoIterator<Item = SubstateTreeChange>,
+    ) -> IdempotentPutResult {
+        let next_version = self.current_version.unwrap_or(0) + 1;
+        let result = StateTree::<_, TestMapper>::new(&mut self.tree_store)
+            .put_substate_changes_idempotent(self.current_version, next_version, changes)
+            .unwrap();
+        if result.is_applied() {
+            self.current_version = Some(next_version);
+        }
+        result
+    }
+
     pub fn put_changes_at_version(
         &mut self,
         current_version: Option<Version>,
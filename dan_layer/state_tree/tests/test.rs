@@ -5,9 +5,22 @@
 use std::collections::HashSet;
 
 use itertools::Itertools;
-use tari_state_tree::{memory_store::MemoryTreeStore, StaleTreeNode, Version, SPARSE_MERKLE_PLACEHOLDER_HASH};
+use tari_engine_types::{
+    fee_claim::FeeClaim,
+    substate::{Substate, SubstateDiff, SubstateId},
+};
+use tari_state_tree::{
+    memory_store::MemoryTreeStore,
+    IncrementalStateTree,
+    StaleTreeNode,
+    StateTree,
+    SubstateTreeChange,
+    Version,
+    SPARSE_MERKLE_PLACEHOLDER_HASH,
+};
+use tari_template_lib::models::{Amount, ComponentAddress, ObjectKey};
 
-use crate::support::{change, HashTreeTester};
+use crate::support::{change, HashTreeTester, TestMapper};
 mod support;
 
 #[test]
@@ -133,6 +146,142 @@ fn hash_differs_when_states_only_differ_by_value() {
     assert_ne!(hash_1, hash_2);
 }
 
+#[test]
+fn idempotent_put_is_a_no_op_when_diff_replayed() {
+    let mut tester = HashTreeTester::new_empty();
+    let diff = || vec![change(1, Some(30)), change(2, Some(40)), change(3, None)];
+    let result_v1 = tester.put_substate_changes_idempotent(diff());
+    assert!(result_v1.is_applied());
+    let num_nodes = tester.tree_store.nodes.len();
+
+    let replayed = tester.put_substate_changes_idempotent(diff());
+    assert!(!replayed.is_applied());
+    assert_eq!(replayed.root_hash(), result_v1.root_hash());
+    assert_eq!(tester.tree_store.nodes.len(), num_nodes);
+    assert_eq!(tester.current_version, Some(1));
+}
+
+#[test]
+fn idempotent_put_applies_only_remaining_changes() {
+    let mut tester = HashTreeTester::new_empty();
+    tester.put_substate_changes(vec![change(1, Some(30))]);
+    let result = tester.put_substate_changes_idempotent(vec![change(1, Some(30)), change(2, Some(40))]);
+    assert!(result.is_applied());
+
+    let mut expected = HashTreeTester::new_empty();
+    let expected_hash = expected.put_substate_changes(vec![change(1, Some(30)), change(2, Some(40))]);
+    assert_eq!(result.root_hash(), expected_hash);
+}
+
+#[test]
+fn streamed_put_results_in_the_same_root_as_a_single_put() {
+    let changes = || (1..=10).map(|i| change(i, Some(i * 10)));
+    let mut tester = HashTreeTester::new_empty();
+    let expected_hash = tester.put_substate_changes(changes());
+
+    let mut store = MemoryTreeStore::new();
+    let (hash, version) = StateTree::<_, TestMapper>::new(&mut store)
+        .put_substate_changes_streamed(None, 1, changes(), 3)
+        .unwrap();
+    assert_eq!(hash, expected_hash);
+    // 4 batches of at most 3 changes
+    assert_eq!(version, 4);
+}
+
+#[test]
+fn chunked_substate_diff_results_in_the_same_root_as_the_whole_diff() {
+    fn id(seed: u8) -> SubstateId {
+        SubstateId::Component(ComponentAddress::new(ObjectKey::from_array([seed; ObjectKey::LENGTH])))
+    }
+    fn substate(amount: i64) -> Substate {
+        Substate::new(0, FeeClaim {
+            epoch: 0,
+            validator_public_key: Default::default(),
+            amount: Amount(amount),
+        })
+    }
+    fn to_changes(diff: SubstateDiff) -> Vec<SubstateTreeChange> {
+        diff.down_iter()
+            .map(|(id, _)| SubstateTreeChange::Down { id: id.clone() })
+            .chain(diff.up_iter().map(|(id, substate)| SubstateTreeChange::Up {
+                id: id.clone(),
+                value_hash: substate.to_value_hash(),
+            }))
+            .collect()
+    }
+
+    let mut initial = SubstateDiff::new();
+    initial.extend_up((0..10).map(|i| (id(i), substate(i.into()))));
+    let mut diff = SubstateDiff::new();
+    diff.extend_down((0..5).map(|i| (id(i), 0)))
+        .extend_up((0..3).map(|i| (id(i), substate(100))))
+        .extend_up((10..15).map(|i| (id(i), substate(i.into()))));
+
+    let mut expected = HashTreeTester::new_empty();
+    expected.put_substate_changes(to_changes(initial.clone()));
+    let expected_hash = expected.put_substate_changes(to_changes(diff.clone()));
+
+    let mut tester = HashTreeTester::new_empty();
+    let mut hash = tester.put_substate_changes(to_changes(initial));
+    for chunk in diff.chunked(4) {
+        hash = tester.put_substate_changes(to_changes(chunk));
+    }
+    assert_eq!(hash, expected_hash);
+    // 13 changes in chunks of at most 4
+    assert_eq!(tester.current_version, Some(5));
+}
+
+#[test]
+fn incremental_tree_advances_one_version_at_a_time_and_rolls_back() {
+    let v2_changes = || vec![change(2, Some(20))];
+    let v3_changes = || vec![change(1, None), change(3, Some(30))];
+    let alt_v3_changes = || vec![change(4, Some(40))];
+
+    let mut expected = HashTreeTester::new_empty();
+    expected.put_substate_changes(vec![change(1, Some(10))]);
+    let mut alt_expected = HashTreeTester::new_empty();
+    alt_expected.put_substate_changes(vec![change(1, Some(10))]);
+    let expected_v2 = expected.put_substate_changes(v2_changes());
+    alt_expected.put_substate_changes(v2_changes());
+    let expected_v3 = expected.put_substate_changes(v3_changes());
+    let expected_alt_v3 = alt_expected.put_substate_changes(alt_v3_changes());
+
+    let mut base = HashTreeTester::new_empty();
+    base.put_substate_changes(vec![change(1, Some(10))]);
+    let mut tree = IncrementalStateTree::<_, TestMapper>::new(&base.tree_store, Some(1));
+    let (hash, _) = tree.apply(2, v2_changes()).unwrap();
+    assert_eq!(hash, expected_v2);
+    let (hash, _) = tree.apply(3, v3_changes()).unwrap();
+    assert_eq!(hash, expected_v3);
+    assert_eq!(tree.current_version(), Some(3));
+
+    tree.rollback_to(2);
+    assert_eq!(tree.current_version(), Some(2));
+    let (hash, _) = tree.apply(3, alt_v3_changes()).unwrap();
+    assert_eq!(hash, expected_alt_v3);
+    assert_eq!(tree.into_diffs().len(), 2);
+}
+
+#[test]
+fn state_hash_tree_diff_reports_its_size() {
+    let mut base = HashTreeTester::new_empty();
+    base.put_substate_changes(vec![change(1, Some(10))]);
+
+    let mut tree = IncrementalStateTree::<_, TestMapper>::new(&base.tree_store, Some(1));
+    let (_, small_diff) = tree.apply(2, vec![change(2, Some(20))]).unwrap();
+    let (_, large_diff) = tree
+        .apply(3, (3..20).map(|i| change(i, Some(i))).collect::<Vec<_>>())
+        .unwrap();
+
+    assert!(!small_diff.is_empty());
+    assert_eq!(
+        small_diff.len(),
+        small_diff.new_nodes.len() + small_diff.stale_tree_nodes.len()
+    );
+    assert!(large_diff.len() > small_diff.len());
+    assert!(large_diff.byte_size_estimate() > small_diff.byte_size_estimate());
+}
+
 #[test]
 fn supports_empty_state() {
     let mut tester = HashTreeTester::new_empty();
@@ -27,6 +27,8 @@ mod locked_block;
 mod no_vote;
 mod quorum;
 mod quorum_certificate;
+#[cfg(feature = "test-helpers")]
+mod quorum_certificate_builder;
 mod state_transition;
 mod state_tree_diff;
 mod substate;
@@ -68,6 +70,8 @@ pub use locked_block::*;
 pub use no_vote::*;
 pub use quorum::*;
 pub use quorum_certificate::*;
+#[cfg(feature = "test-helpers")]
+pub use quorum_certificate_builder::*;
 pub use state_transition::*;
 pub use state_tree_diff::*;
 pub use substate::*;
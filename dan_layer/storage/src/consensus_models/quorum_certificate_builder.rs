@@ -0,0 +1,98 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use tari_common_types::types::{FixedHash, PrivateKey};
+use tari_crypto::keys::SecretKey;
+use tari_dan_common_types::{hashing::vote_signature_hasher, Epoch, NodeHeight, ShardGroup};
+
+use crate::consensus_models::{BlockId, QuorumCertificate, QuorumDecision, ValidatorSignature};
+
+/// Builds a [QuorumCertificate] for tests. Each signature is over the vote message for the certified block and
+/// decision, as signed by the vote signature service. The certificate is signed by randomly generated validator keys
+/// unless the signers are given, in which case it passes validation against a committee of those validators.
+#[derive(Debug, Clone)]
+pub struct QuorumCertificateBuilder {
+    epoch: Epoch,
+    shard_group: ShardGroup,
+    block_id: Option<BlockId>,
+    block_height: NodeHeight,
+    num_signatures: usize,
+    signers: Option<Vec<PrivateKey>>,
+    leaf_hashes: Vec<FixedHash>,
+    decision: QuorumDecision,
+}
+
+impl QuorumCertificateBuilder {
+    pub fn new(epoch: Epoch, shard_group: ShardGroup) -> Self {
+        Self {
+            epoch,
+            shard_group,
+            block_id: None,
+            block_height: NodeHeight::zero(),
+            num_signatures: 3,
+            signers: None,
+            leaf_hashes: vec![],
+            decision: QuorumDecision::Accept,
+        }
+    }
+
+    /// Sets the certified block. If not set, a random block ID is used.
+    pub fn with_block(mut self, block_id: BlockId, block_height: NodeHeight) -> Self {
+        self.block_id = Some(block_id);
+        self.block_height = block_height;
+        self
+    }
+
+    pub fn with_height(mut self, block_height: NodeHeight) -> Self {
+        self.block_height = block_height;
+        self
+    }
+
+    /// Sets the number of dummy validator signatures. Defaults to 3.
+    pub fn with_num_signatures(mut self, num_signatures: usize) -> Self {
+        self.num_signatures = num_signatures;
+        self
+    }
+
+    /// Signs the certificate with the given validator keys instead of random keys
+    pub fn with_signers(mut self, signers: Vec<PrivateKey>) -> Self {
+        self.signers = Some(signers);
+        self
+    }
+
+    pub fn with_leaf_hashes(mut self, leaf_hashes: Vec<FixedHash>) -> Self {
+        self.leaf_hashes = leaf_hashes;
+        self
+    }
+
+    pub fn with_decision(mut self, decision: QuorumDecision) -> Self {
+        self.decision = decision;
+        self
+    }
+
+    pub fn build(self) -> QuorumCertificate {
+        let block_id = self
+            .block_id
+            .unwrap_or_else(|| BlockId::new(FixedHash::from(rand::random::<[u8; 32]>())));
+        let message = vote_signature_hasher().chain(&block_id).chain(&self.decision).result();
+        let signers = self.signers.unwrap_or_else(|| {
+            (0..self.num_signatures)
+                .map(|_| PrivateKey::random(&mut rand::rngs::OsRng))
+                .collect()
+        });
+        let signatures = signers
+            .iter()
+            .map(|secret_key| ValidatorSignature::sign(secret_key, message))
+            .collect();
+
+        QuorumCertificate::new(
+            block_id,
+            self.block_height,
+            self.epoch,
+            self.shard_group,
+            signatures,
+            self.leaf_hashes,
+            self.decision,
+        )
+    }
+}
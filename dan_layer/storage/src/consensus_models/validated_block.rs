@@ -16,6 +16,9 @@ use crate::{
 pub struct ValidBlock {
     block: Block,
     dummy_blocks: Vec<Block>,
+    /// The number of dummy blocks preceding this block that were not calculated up front and must be generated and
+    /// saved before this block is saved
+    num_deferred_dummy_blocks: usize,
 }
 
 impl ValidBlock {
@@ -23,11 +26,24 @@ impl ValidBlock {
         Self {
             block,
             dummy_blocks: vec![],
+            num_deferred_dummy_blocks: 0,
         }
     }
 
     pub fn with_dummy_blocks(block: Block, dummy_blocks: Vec<Block>) -> Self {
-        Self { block, dummy_blocks }
+        Self {
+            block,
+            dummy_blocks,
+            num_deferred_dummy_blocks: 0,
+        }
+    }
+
+    pub fn with_deferred_dummy_blocks(block: Block, num_deferred_dummy_blocks: usize) -> Self {
+        Self {
+            block,
+            dummy_blocks: vec![],
+            num_deferred_dummy_blocks,
+        }
     }
 
     pub fn block(&self) -> &Block {
@@ -57,6 +73,10 @@ impl ValidBlock {
     pub fn dummy_blocks(&self) -> &[Block] {
         &self.dummy_blocks
     }
+
+    pub fn num_deferred_dummy_blocks(&self) -> usize {
+        self.num_deferred_dummy_blocks
+    }
 }
 
 impl ValidBlock {
@@ -87,6 +87,13 @@ pub trait StateStore {
     fn create_read_tx(&self) -> Result<Self::ReadTransaction<'_>, StorageError>;
     fn create_write_tx(&self) -> Result<Self::WriteTransaction<'_>, StorageError>;
 
+    /// Creates a read transaction over a consistent snapshot that does not contend with write transactions, for
+    /// long-running reads such as serving a catch-up sync. Stores that do not support snapshot reads return a normal
+    /// read transaction.
+    fn create_snapshot_read_tx(&self) -> Result<Self::ReadTransaction<'_>, StorageError> {
+        self.create_read_tx()
+    }
+
     fn with_write_tx<F: FnOnce(&mut Self::WriteTransaction<'_>) -> Result<R, E>, R, E>(&self, f: F) -> Result<R, E>
     where E: From<StorageError> {
         let mut tx = self.create_write_tx()?;
@@ -110,6 +117,14 @@ pub trait StateStore {
         let ret = f(&tx)?;
         Ok(ret)
     }
+
+    /// Like [StateStore::with_read_tx] but with a transaction from [StateStore::create_snapshot_read_tx]
+    fn with_snapshot_read_tx<F: FnOnce(&Self::ReadTransaction<'_>) -> Result<R, E>, R, E>(&self, f: F) -> Result<R, E>
+    where E: From<StorageError> {
+        let tx = self.create_snapshot_read_tx()?;
+        let ret = f(&tx)?;
+        Ok(ret)
+    }
 }
 
 pub trait StateStoreReadTransaction: Sized {
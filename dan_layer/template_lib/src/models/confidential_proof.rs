@@ -1,7 +1,11 @@
 //   Copyright 2023 The Tari Project
 //   SPDX-License-Identifier: BSD-3-Clause
 
-use std::mem::size_of;
+use std::{
+    fmt,
+    fmt::{Display, Formatter},
+    mem::size_of,
+};
 
 use serde::{de::Error, Deserialize, Serialize};
 #[cfg(feature = "ts")]
@@ -41,6 +45,26 @@ impl ConfidentialOutputStatement {
             change_revealed_amount: Amount::zero(),
         }
     }
+
+    /// Returns true if the output or change statement carries a viewable balance proof, i.e. the view key is enforced
+    /// for the resource
+    pub fn requires_view_key(&self) -> bool {
+        self.output_statement
+            .iter()
+            .chain(self.change_statement.iter())
+            .any(ConfidentialStatement::has_viewable_balance_proof)
+    }
+
+    /// Returns the sum of the revealed output and change amounts, or None if the sum overflows
+    pub fn revealed_total(&self) -> Option<Amount> {
+        self.output_revealed_amount.checked_add(self.change_revealed_amount)
+    }
+
+    /// Returns true if the statement only transfers revealed funds, i.e. it has neither an output nor a change
+    /// statement
+    pub fn is_revealed_only(&self) -> bool {
+        self.output_statement.is_none() && self.change_statement.is_none()
+    }
 }
 
 /// A zero-knowledge proof that a confidential resource amount is valid
@@ -57,10 +81,18 @@ pub struct ConfidentialStatement {
     pub encrypted_data: EncryptedData,
     #[cfg_attr(feature = "ts", ts(type = "number"))]
     pub minimum_value_promise: u64,
-    /// If the view key is enabled for a given resource, this proof MUST be provided, otherwise it MUST NOT.
+    /// If the view key is enabled for a given resource, this proof MUST be provided, otherwise it MUST NOT. Only the
+    /// committed value is encrypted to the view key, revealed amounts are public in the output statement.
     pub viewable_balance_proof: Option<ViewableBalanceProof>,
 }
 
+impl ConfidentialStatement {
+    /// Returns true if this statement carries a viewable balance proof
+    pub fn has_viewable_balance_proof(&self) -> bool {
+        self.viewable_balance_proof.is_some()
+    }
+}
+
 /// ### Verifiable encryption
 ///
 /// A verifiable ElGamal encryption proving system that asserts the value bound to a Pedersen
@@ -186,13 +218,12 @@ impl ConfidentialWithdrawProof {
         self.output_proof.range_proof.is_empty() &&
         // Excess will be zero
         self.inputs.is_empty() &&
-            self.output_proof.output_statement.is_none() &&
-            self.output_proof.change_statement.is_none() &&
+            self.output_proof.is_revealed_only() &&
             // zero balance proof
             self.balance_proof == BalanceProofSignature::zero() &&
             // There are revealed funds
             self.input_revealed_amount > Amount::zero() &&
-            self.output_proof.output_revealed_amount + self.output_proof.change_revealed_amount > Amount::zero()
+            self.output_proof.revealed_total().is_some_and(|total| total > Amount::zero())
     }
 
     pub fn revealed_input_amount(&self) -> Amount {
@@ -209,13 +240,24 @@ impl ConfidentialWithdrawProof {
 }
 
 /// Used by the receiver to determine the value component of the commitment, in both confidential transfers and Minotari
-/// burns
+/// burns.
+///
+/// The layout is `tag | nonce | payload`, where the encrypted payload is `value | mask`, optionally followed by up to
+/// [EncryptedData::max_size] - [EncryptedData::min_size] bytes of extension data, e.g. a base layer payment id. A memo
+/// is an extension that fills the whole extension region and starts with [EncryptedData::MEMO_MARKER]. The marker is
+/// encrypted, so whether data contains a memo is only known after decryption.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct EncryptedData(Vec<u8>);
 
 impl EncryptedData {
     pub const ENCRYPTED_DATA_SIZE_TOTAL: usize = Self::SIZE_NONCE + Self::SIZE_VALUE + Self::SIZE_MASK + Self::SIZE_TAG;
+    /// The first byte of the extension region of data that contains a memo
+    pub const MEMO_MARKER: u8 = 0xff;
     pub const SIZE_MASK: usize = 32;
+    /// The size of a memo. Memos are always padded to this size so that the length of the data does not reveal the
+    /// length of the memo.
+    pub const SIZE_MEMO: usize = 255;
+    pub const SIZE_MEMO_MARKER: usize = 1;
     pub const SIZE_NONCE: usize = 24;
     pub const SIZE_TAG: usize = 16;
     pub const SIZE_VALUE: usize = size_of::<u64>();
@@ -228,6 +270,11 @@ impl EncryptedData {
         Self::min_size() + 256
     }
 
+    /// The size of encrypted data that includes a memo
+    pub const fn size_with_memo() -> usize {
+        Self::min_size() + Self::SIZE_MEMO_MARKER + Self::SIZE_MEMO
+    }
+
     pub fn len(&self) -> usize {
         self.0.len()
     }
@@ -255,8 +302,50 @@ impl EncryptedData {
     pub const fn payload_offset() -> usize {
         Self::SIZE_TAG + Self::SIZE_NONCE
     }
+
+    /// The offset of the extension region, which directly follows the mask
+    pub const fn extension_offset() -> usize {
+        Self::payload_offset() + Self::SIZE_VALUE + Self::SIZE_MASK
+    }
+
+    /// The offset of the memo, which directly follows the memo marker at the start of the extension region
+    pub const fn memo_offset() -> usize {
+        Self::extension_offset() + Self::SIZE_MEMO_MARKER
+    }
+
+    /// Checks that the given bytes have the encrypted data layout i.e. `tag | nonce | value | mask`, followed by at
+    /// most [EncryptedData::max_size] - [EncryptedData::min_size] bytes of extension data. This can be used to reject
+    /// malformed ciphertext before attempting decryption.
+    pub fn validate(bytes: &[u8]) -> Result<(), EncryptedDataError> {
+        if bytes.len() < Self::min_size() || bytes.len() > Self::max_size() {
+            return Err(EncryptedDataError::InvalidLength { len: bytes.len() });
+        }
+        Ok(())
+    }
+}
+
+/// Errors that can occur when validating the encoding of [EncryptedData]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EncryptedDataError {
+    InvalidLength { len: usize },
 }
 
+impl Display for EncryptedDataError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidLength { len } => write!(
+                f,
+                "Encrypted data is {len} bytes but must be between {} and {} bytes",
+                EncryptedData::min_size(),
+                EncryptedData::max_size()
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for EncryptedDataError {}
+
 impl AsRef<[u8]> for EncryptedData {
     fn as_ref(&self) -> &[u8] {
         &self.0
@@ -267,12 +356,7 @@ impl TryFrom<Vec<u8>> for EncryptedData {
     type Error = usize;
 
     fn try_from(value: Vec<u8>) -> Result<Self, Self::Error> {
-        if value.len() < Self::min_size() {
-            return Err(value.len());
-        }
-        if value.len() > Self::max_size() {
-            return Err(value.len());
-        }
+        Self::validate(&value).map_err(|_| value.len())?;
         Ok(Self(value))
     }
 }
@@ -293,3 +377,114 @@ impl<'de> Deserialize<'de> for EncryptedData {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod confidential_output_statement {
+        use super::*;
+
+        #[test]
+        fn it_sums_the_revealed_amounts() {
+            let mut statement = ConfidentialOutputStatement::mint_revealed(Amount(100));
+            statement.change_revealed_amount = Amount(23);
+            assert_eq!(statement.revealed_total(), Some(Amount(123)));
+            assert!(statement.is_revealed_only());
+
+            statement.change_revealed_amount = Amount::MAX;
+            assert_eq!(statement.revealed_total(), None);
+        }
+    }
+
+    mod encrypted_data_validate {
+        use super::*;
+
+        #[test]
+        fn it_accepts_valid_lengths() {
+            for len in [
+                EncryptedData::min_size(),
+                EncryptedData::min_size() + 32,
+                EncryptedData::size_with_memo(),
+                EncryptedData::max_size(),
+            ] {
+                EncryptedData::validate(&vec![0u8; len]).unwrap();
+            }
+        }
+
+        #[test]
+        fn it_rejects_invalid_lengths() {
+            for len in [0, EncryptedData::min_size() - 1, EncryptedData::max_size() + 1] {
+                let err = EncryptedData::validate(&vec![0u8; len]).unwrap_err();
+                assert_eq!(err, EncryptedDataError::InvalidLength { len });
+                assert_eq!(EncryptedData::try_from(vec![0u8; len]), Err(len));
+            }
+        }
+
+        #[test]
+        fn it_decodes_data_with_a_base_layer_payment_id() {
+            let bytes = vec![1u8; EncryptedData::min_size() + 32];
+            let encrypted = EncryptedData::try_from(bytes.clone()).unwrap();
+            assert_eq!(encrypted.as_bytes(), bytes);
+
+            let encoded = tari_bor::encode(&encrypted).unwrap();
+            let decoded: EncryptedData = tari_bor::decode_exact(&encoded).unwrap();
+            assert_eq!(decoded, encrypted);
+        }
+
+        #[test]
+        fn it_fits_a_memo_in_the_extension_region() {
+            assert_eq!(EncryptedData::size_with_memo(), EncryptedData::max_size());
+            assert_eq!(
+                EncryptedData::memo_offset() + EncryptedData::SIZE_MEMO,
+                EncryptedData::size_with_memo()
+            );
+        }
+    }
+
+    mod requires_view_key {
+        use super::*;
+
+        fn statement(viewable_balance_proof: Option<ViewableBalanceProof>) -> ConfidentialStatement {
+            ConfidentialStatement {
+                commitment: PedersonCommitmentBytes::default(),
+                sender_public_nonce: RistrettoPublicKeyBytes::default(),
+                encrypted_data: EncryptedData::try_from(vec![0u8; EncryptedData::min_size()]).unwrap(),
+                minimum_value_promise: 0,
+                viewable_balance_proof,
+            }
+        }
+
+        fn viewable_balance_proof() -> ViewableBalanceProof {
+            let zero_scalar = SchnorrSignatureBytes::from_bytes(&[0u8; 32]).unwrap();
+            ViewableBalanceProof {
+                elgamal_encrypted: RistrettoPublicKeyBytes::default(),
+                elgamal_public_nonce: RistrettoPublicKeyBytes::default(),
+                c_prime: RistrettoPublicKeyBytes::default(),
+                e_prime: RistrettoPublicKeyBytes::default(),
+                r_prime: RistrettoPublicKeyBytes::default(),
+                s_v: zero_scalar,
+                s_m: zero_scalar,
+                s_r: zero_scalar,
+            }
+        }
+
+        #[test]
+        fn it_is_false_without_statements_or_proofs() {
+            let mut output = ConfidentialOutputStatement::mint_revealed(Amount(100));
+            assert!(!output.requires_view_key());
+
+            output.output_statement = Some(statement(None));
+            output.change_statement = Some(statement(None));
+            assert!(!output.requires_view_key());
+        }
+
+        #[test]
+        fn it_is_true_if_any_statement_has_a_proof() {
+            let mut output = ConfidentialOutputStatement::mint_revealed(Amount(100));
+            output.change_statement = Some(statement(Some(viewable_balance_proof())));
+            assert!(output.change_statement.as_ref().unwrap().has_viewable_balance_proof());
+            assert!(output.requires_view_key());
+        }
+    }
+}
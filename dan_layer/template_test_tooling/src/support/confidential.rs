@@ -2,6 +2,7 @@
 //   SPDX-License-Identifier: BSD-3-Clause
 
 use rand::rngs::OsRng;
+use tari_common::configuration::Network;
 use tari_common_types::types::{PrivateKey, PublicKey};
 use tari_crypto::{commitment::HomomorphicCommitmentFactory, keys::SecretKey, tari_utilities::ByteArray};
 use tari_dan_wallet_crypto::{ConfidentialOutputMaskAndValue, ConfidentialProofStatement};
@@ -56,6 +57,7 @@ fn generate_confidential_proof_internal(
         Amount::zero(),
         change_statement.as_ref(),
         Amount::zero(),
+        Network::LocalNet,
     )
     .unwrap();
     (proof, mask, change.map(|_| change_mask))
@@ -173,6 +175,7 @@ fn generate_withdraw_proof_internal(
         revealed_output_amount,
         change_proof.as_ref(),
         Amount::zero(),
+        Network::LocalNet,
     )
     .unwrap();
 
@@ -2,6 +2,8 @@
 //   SPDX-License-Identifier: BSD-3-Clause
 use chacha20poly1305::aead;
 use rand::rngs::OsRng;
+use rayon::prelude::*;
+use tari_common::configuration::Network;
 use tari_crypto::{
     commitment::HomomorphicCommitmentFactory,
     keys::{PublicKey as _, SecretKey},
@@ -13,11 +15,18 @@ use tari_template_lib::{
     crypto::{BalanceProofSignature, PedersonCommitmentBytes},
     models::{Amount, ConfidentialOutputStatement, ConfidentialWithdrawProof, EncryptedData},
 };
+use zeroize::Zeroizing;
 
 use crate::{
     confidential_output::ConfidentialOutputMaskAndValue,
     kdfs,
-    proof::{create_confidential_output_statement, decrypt_data_and_mask, encrypt_data},
+    proof::{
+        commitment_opens_to,
+        create_confidential_output_statement,
+        decrypt_data_and_mask,
+        decrypt_memo,
+        encrypt_data,
+    },
     ConfidentialProofError,
     ConfidentialProofStatement,
 };
@@ -29,17 +38,19 @@ pub fn create_withdraw_proof(
     output_revealed_amount: Amount,
     change_statement: Option<&ConfidentialProofStatement>,
     change_revealed_amount: Amount,
+    network: Network,
 ) -> Result<ConfidentialWithdrawProof, WalletCryptoError> {
     let output_proof = create_confidential_output_statement(
         output_statement,
         output_revealed_amount,
         change_statement,
         change_revealed_amount,
+        network,
     )?;
     let (input_commitments, agg_input_mask) = inputs.iter().fold(
         (Vec::with_capacity(inputs.len()), RistrettoSecretKey::default()),
         |(mut commitments, agg_input), input| {
-            let commitment = get_commitment_factory().commit_value(&input.mask, input.value);
+            let commitment = commitment_for(input.value, &input.mask);
             commitments.push(
                 PedersonCommitmentBytes::from_bytes(commitment.as_bytes()).expect("PedersonCommitment not 32 bytes"),
             );
@@ -47,7 +58,9 @@ pub fn create_withdraw_proof(
         },
     );
 
-    let output_revealed_amount = output_proof.output_revealed_amount + output_proof.change_revealed_amount;
+    let output_revealed_amount = output_proof
+        .revealed_total()
+        .ok_or(ConfidentialProofError::AmountOverflow)?;
     let balance_proof = generate_balance_proof(
         &agg_input_mask,
         input_revealed_amount,
@@ -80,8 +93,35 @@ pub fn encrypt_value_and_mask(
     secret: &RistrettoSecretKey,
 ) -> Result<EncryptedData, WalletCryptoError> {
     let key = kdfs::encrypted_data_dh_kdf_aead(secret, public_nonce);
-    let commitment = get_commitment_factory().commit_value(mask, amount);
-    let encrypted_data = encrypt_data(&key, &commitment, amount, mask)?;
+    let commitment = commitment_for(amount, mask);
+    let encrypted_data = encrypt_data(&key, &commitment, amount, mask, None)?;
+    Ok(encrypted_data)
+}
+
+/// Like [encrypt_value_and_mask], but also encrypts `memo` for the receiver. The memo is padded with zeros to
+/// [EncryptedData::SIZE_MEMO] bytes, and an error is returned if it is longer than that.
+pub fn encrypt_value_mask_and_memo(
+    amount: u64,
+    mask: &RistrettoSecretKey,
+    public_nonce: &RistrettoPublicKey,
+    secret: &RistrettoSecretKey,
+    memo: &[u8],
+) -> Result<EncryptedData, WalletCryptoError> {
+    if memo.len() > EncryptedData::SIZE_MEMO {
+        return Err(WalletCryptoError::InvalidArgument {
+            name: "memo",
+            details: format!(
+                "memo is {} bytes but must be at most {} bytes",
+                memo.len(),
+                EncryptedData::SIZE_MEMO
+            ),
+        });
+    }
+    let mut padded = Zeroizing::new([0u8; EncryptedData::SIZE_MEMO]);
+    padded[..memo.len()].copy_from_slice(memo);
+    let key = kdfs::encrypted_data_dh_kdf_aead(secret, public_nonce);
+    let commitment = commitment_for(amount, mask);
+    let encrypted_data = encrypt_data(&key, &commitment, amount, mask, Some(&padded))?;
     Ok(encrypted_data)
 }
 
@@ -95,6 +135,16 @@ pub fn extract_value_and_mask(
     Ok((value, mask))
 }
 
+/// Returns the padded memo of `encrypted_data`, or None if it was created without a memo
+pub fn extract_memo(
+    encryption_key: &RistrettoSecretKey,
+    commitment: &PedersenCommitment,
+    encrypted_data: &EncryptedData,
+) -> Result<Option<[u8; EncryptedData::SIZE_MEMO]>, WalletCryptoError> {
+    decrypt_memo(encryption_key, commitment, encrypted_data)
+        .map_err(|e| WalletCryptoError::FailedDecryptData { details: e.to_string() })
+}
+
 pub fn unblind_output(
     output_commitment: &PedersenCommitment,
     output_encrypted_value: &EncryptedData,
@@ -104,14 +154,41 @@ pub fn unblind_output(
     let encryption_key = kdfs::encrypted_data_dh_kdf_aead(claim_secret, reciprocal_public_key);
 
     let (value, mask) = extract_value_and_mask(&encryption_key, output_commitment, output_encrypted_value)?;
-    let commitment = get_commitment_factory().commit_value(&mask, value);
-    if *output_commitment == commitment {
+    if commitment_opens_to(output_commitment, value, &mask) {
         Ok(ConfidentialOutputMaskAndValue { value, mask })
     } else {
         Err(WalletCryptoError::UnableToOpenCommitment)
     }
 }
 
+/// Returns true if the output belongs to the owner of `private_view_key` i.e. the encrypted data decrypts using the key
+/// derived (Diffie-Hellman) from the private view key and the sender public nonce, and the decrypted value and mask
+/// open the commitment.
+pub fn verify_output_ownership(
+    private_view_key: &RistrettoSecretKey,
+    sender_public_nonce: &RistrettoPublicKey,
+    commitment: &PedersenCommitment,
+    encrypted_data: &EncryptedData,
+) -> bool {
+    unblind_output(commitment, encrypted_data, private_view_key, sender_public_nonce).is_ok()
+}
+
+/// Attempts to open every given output (e.g. all the confidential outputs in a block) with the private view key, for
+/// audits. Each output is given as its commitment, the sender public nonce and its encrypted data. The value and mask
+/// of the outputs that belong to the view key are returned in the same order as the outputs, with `None` for outputs
+/// that do not. Outputs are scanned concurrently on the rayon thread pool.
+pub fn audit_block_outputs(
+    private_view_key: &RistrettoSecretKey,
+    outputs: &[(PedersenCommitment, RistrettoPublicKey, EncryptedData)],
+) -> Vec<Option<ConfidentialOutputMaskAndValue>> {
+    outputs
+        .par_iter()
+        .map(|(commitment, sender_public_nonce, encrypted_data)| {
+            unblind_output(commitment, encrypted_data, private_view_key, sender_public_nonce).ok()
+        })
+        .collect()
+}
+
 pub fn create_output_for_dest(
     dest_public_key: &RistrettoPublicKey,
     amount: Amount,
@@ -126,9 +203,9 @@ pub fn create_output_for_dest(
             name: "amount",
             details: "[generate_output_for_dest] amount is negative".to_string(),
         })?;
-    let commitment = create_commitment(&mask, amount);
+    let commitment = commitment_for(amount, &mask);
     let encrypt_key = kdfs::encrypted_data_dh_kdf_aead(&mask, dest_public_key);
-    let encrypted_data = encrypt_data(&encrypt_key, &commitment, amount, &mask)?;
+    let encrypted_data = encrypt_data(&encrypt_key, &commitment, amount, &mask, None)?;
 
     Ok(ConfidentialOutput {
         commitment,
@@ -139,7 +216,9 @@ pub fn create_output_for_dest(
     })
 }
 
-fn create_commitment(mask: &RistrettoSecretKey, value: u64) -> PedersenCommitment {
+/// Returns the Pedersen commitment to `value` with `mask`, using the same commitment factory as the confidential
+/// proofs created and validated by this crate
+pub fn commitment_for(value: u64, mask: &RistrettoSecretKey) -> PedersenCommitment {
     get_commitment_factory().commit_value(mask, value)
 }
 
@@ -185,3 +264,88 @@ impl From<aead::Error> for WalletCryptoError {
         WalletCryptoError::AeadError(err)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod verify_output_ownership {
+        use super::*;
+
+        #[test]
+        fn it_verifies_outputs_encrypted_to_the_view_key() {
+            let (view_key, view_public_key) = RistrettoPublicKey::random_keypair(&mut OsRng);
+            let output = create_output_for_dest(&view_public_key, Amount(123)).unwrap();
+
+            assert!(verify_output_ownership(
+                &view_key,
+                &output.stealth_public_nonce,
+                &output.commitment,
+                &output.encrypted_data
+            ));
+
+            let other_view_key = RistrettoSecretKey::random(&mut OsRng);
+            assert!(!verify_output_ownership(
+                &other_view_key,
+                &output.stealth_public_nonce,
+                &output.commitment,
+                &output.encrypted_data
+            ));
+        }
+    }
+    mod audit_block_outputs {
+        use super::*;
+
+        #[test]
+        fn it_opens_only_the_outputs_for_the_view_key() {
+            let (view_key, view_public_key) = RistrettoPublicKey::random_keypair(&mut OsRng);
+            let (_, other_public_key) = RistrettoPublicKey::random_keypair(&mut OsRng);
+            let outputs = [
+                create_output_for_dest(&view_public_key, Amount(10)).unwrap(),
+                create_output_for_dest(&other_public_key, Amount(20)).unwrap(),
+                create_output_for_dest(&view_public_key, Amount(30)).unwrap(),
+            ]
+            .into_iter()
+            .map(|output| (output.commitment, output.stealth_public_nonce, output.encrypted_data))
+            .collect::<Vec<_>>();
+
+            let results = audit_block_outputs(&view_key, &outputs);
+            let values = results.iter().map(|r| r.as_ref().map(|o| o.value)).collect::<Vec<_>>();
+            assert_eq!(values, [Some(10), None, Some(30)]);
+
+            let opened = results[0].as_ref().unwrap();
+            assert_eq!(commitment_for(opened.value, &opened.mask), outputs[0].0);
+        }
+    }
+
+    mod commitment_for {
+        use super::*;
+
+        #[test]
+        fn it_matches_the_output_statement_commitment() {
+            let mask = RistrettoSecretKey::random(&mut OsRng);
+            let statement = ConfidentialProofStatement {
+                amount: Amount(123),
+                mask: mask.clone(),
+                sender_public_nonce: Default::default(),
+                minimum_value_promise: 0,
+                encrypted_data: EncryptedData::try_from(vec![0; EncryptedData::min_size()]).unwrap(),
+                resource_view_key: None,
+            };
+            let output = create_confidential_output_statement(
+                Some(&statement),
+                Default::default(),
+                None,
+                Default::default(),
+                Network::LocalNet,
+            )
+            .unwrap();
+
+            let commitment = commitment_for(123, &mask);
+            assert_eq!(
+                output.output_statement.unwrap().commitment.as_bytes(),
+                commitment.as_bytes()
+            );
+        }
+    }
+}
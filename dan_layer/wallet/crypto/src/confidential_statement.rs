@@ -8,6 +8,8 @@ use tari_crypto::{
 use tari_engine_types::confidential::get_commitment_factory;
 use tari_template_lib::models::{Amount, EncryptedData};
 
+use crate::ConfidentialProofError;
+
 #[derive(Debug, Clone)]
 pub struct ConfidentialProofStatement {
     pub amount: Amount,
@@ -22,4 +24,71 @@ impl ConfidentialProofStatement {
     pub fn to_commitment(&self) -> PedersenCommitment {
         get_commitment_factory().commit_value(&self.mask, self.amount.value() as u64)
     }
+
+    /// Returns the value of the output. The minimum value promise is a lower bound on this value that is revealed and
+    /// proven by the range proof, not an amount in addition to it, so balances are calculated from this value alone.
+    pub fn effective_value(&self) -> Amount {
+        self.amount
+    }
+
+    /// Returns the value of the output, checking that it is not negative and is at least the minimum value promise.
+    /// A range proof cannot be created for a statement that does not satisfy these invariants.
+    pub fn checked_value(&self) -> Result<u64, ConfidentialProofError> {
+        let value = self
+            .amount
+            .as_u64_checked()
+            .ok_or(ConfidentialProofError::NegativeAmount)?;
+        if value < self.minimum_value_promise {
+            return Err(ConfidentialProofError::ValueBelowMinimumValuePromise {
+                value,
+                minimum_value_promise: self.minimum_value_promise,
+            });
+        }
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::OsRng;
+    use tari_crypto::keys::SecretKey;
+
+    use super::*;
+
+    mod checked_value {
+        use super::*;
+
+        fn statement(amount: i64, minimum_value_promise: u64) -> ConfidentialProofStatement {
+            ConfidentialProofStatement {
+                amount: amount.into(),
+                mask: RistrettoSecretKey::random(&mut OsRng),
+                sender_public_nonce: Default::default(),
+                minimum_value_promise,
+                encrypted_data: EncryptedData::try_from(vec![0; EncryptedData::min_size()]).unwrap(),
+                resource_view_key: None,
+            }
+        }
+
+        #[test]
+        fn it_returns_the_value_if_it_is_at_least_the_minimum_value_promise() {
+            assert_eq!(statement(100, 0).checked_value().unwrap(), 100);
+            assert_eq!(statement(100, 100).checked_value().unwrap(), 100);
+            assert_eq!(statement(100, 100).effective_value(), Amount(100));
+        }
+
+        #[test]
+        fn it_errors_if_the_value_is_below_the_minimum_value_promise() {
+            let err = statement(99, 100).checked_value().unwrap_err();
+            assert!(matches!(err, ConfidentialProofError::ValueBelowMinimumValuePromise {
+                value: 99,
+                minimum_value_promise: 100
+            }));
+        }
+
+        #[test]
+        fn it_errors_if_the_value_is_negative() {
+            let err = statement(-1, 0).checked_value().unwrap_err();
+            assert!(matches!(err, ConfidentialProofError::NegativeAmount));
+        }
+    }
 }
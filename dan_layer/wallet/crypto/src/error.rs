@@ -8,16 +8,32 @@ use tari_crypto::errors::RangeProofError;
 pub enum ConfidentialProofError {
     #[error("Range proof error: {0}")]
     RangeProof(RangeProofError),
-    #[error("Aead error")]
-    AeadError,
+    #[error("AEAD error: {0}")]
+    Aead(#[from] aead::Error),
     #[error("Negative amount")]
     NegativeAmount,
-}
-
-impl From<aead::Error> for ConfidentialProofError {
-    fn from(_value: aead::Error) -> Self {
-        Self::AeadError
-    }
+    #[error("Value {value} is less than the minimum value promise {minimum_value_promise}")]
+    ValueBelowMinimumValuePromise { value: u64, minimum_value_promise: u64 },
+    #[error("Amount overflow")]
+    AmountOverflow,
+    #[error("Invalid commitment")]
+    InvalidCommitment,
+    #[error("Transaction is not balanced")]
+    Unbalanced,
+    #[error("Range proof for aggregation factor {agg_factor} has length {actual} but expected {expected}")]
+    RangeProofLengthMismatch {
+        agg_factor: usize,
+        expected: usize,
+        actual: usize,
+    },
+    #[error("Value is greater than the maximum searched value {max_value}")]
+    ValueOutOfRange { max_value: u64 },
+    #[error("Maximum value {max_value} exceeds the maximum recoverable value {max}")]
+    MaxValueTooLarge { max_value: u64, max: u64 },
+    #[error("Value lookup failed: {details}")]
+    ValueLookupFailed { details: String },
+    #[error("Range proof aggregation factor {agg_factor} is not supported (maximum {max})")]
+    UnsupportedAggregationFactor { agg_factor: usize, max: usize },
 }
 
 impl From<RangeProofError> for ConfidentialProofError {
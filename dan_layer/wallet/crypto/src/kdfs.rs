@@ -1,22 +1,32 @@
 //   Copyright 2024 The Tari Project
 //   SPDX-License-Identifier: BSD-3-Clause
 
-use chacha20poly1305::{aead::generic_array::GenericArray, Key};
-use digest::FixedOutput;
+use blake2::Blake2b;
+use chacha20poly1305::{aead::generic_array::GenericArray, consts::U64, Key};
+use digest::{
+    generic_array::ArrayLength,
+    typenum::{IsLessOrEqual, LeEq, NonZero},
+    Digest,
+    FixedOutputReset,
+};
 use tari_crypto::{
     dhke::DiffieHellmanSharedSecret,
+    hashing::{DomainSeparatedHasher, DomainSeparation},
     keys::SecretKey,
     ristretto::{RistrettoPublicKey, RistrettoSecretKey},
 };
 use tari_engine_types::base_layer_hashing::encrypted_data_hasher;
-use tari_utilities::{hidden_type, safe_array::SafeArray, Hidden};
-use zeroize::Zeroize;
+use tari_hashing::TransactionSecureNonceKdfDomain;
+use tari_utilities::{hidden_type, safe_array::SafeArray, ByteArray, Hidden};
 
 pub(crate) const AEAD_KEY_LEN: usize = std::mem::size_of::<Key>();
+/// Blake2b compresses 128-byte blocks
+const BLAKE2B_BLOCK_LEN: usize = 128;
 
 // Type for hiding aead key encryption
 hidden_type!(EncryptedDataKey, SafeArray<u8, AEAD_KEY_LEN>);
 hidden_type!(EncryptedDataKey64, SafeArray<u8, 64>);
+hidden_type!(OutputMaskBytes, SafeArray<u8, 64>);
 
 /// Generate a ChaCha20-Poly1305 key from a private key and commitment using Blake2b
 pub fn encrypted_data_dh_kdf_aead(
@@ -26,9 +36,90 @@ pub fn encrypted_data_dh_kdf_aead(
     let shared_secret = DiffieHellmanSharedSecret::<RistrettoPublicKey>::new(private_key, public_nonce);
     let mut aead_key = EncryptedDataKey64::from(SafeArray::default());
     // Must match base layer burn
-    encrypted_data_hasher()
-        .chain(shared_secret.as_bytes())
-        .finalize_into(GenericArray::from_mut_slice(aead_key.reveal_mut()));
+    finalize_secret_into(
+        encrypted_data_hasher(),
+        &[shared_secret.as_bytes()],
+        aead_key.reveal_mut(),
+    );
 
     RistrettoSecretKey::from_uniform_bytes(aead_key.reveal()).unwrap()
 }
+
+/// Deterministically derive the mask for the output at `output_index` from a seed key. Wallets that recover outputs
+/// from a seed MUST use this derivation so that the masks agree.
+pub fn derive_output_mask(seed_key: &RistrettoSecretKey, output_index: u64) -> RistrettoSecretKey {
+    let mut mask_bytes = OutputMaskBytes::from(SafeArray::default());
+    finalize_secret_into(
+        DomainSeparatedHasher::<Blake2b<U64>, TransactionSecureNonceKdfDomain>::new_with_label("output_mask"),
+        &[seed_key.as_bytes(), &output_index.to_le_bytes()],
+        mask_bytes.reveal_mut(),
+    );
+
+    RistrettoSecretKey::from_uniform_bytes(mask_bytes.reveal()).unwrap()
+}
+
+/// Hashes the secret `inputs` into `output` using a Blake2b hasher and wipes the buffered input afterwards.
+///
+/// Blake2b buffers its input until it has a full block, so consuming the hasher with `finalize_into` leaves the last
+/// block of the secret in the dropped hasher. Instead, the hasher is reset on finalization, which discards the chaining
+/// state, and a block of zeros is absorbed to overwrite the buffer. The output is identical to chaining each input and
+/// calling `finalize_into`.
+pub(crate) fn finalize_secret_into<N, M>(
+    mut hasher: DomainSeparatedHasher<Blake2b<N>, M>,
+    inputs: &[&[u8]],
+    output: &mut [u8],
+) where
+    N: ArrayLength<u8> + IsLessOrEqual<U64>,
+    LeEq<N, U64>: NonZero,
+    M: DomainSeparation,
+    DomainSeparatedHasher<Blake2b<N>, M>: FixedOutputReset,
+{
+    for input in inputs {
+        hasher.update(input);
+    }
+    hasher.finalize_into_reset(GenericArray::from_mut_slice(output));
+    hasher.update([0u8; BLAKE2B_BLOCK_LEN]);
+}
+
+#[cfg(test)]
+mod tests {
+    use digest::FixedOutput;
+    use rand::rngs::OsRng;
+
+    use super::*;
+
+    mod derive_output_mask {
+        use super::*;
+
+        #[test]
+        fn it_is_deterministic_per_seed_and_index() {
+            let seed_key = RistrettoSecretKey::random(&mut OsRng);
+            assert_eq!(derive_output_mask(&seed_key, 0), derive_output_mask(&seed_key, 0));
+            assert_ne!(derive_output_mask(&seed_key, 0), derive_output_mask(&seed_key, 1));
+
+            let other_seed_key = RistrettoSecretKey::random(&mut OsRng);
+            assert_ne!(derive_output_mask(&seed_key, 0), derive_output_mask(&other_seed_key, 0));
+        }
+    }
+
+    mod finalize_secret_into {
+        use super::*;
+
+        #[test]
+        fn it_matches_chained_finalization() {
+            let secret = RistrettoSecretKey::random(&mut OsRng);
+            let hasher =
+                || DomainSeparatedHasher::<Blake2b<U64>, TransactionSecureNonceKdfDomain>::new_with_label("test");
+
+            let mut expected = [0u8; 64];
+            hasher()
+                .chain(secret.as_bytes())
+                .chain(b"public")
+                .finalize_into(GenericArray::from_mut_slice(&mut expected));
+
+            let mut output = [0u8; 64];
+            finalize_secret_into(hasher(), &[secret.as_bytes(), b"public"], &mut output);
+            assert_eq!(output, expected);
+        }
+    }
+}
@@ -19,3 +19,9 @@ pub use confidential_statement::*;
 
 mod value_lookup;
 pub use value_lookup::*;
+
+mod value_recovery;
+pub use value_recovery::*;
+
+mod viewable_balance;
+pub use viewable_balance::*;
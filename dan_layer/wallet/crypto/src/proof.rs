@@ -3,7 +3,6 @@
 
 use blake2::Blake2b;
 use chacha20poly1305::{
-    aead,
     aead::{generic_array::GenericArray, OsRng},
     consts::U32,
     AeadCore,
@@ -13,10 +12,12 @@ use chacha20poly1305::{
     XChaCha20Poly1305,
     XNonce,
 };
-use digest::FixedOutput;
+use futures::{channel::oneshot, Stream, StreamExt};
+use rayon::prelude::*;
+use subtle::ConstantTimeEq;
+use tari_common::configuration::Network;
 use tari_crypto::{
     commitment::{ExtensionDegree, HomomorphicCommitmentFactory},
-    errors::RangeProofError,
     extended_range_proof::ExtendedRangeProofService,
     hashing::DomainSeparatedHasher,
     keys::{PublicKey, SecretKey},
@@ -29,10 +30,20 @@ use tari_crypto::{
     },
     tari_utilities::ByteArray,
 };
-use tari_engine_types::confidential::{challenges, get_commitment_factory, get_range_proof_service};
+use tari_engine_types::{
+    confidential::{
+        challenges,
+        get_commitment_factory,
+        try_get_range_proof_service,
+        validate_confidential_proof,
+        ValidatedConfidentialProof,
+        MAX_RANGE_PROOF_AGGREGATION_FACTOR,
+    },
+    resource_container::ResourceError,
+};
 use tari_hashing::TransactionSecureNonceKdfDomain;
 use tari_template_lib::{
-    crypto::RistrettoPublicKeyBytes,
+    crypto::{PedersonCommitmentBytes, RistrettoPublicKeyBytes, SchnorrSignatureBytes},
     models::{
         Amount,
         ConfidentialOutputStatement,
@@ -57,6 +68,7 @@ pub fn create_confidential_output_statement(
     output_revealed_amount: Amount,
     change_statement: Option<&ConfidentialProofStatement>,
     change_revealed_amount: Amount,
+    network: Network,
 ) -> Result<ConfidentialOutputStatement, ConfidentialProofError> {
     let proof_change_statement = change_statement
         .as_ref()
@@ -74,6 +86,7 @@ pub fn create_confidential_output_statement(
                         stmt.amount.as_u64_checked().unwrap(),
                         &change_commitment,
                         view_key,
+                        network,
                     )
                 }),
             })
@@ -94,12 +107,21 @@ pub fn create_confidential_output_statement(
             encrypted_data: stmt.encrypted_data.clone(),
             minimum_value_promise: stmt.minimum_value_promise,
             viewable_balance_proof: stmt.resource_view_key.as_ref().map(|view_key| {
-                create_viewable_balance_proof(&stmt.mask, confidential_output_value, &commitment, view_key)
+                create_viewable_balance_proof(&stmt.mask, confidential_output_value, &commitment, view_key, network)
             }),
         }
     });
 
     let output_range_proof = generate_extended_bullet_proof(output_statement, change_statement)?;
+    let agg_factor = usize::from(output_statement.is_some()) + usize::from(change_statement.is_some());
+    let expected_len = expected_range_proof_len(agg_factor);
+    if output_range_proof.len() != expected_len {
+        return Err(ConfidentialProofError::RangeProofLengthMismatch {
+            agg_factor,
+            expected: expected_len,
+            actual: output_range_proof.len(),
+        });
+    }
 
     Ok(ConfidentialOutputStatement {
         output_statement: proof_output_statement,
@@ -110,24 +132,179 @@ pub fn create_confidential_output_statement(
     })
 }
 
+/// The bit length of the values proven by the range proof
+const RANGE_PROOF_BIT_LENGTH: usize = 64;
+/// The serialized size of a single group element or scalar in the range proof
+const RANGE_PROOF_ELEMENT_SIZE: usize = 32;
+/// The number of fixed-size elements (A, A1, B, r1, s1) in a range proof
+const RANGE_PROOF_FIXED_ELEMENTS: usize = 5;
+
+/// Returns the expected serialized length of an extended range proof for `agg_factor` default Pedersen commitments. An
+/// aggregation factor of 0 (revealed funds only) is expected to have an empty range proof.
+pub fn expected_range_proof_len(agg_factor: usize) -> usize {
+    if agg_factor == 0 {
+        return 0;
+    }
+    let extension_degree = ExtensionDegree::DefaultPedersen as usize;
+    // The inner product rounds produce one L and one R element for each bit of log2(n.m)
+    let rounds = (RANGE_PROOF_BIT_LENGTH * agg_factor.next_power_of_two()).ilog2() as usize;
+    // 1 byte extension degree + fixed elements + d1 (one per extension degree) + L and R vectors
+    1 + RANGE_PROOF_ELEMENT_SIZE * (RANGE_PROOF_FIXED_ELEMENTS + extension_degree + 2 * rounds)
+}
+
+/// Verifies that a transaction balances before the balance proof is signed, returning the public excess.
+///
+/// The public excess `sum(inputs) + input_revealed_amount.H - sum(outputs) - (revealed outputs + fee).H` must be the
+/// public key of `excess_secret` (the input masks minus the output masks), since any value imbalance leaves a residual
+/// H component.
+pub fn verify_balance(
+    inputs: &[PedersenCommitment],
+    input_revealed_amount: Amount,
+    output: &ConfidentialOutputStatement,
+    fee: Amount,
+    excess_secret: &RistrettoSecretKey,
+) -> Result<RistrettoPublicKey, ConfidentialProofError> {
+    let input_revealed_amount = input_revealed_amount
+        .as_u64_checked()
+        .ok_or(ConfidentialProofError::NegativeAmount)?;
+    let output_revealed_amount = output
+        .output_revealed_amount
+        .checked_add(output.change_revealed_amount)
+        .and_then(|amount| amount.checked_add(fee))
+        .ok_or(ConfidentialProofError::AmountOverflow)?
+        .as_u64_checked()
+        .ok_or(ConfidentialProofError::NegativeAmount)?;
+
+    let mut excess = inputs.iter().fold(RistrettoPublicKey::default(), |sum, commitment| {
+        sum + commitment.as_public_key()
+    });
+
+    for statement in output.output_statement.iter().chain(output.change_statement.iter()) {
+        let commitment = PedersenCommitment::from_canonical_bytes(statement.commitment.as_bytes())
+            .map_err(|_| ConfidentialProofError::InvalidCommitment)?;
+        excess = excess - commitment.as_public_key();
+    }
+
+    // 0.G + v.H
+    let factory = get_commitment_factory();
+    let input_revealed_commitment = factory.commit_value(&RistrettoSecretKey::default(), input_revealed_amount);
+    let output_revealed_commitment = factory.commit_value(&RistrettoSecretKey::default(), output_revealed_amount);
+    let excess = excess + input_revealed_commitment.as_public_key() - output_revealed_commitment.as_public_key();
+
+    if excess != RistrettoPublicKey::from_secret_key(excess_secret) {
+        return Err(ConfidentialProofError::Unbalanced);
+    }
+    Ok(excess)
+}
+
+/// Validates many output statements (e.g. all the confidential outputs in a block) concurrently on the rayon thread
+/// pool. The range proof verification dominates the cost of validation, so this scales with the number of cores. The
+/// results are returned in the same order as the given statements.
+pub fn validate_confidential_proofs_parallel(
+    proofs: &[&ConfidentialOutputStatement],
+    view_key: Option<&RistrettoPublicKey>,
+    network: Network,
+) -> Vec<Result<ValidatedConfidentialProof, ResourceError>> {
+    proofs
+        .par_iter()
+        .map(|proof| validate_confidential_proof(proof, view_key, network))
+        .collect()
+}
+
+/// Returns the maximum serialized size of a [ConfidentialOutputStatement] with the given components and a range proof
+/// for `agg_factor` commitments. This allows wallets to estimate fees before generating the proofs. Amounts are assumed
+/// to take their maximum encoded size, so the actual size is never larger than the estimate.
+pub fn estimate_output_statement_size(
+    has_output: bool,
+    has_change: bool,
+    has_view_key: bool,
+    agg_factor: usize,
+) -> usize {
+    let statement = || ConfidentialStatement {
+        commitment: PedersonCommitmentBytes::default(),
+        sender_public_nonce: RistrettoPublicKeyBytes::default(),
+        encrypted_data: EncryptedData::try_from(vec![0; EncryptedData::min_size()])
+            .expect("min_size is a valid encrypted data size"),
+        minimum_value_promise: u64::MAX,
+        viewable_balance_proof: has_view_key.then(|| {
+            let point = RistrettoPublicKeyBytes::default();
+            let scalar = SchnorrSignatureBytes::from_bytes(&[0u8; SchnorrSignatureBytes::length()])
+                .expect("correct length for scalar bytes");
+            ViewableBalanceProof {
+                elgamal_encrypted: point,
+                elgamal_public_nonce: point,
+                c_prime: point,
+                e_prime: point,
+                r_prime: point,
+                s_v: scalar,
+                s_m: scalar,
+                s_r: scalar,
+            }
+        }),
+    };
+
+    let output_statement = ConfidentialOutputStatement {
+        output_statement: has_output.then(statement),
+        change_statement: has_change.then(statement),
+        range_proof: vec![0; expected_range_proof_len(agg_factor)],
+        output_revealed_amount: Amount::MAX,
+        change_revealed_amount: Amount::MAX,
+    };
+
+    tari_bor::encoded_len(&output_statement).expect("ConfidentialOutputStatement is always serializable")
+}
+
 fn inner_encrypted_data_kdf_aead(
     encryption_key: &RistrettoSecretKey,
     commitment: &PedersenCommitment,
 ) -> EncryptedDataKey {
     let mut aead_key = EncryptedDataKey::from(SafeArray::default());
-    DomainSeparatedHasher::<Blake2b<U32>, TransactionSecureNonceKdfDomain>::new_with_label("encrypted_value_and_mask")
-        .chain(encryption_key.as_bytes())
-        .chain(commitment.as_bytes())
-        .finalize_into(GenericArray::from_mut_slice(aead_key.reveal_mut()));
+    kdfs::finalize_secret_into(
+        DomainSeparatedHasher::<Blake2b<U32>, TransactionSecureNonceKdfDomain>::new_with_label(
+            "encrypted_value_and_mask",
+        ),
+        &[encryption_key.as_bytes(), commitment.as_bytes()],
+        aead_key.reveal_mut(),
+    );
     aead_key
 }
 
+/// Creates a proof that the value committed to in `commitment` is encrypted to the view key. Only the hidden
+/// (committed) value of an output is encrypted, so for an output that also has a revealed amount, the view key holder
+/// recovers the hidden value and adds the revealed amount from the output statement, see
+/// [crate::recover_viewable_output_total].
 pub fn create_viewable_balance_proof(
     mask: &RistrettoSecretKey,
     output_amount: u64,
     commitment: &PedersenCommitment,
     view_key: &RistrettoPublicKey,
+    network: Network,
 ) -> ViewableBalanceProof {
+    let (proof, _) = create_viewable_balance_proof_and_challenge(mask, output_amount, commitment, view_key, network);
+    proof
+}
+
+/// Creates a viewable balance proof and additionally returns the 64-byte Fiat-Shamir challenge `e` used to generate
+/// it. This is intended for conformance testing against other implementations, where comparing challenges directly is
+/// more informative than a failed signature check.
+#[cfg(any(test, feature = "conformance"))]
+pub fn viewable_balance_proof_with_challenge(
+    mask: &RistrettoSecretKey,
+    output_amount: u64,
+    commitment: &PedersenCommitment,
+    view_key: &RistrettoPublicKey,
+    network: Network,
+) -> (ViewableBalanceProof, [u8; 64]) {
+    create_viewable_balance_proof_and_challenge(mask, output_amount, commitment, view_key, network)
+}
+
+fn create_viewable_balance_proof_and_challenge(
+    mask: &RistrettoSecretKey,
+    output_amount: u64,
+    commitment: &PedersenCommitment,
+    view_key: &RistrettoPublicKey,
+    network: Network,
+) -> (ViewableBalanceProof, [u8; 64]) {
     let (elgamal_secret_nonce, elgamal_public_nonce) = RistrettoPublicKey::random_keypair(&mut OsRng);
     let r = &elgamal_secret_nonce;
     let value_as_secret = RistrettoSecretKey::from(output_amount);
@@ -162,7 +339,8 @@ pub fn create_viewable_balance_proof(
         r_prime: &r_prime,
     };
 
-    let e = &challenges::viewable_balance_proof_challenge64(commitment, view_key, challenge_fields);
+    let challenge = challenges::viewable_balance_proof_challenge64(network, commitment, view_key, challenge_fields);
+    let e = &challenge;
 
     // Generate signatures
     // TODO: sign_raw_uniform should take a [u8; 64] for the challenge so that length mismatches are caught at compile
@@ -178,7 +356,7 @@ pub fn create_viewable_balance_proof(
     let s_r = RistrettoSchnorr::sign_raw_uniform(r, x_r, e)
         .expect("INVARIANT VIOLATION: sr RistrettoSchnorr::sign_raw_uniform and challenge hash output length mismatch");
 
-    ViewableBalanceProof {
+    let proof = ViewableBalanceProof {
         elgamal_encrypted,
         elgamal_public_nonce,
         c_prime,
@@ -187,17 +365,78 @@ pub fn create_viewable_balance_proof(
         s_v: copy_fixed(s_v.get_signature().as_bytes()),
         s_m: copy_fixed(s_m.get_signature().as_bytes()),
         s_r: copy_fixed(s_r.get_signature().as_bytes()),
-    }
+    };
+
+    (proof, challenge)
+}
+
+/// A proof that a commitment `C` opens to a public value `v` without revealing the mask. This is a Schnorr proof of
+/// knowledge of the mask `m` such that `C - v.H = m.G`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpeningProof {
+    pub signature: RistrettoSchnorr,
+}
+
+/// Creates a proof that `commitment` opens to `value`, given its `mask`. This allows the value of a single output to be
+/// disclosed without disclosing the mask.
+pub fn create_opening_proof(mask: &RistrettoSecretKey, value: u64, commitment: &PedersenCommitment) -> OpeningProof {
+    let (secret_nonce, public_nonce) = RistrettoPublicKey::random_keypair(&mut OsRng);
+    let challenge = challenges::commitment_opening_proof64(commitment, value, &public_nonce);
+    // s = k + e.m
+    let signature = RistrettoSchnorr::sign_raw_uniform(mask, secret_nonce, &challenge)
+        .expect("INVARIANT VIOLATION: RistrettoSchnorr::sign_raw_uniform and challenge hash output length mismatch");
+    OpeningProof { signature }
+}
+
+/// Verifies that `commitment` opens to `value` using the given opening proof
+pub fn verify_opening_proof(commitment: &PedersenCommitment, value: u64, proof: &OpeningProof) -> bool {
+    // P = C - v.H
+    let value_commitment = get_commitment_factory().commit_value(&RistrettoSecretKey::default(), value);
+    let public_key = commitment.as_public_key() - value_commitment.as_public_key();
+    let challenge = challenges::commitment_opening_proof64(commitment, value, proof.signature.get_public_nonce());
+    // s.G = R + e.P
+    proof.signature.verify_raw_uniform(&public_key, &challenge)
 }
 
 const ENCRYPTED_DATA_TAG: &[u8] = b"TARI_AAD_VALUE_AND_MASK_EXTEND_NONCE_VARIANT";
 
+/// The offsets of the memo marker and memo within the decrypted payload
+const MEMO_MARKER_PAYLOAD_OFFSET: usize = EncryptedData::extension_offset() - EncryptedData::payload_offset();
+const MEMO_PAYLOAD_OFFSET: usize = EncryptedData::memo_offset() - EncryptedData::payload_offset();
+
+/// Encrypts the value and mask, and the memo if one is given, for the owner of `encryption_key`. Data without a memo is
+/// byte-compatible with data created before memos were supported.
 pub(crate) fn encrypt_data(
     encryption_key: &RistrettoSecretKey,
     commitment: &PedersenCommitment,
     value: u64,
     mask: &RistrettoSecretKey,
-) -> Result<EncryptedData, aead::Error> {
+    memo: Option<&[u8; EncryptedData::SIZE_MEMO]>,
+) -> Result<EncryptedData, ConfidentialProofError> {
+    // Encode the value, mask and memo
+    let size = if memo.is_some() {
+        EncryptedData::size_with_memo()
+    } else {
+        EncryptedData::min_size()
+    };
+    let mut payload = Zeroizing::new(vec![0; size - EncryptedData::payload_offset()]);
+    payload[..EncryptedData::SIZE_VALUE].copy_from_slice(value.to_le_bytes().as_ref());
+    payload[EncryptedData::SIZE_VALUE..EncryptedData::SIZE_VALUE + EncryptedData::SIZE_MASK]
+        .copy_from_slice(mask.as_bytes());
+    if let Some(memo) = memo {
+        payload[MEMO_MARKER_PAYLOAD_OFFSET] = EncryptedData::MEMO_MARKER;
+        payload[MEMO_PAYLOAD_OFFSET..].copy_from_slice(memo);
+    }
+    encrypt_payload(encryption_key, commitment, &payload)
+}
+
+/// Encrypts the plaintext `payload`, i.e. the value and mask followed by any extension data, for the owner of
+/// `encryption_key`
+fn encrypt_payload(
+    encryption_key: &RistrettoSecretKey,
+    commitment: &PedersenCommitment,
+    payload: &[u8],
+) -> Result<EncryptedData, ConfidentialProofError> {
     fn payload_slice_mut(bytes: &mut [u8]) -> &mut [u8] {
         &mut bytes[EncryptedData::payload_offset()..]
     }
@@ -215,32 +454,30 @@ pub(crate) fn encrypt_data(
     let aead_key = inner_encrypted_data_kdf_aead(encryption_key, commitment);
     let cipher = XChaCha20Poly1305::new(GenericArray::from_slice(aead_key.reveal()));
 
-    // Encode the value and mask
-    let mut bytes = vec![0; EncryptedData::min_size()];
+    let mut bytes = vec![0; EncryptedData::payload_offset() + payload.len()];
     let payload_mut = payload_slice_mut(&mut bytes);
-    payload_mut[..EncryptedData::SIZE_VALUE].copy_from_slice(value.to_le_bytes().as_ref());
-    payload_mut[EncryptedData::SIZE_VALUE..EncryptedData::SIZE_VALUE + EncryptedData::SIZE_MASK]
-        .copy_from_slice(mask.as_bytes());
+    payload_mut.copy_from_slice(payload);
     // Encrypt in place
     match cipher.encrypt_in_place_detached(&nonce, ENCRYPTED_DATA_TAG, payload_mut) {
         Ok(tag) => {
             tag_slice_mut(&mut bytes).copy_from_slice(&tag);
             nonce_slice_mut(&mut bytes).copy_from_slice(&nonce);
 
-            Ok(EncryptedData::try_from(bytes).expect("bytes length == EncryptedData::min_size()"))
+            Ok(EncryptedData::try_from(bytes).expect("min_size <= bytes length <= max_size"))
         },
         Err(err) => {
             bytes.zeroize();
-            Err(err)
+            Err(err.into())
         },
     }
 }
 
-pub fn decrypt_data_and_mask(
+/// Authenticates and decrypts the payload of `encrypted_data`, returning the plaintext value, mask and memo bytes
+fn decrypt_payload(
     encryption_key: &RistrettoSecretKey,
     commitment: &PedersenCommitment,
     encrypted_data: &EncryptedData,
-) -> Result<(u64, RistrettoSecretKey), aead::Error> {
+) -> Result<Zeroizing<Vec<u8>>, ConfidentialProofError> {
     // Extract the tag, nonce, and ciphertext
     let tag = Tag::from_slice(encrypted_data.tag_slice());
     let nonce = XNonce::from_slice(encrypted_data.nonce_slice());
@@ -252,6 +489,15 @@ pub fn decrypt_data_and_mask(
 
     // Decrypt in place
     cipher.decrypt_in_place_detached(nonce, ENCRYPTED_DATA_TAG, bytes.as_mut_slice(), tag)?;
+    Ok(bytes)
+}
+
+pub fn decrypt_data_and_mask(
+    encryption_key: &RistrettoSecretKey,
+    commitment: &PedersenCommitment,
+    encrypted_data: &EncryptedData,
+) -> Result<(u64, RistrettoSecretKey), ConfidentialProofError> {
+    let bytes = decrypt_payload(encryption_key, commitment, encrypted_data)?;
 
     // Decode the value and mask
     let mut value_bytes = [0u8; EncryptedData::SIZE_VALUE];
@@ -265,10 +511,100 @@ pub fn decrypt_data_and_mask(
     ))
 }
 
+/// Decrypts the memo of `encrypted_data`. Returns None if the data was created without a memo, including data that
+/// carries other extension data such as a base layer payment id. The whole payload is authenticated, so an error is
+/// returned if the data cannot be decrypted with `encryption_key` even if there is no memo.
+pub fn decrypt_memo(
+    encryption_key: &RistrettoSecretKey,
+    commitment: &PedersenCommitment,
+    encrypted_data: &EncryptedData,
+) -> Result<Option<[u8; EncryptedData::SIZE_MEMO]>, ConfidentialProofError> {
+    let bytes = decrypt_payload(encryption_key, commitment, encrypted_data)?;
+    if bytes.len() != MEMO_PAYLOAD_OFFSET + EncryptedData::SIZE_MEMO ||
+        bytes[MEMO_MARKER_PAYLOAD_OFFSET] != EncryptedData::MEMO_MARKER
+    {
+        return Ok(None);
+    }
+
+    let mut memo = [0u8; EncryptedData::SIZE_MEMO];
+    memo.copy_from_slice(&bytes[MEMO_PAYLOAD_OFFSET..]);
+    Ok(Some(memo))
+}
+
+/// Decrypts the value and mask of each output in the stream, e.g. as outputs are received from a scan. Decryption is
+/// CPU bound, so each output is decrypted on the rayon thread pool rather than on the task polling the stream. Up to
+/// `concurrency` outputs are decrypted at a time and results are yielded as they complete, so they may not be in the
+/// same order as the outputs.
+pub fn decrypt_stream<S>(
+    encryption_key: &RistrettoSecretKey,
+    outputs: S,
+    concurrency: usize,
+) -> impl Stream<Item = Result<(u64, RistrettoSecretKey), ConfidentialProofError>>
+where
+    S: Stream<Item = (PedersenCommitment, EncryptedData)>,
+{
+    let encryption_key = encryption_key.clone();
+    outputs
+        .map(move |(commitment, encrypted_data)| {
+            let encryption_key = encryption_key.clone();
+            let (tx, rx) = oneshot::channel();
+            rayon::spawn(move || {
+                // The receiver is only dropped if the stream is dropped, in which case the result is not needed
+                let _ignore = tx.send(decrypt_data_and_mask(&encryption_key, &commitment, &encrypted_data));
+            });
+            async move { rx.await.expect("decryption task panicked") }
+        })
+        .buffer_unordered(concurrency.max(1))
+}
+
+/// Re-encrypts the value and mask of `encrypted_data` from `old_key` to `new_key` with a fresh nonce, e.g. when a
+/// wallet rotates its encryption key. An error is returned if the data cannot be decrypted with `old_key` or the
+/// decrypted value and mask do not open `commitment`. Any extension data, such as a memo, is preserved.
+pub fn rekey_encrypted_data(
+    old_key: &RistrettoSecretKey,
+    new_key: &RistrettoSecretKey,
+    commitment: &PedersenCommitment,
+    encrypted_data: &EncryptedData,
+) -> Result<EncryptedData, ConfidentialProofError> {
+    let (value, mask) = decrypt_data_and_mask(old_key, commitment, encrypted_data)?;
+    if !commitment_opens_to(commitment, value, &mask) {
+        return Err(ConfidentialProofError::InvalidCommitment);
+    }
+    let payload = decrypt_payload(old_key, commitment, encrypted_data)?;
+    encrypt_payload(new_key, commitment, &payload)
+}
+
+/// Decrypts each encrypted data with its encryption key and checks that the decrypted value and mask open its
+/// commitment, e.g. to audit outputs given as `(encryption_key, commitment, encrypted_data)`. Each entry is checked
+/// independently, concurrently on the rayon thread pool, and the decrypted values or errors are returned in the same
+/// order as the entries. [ConfidentialProofError::InvalidCommitment] is returned for entries that decrypt but do not
+/// open their commitment.
+pub fn verify_encrypted_bindings(
+    bindings: &[(RistrettoSecretKey, PedersenCommitment, EncryptedData)],
+) -> Vec<Result<u64, ConfidentialProofError>> {
+    bindings
+        .par_iter()
+        .map(|(encryption_key, commitment, encrypted_data)| {
+            let (value, mask) = decrypt_data_and_mask(encryption_key, commitment, encrypted_data)?;
+            if !commitment_opens_to(commitment, value, &mask) {
+                return Err(ConfidentialProofError::InvalidCommitment);
+            }
+            Ok(value)
+        })
+        .collect()
+}
+
+/// Returns true if `value` and `mask` open `commitment`. The commitments are compared in constant time, so the time
+/// taken does not reveal how much of a recomputed commitment matches.
+pub(crate) fn commitment_opens_to(commitment: &PedersenCommitment, value: u64, mask: &RistrettoSecretKey) -> bool {
+    let recomputed = get_commitment_factory().commit_value(mask, value);
+    recomputed.as_bytes().ct_eq(commitment.as_bytes()).into()
+}
+
 fn generate_extended_bullet_proof(
     output_statement: Option<&ConfidentialProofStatement>,
     change_statement: Option<&ConfidentialProofStatement>,
-) -> Result<Vec<u8>, RangeProofError> {
+) -> Result<Vec<u8>, ConfidentialProofError> {
     if output_statement.is_none() && change_statement.is_none() {
         // We're only outputting revealed funds, so no need to generate a range proof (i.e. zero length is valid)
         return Ok(vec![]);
@@ -282,7 +618,7 @@ fn generate_extended_bullet_proof(
             RistrettoExtendedMask::assign(ExtensionDegree::DefaultPedersen, vec![stmt.mask.clone()]).unwrap();
         extended_witnesses.push(RistrettoExtendedWitness {
             mask: extended_mask,
-            value: stmt.amount.value() as u64,
+            value: stmt.checked_value()?,
             minimum_value_promise: stmt.minimum_value_promise,
         });
         agg_factor += 1;
@@ -292,13 +628,18 @@ fn generate_extended_bullet_proof(
             RistrettoExtendedMask::assign(ExtensionDegree::DefaultPedersen, vec![stmt.mask.clone()]).unwrap();
         extended_witnesses.push(RistrettoExtendedWitness {
             mask: extended_mask,
-            value: stmt.amount.value() as u64,
+            value: stmt.checked_value()?,
             minimum_value_promise: stmt.minimum_value_promise,
         });
         agg_factor += 1;
     }
 
-    let output_range_proof = get_range_proof_service(agg_factor).construct_extended_proof(extended_witnesses, None)?;
+    let range_proof_service =
+        try_get_range_proof_service(agg_factor).ok_or(ConfidentialProofError::UnsupportedAggregationFactor {
+            agg_factor,
+            max: MAX_RANGE_PROOF_AGGREGATION_FACTOR,
+        })?;
+    let output_range_proof = range_proof_service.construct_extended_proof(extended_witnesses, None)?;
     Ok(output_range_proof)
 }
 
@@ -329,6 +670,7 @@ mod tests {
                 Default::default(),
                 None,
                 Default::default(),
+                Network::LocalNet,
             )
             .unwrap()
         }
@@ -336,16 +678,231 @@ mod tests {
         #[test]
         fn it_is_valid_if_proof_is_valid() {
             let proof = create_valid_proof(100.into(), 0);
-            validate_confidential_proof(&proof, None).unwrap();
+            validate_confidential_proof(&proof, None, Network::LocalNet).unwrap();
         }
 
         #[test]
         fn it_is_invalid_if_minimum_value_changed() {
             let mut proof = create_valid_proof(100.into(), 100);
             proof.output_statement.as_mut().unwrap().minimum_value_promise = 99;
-            validate_confidential_proof(&proof, None).unwrap_err();
+            validate_confidential_proof(&proof, None, Network::LocalNet).unwrap_err();
             proof.output_statement.as_mut().unwrap().minimum_value_promise = 1000;
-            validate_confidential_proof(&proof, None).unwrap_err();
+            validate_confidential_proof(&proof, None, Network::LocalNet).unwrap_err();
+        }
+    }
+
+    mod validate_confidential_proofs_parallel {
+        use super::*;
+
+        #[test]
+        fn it_validates_each_proof_in_order() {
+            let statement = |amount: i64, minimum_value_promise| {
+                create_confidential_output_statement(
+                    Some(&ConfidentialProofStatement {
+                        amount: amount.into(),
+                        minimum_value_promise,
+                        mask: RistrettoSecretKey::random(&mut OsRng),
+                        sender_public_nonce: Default::default(),
+                        encrypted_data: EncryptedData::try_from(vec![0; EncryptedData::min_size()]).unwrap(),
+                        resource_view_key: None,
+                    }),
+                    Default::default(),
+                    None,
+                    Default::default(),
+                    Network::LocalNet,
+                )
+                .unwrap()
+            };
+            let valid1 = statement(100, 0);
+            let mut invalid = statement(100, 100);
+            invalid.output_statement.as_mut().unwrap().minimum_value_promise = 99;
+            let valid2 = statement(50, 10);
+
+            let results = validate_confidential_proofs_parallel(&[&valid1, &invalid, &valid2], None, Network::LocalNet);
+            assert_eq!(results.len(), 3);
+            assert!(results[0].is_ok());
+            assert!(results[1].is_err());
+            assert!(results[2].is_ok());
+        }
+    }
+
+    mod verify_balance {
+        use super::*;
+
+        fn create_output(
+            amount: u64,
+            output_revealed_amount: u64,
+            mask: &RistrettoSecretKey,
+        ) -> ConfidentialOutputStatement {
+            create_confidential_output_statement(
+                Some(&ConfidentialProofStatement {
+                    amount: amount.into(),
+                    minimum_value_promise: 0,
+                    mask: mask.clone(),
+                    sender_public_nonce: Default::default(),
+                    encrypted_data: EncryptedData::try_from(vec![0; EncryptedData::min_size()]).unwrap(),
+                    resource_view_key: None,
+                }),
+                output_revealed_amount.into(),
+                None,
+                Default::default(),
+                Network::LocalNet,
+            )
+            .unwrap()
+        }
+
+        #[test]
+        fn it_returns_the_public_excess_if_balanced() {
+            let input_mask = RistrettoSecretKey::random(&mut OsRng);
+            let input = get_commitment_factory().commit_value(&input_mask, 100);
+            let output_mask = RistrettoSecretKey::random(&mut OsRng);
+            let output = create_output(60, 30, &output_mask);
+            let excess_secret = &input_mask - &output_mask;
+
+            let excess = verify_balance(&[input], Amount::zero(), &output, 10.into(), &excess_secret).unwrap();
+            assert_eq!(excess, RistrettoPublicKey::from_secret_key(&excess_secret));
+        }
+
+        #[test]
+        fn it_includes_the_revealed_input_amount() {
+            let input_mask = RistrettoSecretKey::random(&mut OsRng);
+            let input = get_commitment_factory().commit_value(&input_mask, 100);
+            let output_mask = RistrettoSecretKey::random(&mut OsRng);
+            let output = create_output(60, 70, &output_mask);
+            let excess_secret = &input_mask - &output_mask;
+
+            let excess = verify_balance(&[input.clone()], 40.into(), &output, 10.into(), &excess_secret).unwrap();
+            assert_eq!(excess, RistrettoPublicKey::from_secret_key(&excess_secret));
+
+            let err = verify_balance(&[input], Amount::zero(), &output, 10.into(), &excess_secret).unwrap_err();
+            assert!(matches!(err, ConfidentialProofError::Unbalanced));
+        }
+
+        #[test]
+        fn it_fails_if_not_balanced() {
+            let input_mask = RistrettoSecretKey::random(&mut OsRng);
+            let input = get_commitment_factory().commit_value(&input_mask, 100);
+            let output_mask = RistrettoSecretKey::random(&mut OsRng);
+            let output = create_output(60, 30, &output_mask);
+            let excess_secret = &input_mask - &output_mask;
+
+            let err = verify_balance(&[input.clone()], Amount::zero(), &output, 11.into(), &excess_secret).unwrap_err();
+            assert!(matches!(err, ConfidentialProofError::Unbalanced));
+
+            // Balanced values but the wrong masks
+            let wrong_secret = RistrettoSecretKey::random(&mut OsRng);
+            let err = verify_balance(&[input], Amount::zero(), &output, 10.into(), &wrong_secret).unwrap_err();
+            assert!(matches!(err, ConfidentialProofError::Unbalanced));
+        }
+    }
+
+    mod estimate_output_statement_size {
+        use super::*;
+
+        #[test]
+        fn it_is_at_least_the_size_of_the_generated_statement() {
+            let statement = |amount: i64| ConfidentialProofStatement {
+                amount: amount.into(),
+                minimum_value_promise: 0,
+                mask: RistrettoSecretKey::random(&mut OsRng),
+                sender_public_nonce: Default::default(),
+                encrypted_data: EncryptedData::try_from(vec![0; EncryptedData::min_size()]).unwrap(),
+                resource_view_key: None,
+            };
+            let output = statement(100);
+            let change = statement(50);
+
+            let proof = create_confidential_output_statement(
+                Some(&output),
+                10.into(),
+                Some(&change),
+                Default::default(),
+                Network::LocalNet,
+            )
+            .unwrap();
+            let size = tari_bor::encoded_len(&proof).unwrap();
+            let estimate = estimate_output_statement_size(true, true, false, 2);
+            assert!(size <= estimate);
+            // Only the variable length amounts may be smaller
+            assert!(estimate - size <= 32);
+        }
+    }
+
+    mod range_proof_len {
+        use super::*;
+
+        #[test]
+        fn it_matches_the_generated_proof_length() {
+            let statement = |amount: i64| ConfidentialProofStatement {
+                amount: amount.into(),
+                minimum_value_promise: 0,
+                mask: RistrettoSecretKey::random(&mut OsRng),
+                sender_public_nonce: Default::default(),
+                encrypted_data: EncryptedData::try_from(vec![0; EncryptedData::min_size()]).unwrap(),
+                resource_view_key: None,
+            };
+            let output = statement(100);
+            let change = statement(50);
+
+            let proof = generate_extended_bullet_proof(Some(&output), None).unwrap();
+            assert_eq!(proof.len(), expected_range_proof_len(1));
+            let proof = generate_extended_bullet_proof(Some(&output), Some(&change)).unwrap();
+            assert_eq!(proof.len(), expected_range_proof_len(2));
+            let proof = generate_extended_bullet_proof(None, None).unwrap();
+            assert_eq!(proof.len(), expected_range_proof_len(0));
+        }
+    }
+
+    mod viewable_balance_proof {
+        use tari_crypto::ristretto::RistrettoSecretKey;
+
+        use super::*;
+
+        #[test]
+        fn it_returns_the_challenge_used_in_the_proof() {
+            let mask = RistrettoSecretKey::random(&mut OsRng);
+            let (_, view_key) = RistrettoPublicKey::random_keypair(&mut OsRng);
+            let commitment = get_commitment_factory().commit_value(&mask, 123);
+
+            let (proof, challenge) =
+                viewable_balance_proof_with_challenge(&mask, 123, &commitment, &view_key, Network::LocalNet);
+
+            let expected = challenges::viewable_balance_proof_challenge64(
+                Network::LocalNet,
+                &commitment,
+                &view_key,
+                proof.as_challenge_fields(),
+            );
+            assert_eq!(challenge, expected);
+        }
+    }
+
+    mod opening_proof {
+        use super::*;
+
+        #[test]
+        fn it_verifies_the_opened_value() {
+            let mask = RistrettoSecretKey::random(&mut OsRng);
+            let commitment = get_commitment_factory().commit_value(&mask, 123);
+
+            let proof = create_opening_proof(&mask, 123, &commitment);
+            assert!(verify_opening_proof(&commitment, 123, &proof));
+        }
+
+        #[test]
+        fn it_is_invalid_for_a_different_value_or_commitment() {
+            let mask = RistrettoSecretKey::random(&mut OsRng);
+            let commitment = get_commitment_factory().commit_value(&mask, 123);
+            let proof = create_opening_proof(&mask, 123, &commitment);
+            assert!(!verify_opening_proof(&commitment, 124, &proof));
+
+            let other_commitment = get_commitment_factory().commit_value(&mask, 124);
+            assert!(!verify_opening_proof(&other_commitment, 124, &proof));
+
+            // The commitment does not open to the value with a different mask
+            let wrong_mask = RistrettoSecretKey::random(&mut OsRng);
+            let proof = create_opening_proof(&wrong_mask, 123, &commitment);
+            assert!(!verify_opening_proof(&commitment, 123, &proof));
         }
     }
 
@@ -360,10 +917,149 @@ mod tests {
             let amount = 100;
             let commitment = get_commitment_factory().commit_value(&key, amount);
             let mask = RistrettoSecretKey::random(&mut OsRng);
-            let encrypted = encrypt_data(&key, &commitment, amount, &mask).unwrap();
+            let encrypted = encrypt_data(&key, &commitment, amount, &mask, None).unwrap();
 
             let val = decrypt_data_and_mask(&key, &commitment, &encrypted).unwrap();
             assert_eq!(val.0, 100);
         }
+
+        #[test]
+        fn it_rekeys_encrypted_data() {
+            let old_key = RistrettoSecretKey::random(&mut OsRng);
+            let new_key = RistrettoSecretKey::random(&mut OsRng);
+            let mask = RistrettoSecretKey::random(&mut OsRng);
+            let commitment = get_commitment_factory().commit_value(&mask, 100);
+            let encrypted = encrypt_data(&old_key, &commitment, 100, &mask, None).unwrap();
+
+            let rekeyed = rekey_encrypted_data(&old_key, &new_key, &commitment, &encrypted).unwrap();
+            let (value, decrypted_mask) = decrypt_data_and_mask(&new_key, &commitment, &rekeyed).unwrap();
+            assert_eq!(value, 100);
+            assert_eq!(decrypted_mask, mask);
+            let err = decrypt_data_and_mask(&old_key, &commitment, &rekeyed).unwrap_err();
+            assert!(matches!(err, ConfidentialProofError::Aead(_)));
+        }
+
+        #[test]
+        fn it_decrypts_a_stream_of_outputs() {
+            let key = RistrettoSecretKey::random(&mut OsRng);
+            let outputs = (1..=10u64)
+                .map(|value| {
+                    let mask = RistrettoSecretKey::random(&mut OsRng);
+                    let commitment = get_commitment_factory().commit_value(&mask, value);
+                    let encrypted = encrypt_data(&key, &commitment, value, &mask, None).unwrap();
+                    (commitment, encrypted)
+                })
+                .collect::<Vec<_>>();
+
+            let results = futures::executor::block_on(
+                decrypt_stream(&key, futures::stream::iter(outputs), 4).collect::<Vec<_>>(),
+            );
+            let mut values = results.into_iter().map(|result| result.unwrap().0).collect::<Vec<_>>();
+            values.sort_unstable();
+            assert_eq!(values, (1..=10).collect::<Vec<_>>());
+        }
+
+        #[test]
+        fn it_encrypts_and_decrypts_a_memo() {
+            let key = RistrettoSecretKey::random(&mut OsRng);
+            let mask = RistrettoSecretKey::random(&mut OsRng);
+            let commitment = get_commitment_factory().commit_value(&mask, 100);
+            let mut memo = [0u8; EncryptedData::SIZE_MEMO];
+            memo[..5].copy_from_slice(b"hello");
+            let encrypted = encrypt_data(&key, &commitment, 100, &mask, Some(&memo)).unwrap();
+            assert_eq!(encrypted.len(), EncryptedData::size_with_memo());
+
+            let (value, decrypted_mask) = decrypt_data_and_mask(&key, &commitment, &encrypted).unwrap();
+            assert_eq!(value, 100);
+            assert_eq!(decrypted_mask, mask);
+            assert_eq!(decrypt_memo(&key, &commitment, &encrypted).unwrap(), Some(memo));
+
+            let other_key = RistrettoSecretKey::random(&mut OsRng);
+            let err = decrypt_memo(&other_key, &commitment, &encrypted).unwrap_err();
+            assert!(matches!(err, ConfidentialProofError::Aead(_)));
+        }
+
+        #[test]
+        fn it_has_no_memo_if_encrypted_without_one() {
+            let key = RistrettoSecretKey::random(&mut OsRng);
+            let mask = RistrettoSecretKey::random(&mut OsRng);
+            let commitment = get_commitment_factory().commit_value(&mask, 100);
+            let encrypted = encrypt_data(&key, &commitment, 100, &mask, None).unwrap();
+            assert_eq!(encrypted.len(), EncryptedData::min_size());
+
+            assert_eq!(decrypt_memo(&key, &commitment, &encrypted).unwrap(), None);
+        }
+
+        #[test]
+        fn it_decrypts_data_with_a_base_layer_payment_id() {
+            let key = RistrettoSecretKey::random(&mut OsRng);
+            let mask = RistrettoSecretKey::random(&mut OsRng);
+            let commitment = get_commitment_factory().commit_value(&mask, 100);
+            for extension_len in [32, EncryptedData::max_size() - EncryptedData::min_size()] {
+                let mut payload =
+                    vec![1u8; EncryptedData::min_size() - EncryptedData::payload_offset() + extension_len];
+                payload[..EncryptedData::SIZE_VALUE].copy_from_slice(&100u64.to_le_bytes());
+                payload[EncryptedData::SIZE_VALUE..EncryptedData::SIZE_VALUE + EncryptedData::SIZE_MASK]
+                    .copy_from_slice(mask.as_bytes());
+                let encrypted = encrypt_payload(&key, &commitment, &payload).unwrap();
+                assert_eq!(encrypted.len(), EncryptedData::min_size() + extension_len);
+
+                let (value, decrypted_mask) = decrypt_data_and_mask(&key, &commitment, &encrypted).unwrap();
+                assert_eq!(value, 100);
+                assert_eq!(decrypted_mask, mask);
+                // The extension is not marked as a memo
+                assert_eq!(decrypt_memo(&key, &commitment, &encrypted).unwrap(), None);
+
+                let new_key = RistrettoSecretKey::random(&mut OsRng);
+                let rekeyed = rekey_encrypted_data(&key, &new_key, &commitment, &encrypted).unwrap();
+                assert_eq!(*decrypt_payload(&new_key, &commitment, &rekeyed).unwrap(), payload);
+            }
+        }
+
+        #[test]
+        fn it_preserves_the_memo_when_rekeying() {
+            let old_key = RistrettoSecretKey::random(&mut OsRng);
+            let new_key = RistrettoSecretKey::random(&mut OsRng);
+            let mask = RistrettoSecretKey::random(&mut OsRng);
+            let commitment = get_commitment_factory().commit_value(&mask, 100);
+            let memo = [7u8; EncryptedData::SIZE_MEMO];
+            let encrypted = encrypt_data(&old_key, &commitment, 100, &mask, Some(&memo)).unwrap();
+
+            let rekeyed = rekey_encrypted_data(&old_key, &new_key, &commitment, &encrypted).unwrap();
+            assert_eq!(decrypt_memo(&new_key, &commitment, &rekeyed).unwrap(), Some(memo));
+        }
+
+        #[test]
+        fn it_fails_to_rekey_if_the_commitment_does_not_open() {
+            let key = RistrettoSecretKey::random(&mut OsRng);
+            let mask = RistrettoSecretKey::random(&mut OsRng);
+            let commitment = get_commitment_factory().commit_value(&mask, 100);
+            // The encrypted value does not match the committed value
+            let encrypted = encrypt_data(&key, &commitment, 99, &mask, None).unwrap();
+
+            let err = rekey_encrypted_data(&key, &key, &commitment, &encrypted).unwrap_err();
+            assert!(matches!(err, ConfidentialProofError::InvalidCommitment));
+        }
+
+        #[test]
+        fn it_verifies_each_encrypted_binding() {
+            let key = RistrettoSecretKey::random(&mut OsRng);
+            let mask = RistrettoSecretKey::random(&mut OsRng);
+            let commitment = get_commitment_factory().commit_value(&mask, 100);
+            let valid = encrypt_data(&key, &commitment, 100, &mask, None).unwrap();
+            // The encrypted value does not match the committed value
+            let mismatched = encrypt_data(&key, &commitment, 99, &mask, None).unwrap();
+            let other_key = RistrettoSecretKey::random(&mut OsRng);
+
+            let results = verify_encrypted_bindings(&[
+                (key.clone(), commitment.clone(), valid.clone()),
+                (key.clone(), commitment.clone(), mismatched),
+                (other_key, commitment.clone(), valid),
+            ]);
+            assert_eq!(results.len(), 3);
+            assert_eq!(*results[0].as_ref().unwrap(), 100);
+            assert!(matches!(results[1], Err(ConfidentialProofError::InvalidCommitment)));
+            assert!(matches!(results[2], Err(ConfidentialProofError::Aead(_))));
+        }
     }
 }
@@ -0,0 +1,143 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::collections::HashMap;
+
+use tari_crypto::{
+    commitment::HomomorphicCommitmentFactory,
+    ristretto::{pedersen::PedersenCommitment, RistrettoPublicKey, RistrettoSecretKey},
+    tari_utilities::ByteArray,
+};
+use tari_engine_types::confidential::get_commitment_factory;
+
+use crate::{byte_utils::copy_fixed, ConfidentialProofError};
+
+/// The largest `max_value` accepted by [recover_value_from_commitment]. The baby-step table has sqrt(max_value)
+/// entries, so this bounds it to about a million entries.
+pub const MAX_RECOVERABLE_VALUE: u64 = 1 << 40;
+
+/// Recovers the value of a commitment from its mask by solving `value.H = C - mask.G` for a value in
+/// `0..=max_value` using a baby-step giant-step search. This is a last resort for when the mask is known (e.g. derived
+/// from the wallet seed) but the encrypted data has been lost. The search takes O(sqrt(max_value)) time and memory.
+/// Returns `None` if the mask does not open the commitment to a value in the range, or
+/// [ConfidentialProofError::MaxValueTooLarge] if `max_value` is greater than [MAX_RECOVERABLE_VALUE].
+pub fn recover_value_from_commitment(
+    commitment: &PedersenCommitment,
+    mask: &RistrettoSecretKey,
+    max_value: u64,
+) -> Result<Option<u64>, ConfidentialProofError> {
+    if max_value > MAX_RECOVERABLE_VALUE {
+        return Err(ConfidentialProofError::MaxValueTooLarge {
+            max_value,
+            max: MAX_RECOVERABLE_VALUE,
+        });
+    }
+
+    let factory = get_commitment_factory();
+    // value.H = C - mask.G
+    let target = commitment.as_public_key() - factory.commit_value(mask, 0).as_public_key();
+    let h = factory
+        .commit_value(&RistrettoSecretKey::default(), 1)
+        .as_public_key()
+        .clone();
+
+    // (isqrt(max_value) + 1)^2 > max_value, so every value in the range is i.m + j for some i, j < m
+    let num_steps = integer_sqrt(max_value) + 1;
+
+    // Baby steps: j.H for j in 0..m
+    let mut baby_steps = HashMap::new();
+    let mut point = RistrettoPublicKey::default();
+    for j in 0..num_steps {
+        baby_steps.insert(copy_fixed::<32, [u8; 32]>(point.as_bytes()), j);
+        point = &point + &h;
+    }
+
+    // Giant steps: target - i.m.H for i in 0..m
+    let giant_step = &RistrettoSecretKey::from(num_steps) * &h;
+    let mut point = target;
+    for i in 0..num_steps {
+        if let Some(j) = baby_steps.get(&copy_fixed::<32, [u8; 32]>(point.as_bytes())) {
+            let value = i * num_steps + j;
+            return Ok((value <= max_value).then_some(value));
+        }
+        point = &point - &giant_step;
+    }
+
+    Ok(None)
+}
+
+/// Returns the floor of the square root of `n`, which must be at most [MAX_RECOVERABLE_VALUE] so that squaring the
+/// result cannot overflow
+fn integer_sqrt(n: u64) -> u64 {
+    // The float estimate may be off by one for large n, so it is corrected using exact integer arithmetic
+    let mut root = (n as f64).sqrt() as u64;
+    while root * root > n {
+        root -= 1;
+    }
+    while (root + 1) * (root + 1) <= n {
+        root += 1;
+    }
+    root
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::OsRng;
+    use tari_crypto::keys::SecretKey;
+
+    use super::*;
+
+    #[test]
+    fn it_recovers_values_in_range() {
+        let mask = RistrettoSecretKey::random(&mut OsRng);
+        for value in [0, 1, 99, 100, 12_345] {
+            let commitment = get_commitment_factory().commit_value(&mask, value);
+            assert_eq!(
+                recover_value_from_commitment(&commitment, &mask, 12_345).unwrap(),
+                Some(value)
+            );
+        }
+    }
+
+    #[test]
+    fn it_returns_none_if_the_value_is_out_of_range() {
+        let mask = RistrettoSecretKey::random(&mut OsRng);
+        let commitment = get_commitment_factory().commit_value(&mask, 1001);
+        assert_eq!(recover_value_from_commitment(&commitment, &mask, 1000).unwrap(), None);
+    }
+
+    #[test]
+    fn it_returns_none_for_the_wrong_mask() {
+        let mask = RistrettoSecretKey::random(&mut OsRng);
+        let commitment = get_commitment_factory().commit_value(&mask, 10);
+        let wrong_mask = RistrettoSecretKey::random(&mut OsRng);
+        assert_eq!(
+            recover_value_from_commitment(&commitment, &wrong_mask, 1000).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn it_rejects_a_max_value_that_is_too_large() {
+        let mask = RistrettoSecretKey::random(&mut OsRng);
+        let commitment = get_commitment_factory().commit_value(&mask, 10);
+        let err = recover_value_from_commitment(&commitment, &mask, MAX_RECOVERABLE_VALUE + 1).unwrap_err();
+        assert!(matches!(err, ConfidentialProofError::MaxValueTooLarge { .. }));
+    }
+
+    #[test]
+    fn it_calculates_the_integer_square_root() {
+        for (n, expected) in [
+            (0, 0),
+            (1, 1),
+            (3, 1),
+            (4, 2),
+            (99, 9),
+            (100, 10),
+            (MAX_RECOVERABLE_VALUE, 1 << 20),
+        ] {
+            assert_eq!(integer_sqrt(n), expected);
+        }
+        assert_eq!(integer_sqrt(MAX_RECOVERABLE_VALUE - 1), (1 << 20) - 1);
+    }
+}
@@ -0,0 +1,120 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::fmt::Display;
+
+use tari_crypto::{
+    keys::PublicKey as _,
+    ristretto::{RistrettoPublicKey, RistrettoSecretKey},
+};
+use tari_engine_types::confidential::{ElgamalVerifiableBalance, ValueLookupTable};
+use tari_template_lib::models::Amount;
+
+use crate::ConfidentialProofError;
+
+/// Recovers the value of a viewable balance by searching the values `0..=max_value`. The search is bounded, so a value
+/// greater than `max_value` results in [ConfidentialProofError::ValueOutOfRange] rather than an unbounded search.
+pub fn recover_viewable_balance<TLookup>(
+    balance: &ElgamalVerifiableBalance,
+    view_private_key: &RistrettoSecretKey,
+    max_value: u64,
+    lookup_table: &mut TLookup,
+) -> Result<u64, ConfidentialProofError>
+where
+    TLookup: ValueLookupTable,
+    TLookup::Error: Display,
+{
+    balance
+        .brute_force_balance(view_private_key, 0..=max_value, lookup_table)
+        .map_err(|e| ConfidentialProofError::ValueLookupFailed { details: e.to_string() })?
+        .ok_or(ConfidentialProofError::ValueOutOfRange { max_value })
+}
+
+/// Recovers the total value of an output whose amount is split into a hidden and a revealed portion. The viewable
+/// balance only encrypts the hidden portion, since that is the value that the proof binds to the commitment. The
+/// revealed portion is public in the output statement and is added to the hidden portion, which is searched for in
+/// `0..=max_value`.
+pub fn recover_viewable_output_total<TLookup>(
+    balance: &ElgamalVerifiableBalance,
+    revealed_amount: Amount,
+    view_private_key: &RistrettoSecretKey,
+    max_value: u64,
+    lookup_table: &mut TLookup,
+) -> Result<u64, ConfidentialProofError>
+where
+    TLookup: ValueLookupTable,
+    TLookup::Error: Display,
+{
+    let revealed_amount = revealed_amount
+        .as_u64_checked()
+        .ok_or(ConfidentialProofError::NegativeAmount)?;
+    let hidden_amount = recover_viewable_balance(balance, view_private_key, max_value, lookup_table)?;
+    hidden_amount
+        .checked_add(revealed_amount)
+        .ok_or(ConfidentialProofError::AmountOverflow)
+}
+
+/// Returns true if the viewable balance encrypts the candidate value. Unlike [recover_viewable_balance], this is a
+/// constant time check and can be used when the expected value is already known.
+pub fn recover_value_hint(
+    balance: &ElgamalVerifiableBalance,
+    view_private_key: &RistrettoSecretKey,
+    candidate_value: u64,
+) -> bool {
+    // V = E - pR
+    let value_point = &balance.encrypted - view_private_key * &balance.public_nonce;
+    value_point == RistrettoPublicKey::from_secret_key(&RistrettoSecretKey::from(candidate_value))
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::OsRng;
+    use tari_crypto::keys::SecretKey;
+
+    use super::*;
+    use crate::AlwaysMissLookupTable;
+
+    fn create_balance(view_key: &RistrettoSecretKey, value: u64) -> ElgamalVerifiableBalance {
+        let (nonce_sk, nonce_pk) = RistrettoPublicKey::random_keypair(&mut OsRng);
+        let rp = nonce_sk * view_key;
+        ElgamalVerifiableBalance {
+            encrypted: RistrettoPublicKey::from_secret_key(&rp) +
+                RistrettoPublicKey::from_secret_key(&RistrettoSecretKey::from(value)),
+            public_nonce: nonce_pk,
+        }
+    }
+
+    mod recover_viewable_balance {
+        use super::*;
+
+        #[test]
+        fn it_recovers_values_up_to_the_max_value() {
+            let view_key = RistrettoSecretKey::random(&mut OsRng);
+            let balance = create_balance(&view_key, 100);
+
+            let value = recover_viewable_balance(&balance, &view_key, 100, &mut AlwaysMissLookupTable).unwrap();
+            assert_eq!(value, 100);
+
+            let err = recover_viewable_balance(&balance, &view_key, 99, &mut AlwaysMissLookupTable).unwrap_err();
+            assert!(matches!(err, ConfidentialProofError::ValueOutOfRange { max_value: 99 }));
+        }
+    }
+
+    mod recover_value_hint {
+        use super::*;
+
+        #[test]
+        fn it_confirms_the_candidate_value() {
+            let view_key = RistrettoSecretKey::random(&mut OsRng);
+            let balance = create_balance(&view_key, 1234);
+
+            assert!(recover_value_hint(&balance, &view_key, 1234));
+            assert!(!recover_value_hint(&balance, &view_key, 1235));
+            assert!(!recover_value_hint(
+                &balance,
+                &RistrettoSecretKey::random(&mut OsRng),
+                1234
+            ));
+        }
+    }
+}
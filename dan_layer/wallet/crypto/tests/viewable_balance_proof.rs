@@ -4,6 +4,7 @@
 use std::time::Instant;
 
 use rand::rngs::OsRng;
+use tari_common::configuration::Network;
 use tari_crypto::{
     keys::{PublicKey, SecretKey},
     ristretto::{pedersen::PedersenCommitment, RistrettoPublicKey, RistrettoSecretKey},
@@ -11,11 +12,18 @@ use tari_crypto::{
 use tari_dan_wallet_crypto::{
     create_confidential_output_statement,
     create_withdraw_proof,
+    recover_viewable_output_total,
     AlwaysMissLookupTable,
     ConfidentialProofStatement,
 };
-use tari_engine_types::confidential::validate_elgamal_verifiable_balance_proof;
+use tari_engine_types::confidential::{
+    validate_confidential_proof,
+    validate_elgamal_verifiable_balance_proof,
+    verify_viewable_balance_proof,
+    ViewableProofFailure,
+};
 use tari_template_lib::{
+    crypto::{RistrettoPublicKeyBytes, SchnorrSignatureBytes},
     models::{Amount, EncryptedData},
     template_dependencies::{decode_exact, encode_with_len},
 };
@@ -42,7 +50,7 @@ fn keypair_from_seed(seed: u8) -> (RistrettoSecretKey, RistrettoPublicKey) {
 #[test]
 fn it_allows_no_balance_proof_for_no_view_key() {
     let commitment = PedersenCommitment::from_public_key(&RistrettoPublicKey::default());
-    let proof = validate_elgamal_verifiable_balance_proof(&commitment, None, None).unwrap();
+    let proof = validate_elgamal_verifiable_balance_proof(&commitment, None, None, Network::LocalNet).unwrap();
     assert!(proof.is_none());
 }
 
@@ -51,8 +59,14 @@ fn it_errors_no_balance_proof_with_view_key() {
     let (_, view_key) = keypair_from_seed(1);
     let output_statement = create_output_statement(123.into(), &view_key);
 
-    let proof =
-        create_confidential_output_statement(Some(&output_statement), Amount::zero(), None, Amount::zero()).unwrap();
+    let proof = create_confidential_output_statement(
+        Some(&output_statement),
+        Amount::zero(),
+        None,
+        Amount::zero(),
+        Network::LocalNet,
+    )
+    .unwrap();
     let output_statement = proof.output_statement.as_ref().unwrap();
     let viewable_balance_proof = proof
         .output_statement
@@ -62,13 +76,20 @@ fn it_errors_no_balance_proof_with_view_key() {
         .as_ref()
         .unwrap();
     let commitment = PedersenCommitment::from_canonical_bytes(output_statement.commitment.as_ref()).unwrap();
-    validate_elgamal_verifiable_balance_proof(&commitment, None, Some(viewable_balance_proof)).unwrap_err();
+    validate_elgamal_verifiable_balance_proof(&commitment, None, Some(viewable_balance_proof), Network::LocalNet)
+        .unwrap_err();
 }
 
 #[test]
 fn it_errors_with_balance_proof_and_no_view_key() {
     let commitment = PedersenCommitment::from_public_key(&RistrettoPublicKey::default());
-    validate_elgamal_verifiable_balance_proof(&commitment, Some(&RistrettoPublicKey::default()), None).unwrap_err();
+    validate_elgamal_verifiable_balance_proof(
+        &commitment,
+        Some(&RistrettoPublicKey::default()),
+        None,
+        Network::LocalNet,
+    )
+    .unwrap_err();
 }
 
 #[test]
@@ -77,8 +98,14 @@ fn it_generates_a_valid_proof() {
     let output_statement = create_output_statement(123.into(), &view_key);
 
     let timer = Instant::now();
-    let proof =
-        create_confidential_output_statement(Some(&output_statement), Amount::zero(), None, Amount::zero()).unwrap();
+    let proof = create_confidential_output_statement(
+        Some(&output_statement),
+        Amount::zero(),
+        None,
+        Amount::zero(),
+        Network::LocalNet,
+    )
+    .unwrap();
     let gen_proof_time = timer.elapsed();
 
     let output_statement = proof.output_statement.as_ref().unwrap();
@@ -91,9 +118,14 @@ fn it_generates_a_valid_proof() {
         .unwrap();
     let commitment = PedersenCommitment::from_canonical_bytes(output_statement.commitment.as_ref()).unwrap();
     let timer = Instant::now();
-    let proof = validate_elgamal_verifiable_balance_proof(&commitment, Some(&view_key), Some(viewable_balance_proof))
-        .unwrap()
-        .unwrap();
+    let proof = validate_elgamal_verifiable_balance_proof(
+        &commitment,
+        Some(&view_key),
+        Some(viewable_balance_proof),
+        Network::LocalNet,
+    )
+    .unwrap()
+    .unwrap();
     let validate_proof_time = timer.elapsed();
 
     let timer = Instant::now();
@@ -108,6 +140,114 @@ fn it_generates_a_valid_proof() {
     println!("Brute force time: {:?}", brute_force_time);
 }
 
+#[test]
+fn it_recovers_the_total_of_a_mixed_revealed_and_hidden_output() {
+    let (view_key_secret, view_key) = keypair_from_seed(1);
+    let output_statement = create_output_statement(123.into(), &view_key);
+
+    let proof = create_confidential_output_statement(
+        Some(&output_statement),
+        Amount::new(77),
+        None,
+        Amount::zero(),
+        Network::LocalNet,
+    )
+    .unwrap();
+    let validated = validate_confidential_proof(&proof, Some(&view_key), Network::LocalNet).unwrap();
+    let balance = validated.output.unwrap().viewable_balance.unwrap();
+
+    // The viewable balance only encrypts the hidden portion
+    let hidden = balance
+        .brute_force_balance(&view_key_secret, 0..=1000, &mut AlwaysMissLookupTable)
+        .unwrap();
+    assert_eq!(hidden, Some(123));
+
+    let total = recover_viewable_output_total(
+        &balance,
+        validated.output_revealed_amount,
+        &view_key_secret,
+        1000,
+        &mut AlwaysMissLookupTable,
+    )
+    .unwrap();
+    assert_eq!(total, 200);
+}
+
+#[test]
+fn it_is_invalid_on_a_different_network() {
+    let (_, view_key) = keypair_from_seed(1);
+    let output_statement = create_output_statement(123.into(), &view_key);
+
+    let proof = create_confidential_output_statement(
+        Some(&output_statement),
+        Amount::zero(),
+        None,
+        Amount::zero(),
+        Network::Esmeralda,
+    )
+    .unwrap();
+    let output_statement = proof.output_statement.as_ref().unwrap();
+    let viewable_balance_proof = output_statement.viewable_balance_proof.as_ref().unwrap();
+    let commitment = PedersenCommitment::from_canonical_bytes(output_statement.commitment.as_ref()).unwrap();
+
+    validate_elgamal_verifiable_balance_proof(
+        &commitment,
+        Some(&view_key),
+        Some(viewable_balance_proof),
+        Network::Esmeralda,
+    )
+    .unwrap();
+    validate_elgamal_verifiable_balance_proof(
+        &commitment,
+        Some(&view_key),
+        Some(viewable_balance_proof),
+        Network::MainNet,
+    )
+    .unwrap_err();
+}
+
+#[test]
+fn it_reports_which_check_failed() {
+    let (_, view_key) = keypair_from_seed(1);
+    let output_statement = create_output_statement(123.into(), &view_key);
+
+    let proof = create_confidential_output_statement(
+        Some(&output_statement),
+        Amount::zero(),
+        None,
+        Amount::zero(),
+        Network::LocalNet,
+    )
+    .unwrap();
+    let output_statement = proof.output_statement.as_ref().unwrap();
+    let viewable_balance_proof = output_statement.viewable_balance_proof.as_ref().unwrap();
+    let commitment = PedersenCommitment::from_canonical_bytes(output_statement.commitment.as_ref()).unwrap();
+    verify_viewable_balance_proof(&commitment, &view_key, viewable_balance_proof, Network::LocalNet).unwrap();
+
+    let random_scalar = || {
+        let mut bytes = [0u8; 32];
+        bytes.copy_from_slice(RistrettoSecretKey::random(&mut OsRng).as_bytes());
+        SchnorrSignatureBytes::from(bytes)
+    };
+
+    // s_m is only used in the commitment check
+    let mut tampered = viewable_balance_proof.clone();
+    tampered.s_m = random_scalar();
+    let err = verify_viewable_balance_proof(&commitment, &view_key, &tampered, Network::LocalNet).unwrap_err();
+    assert_eq!(err, ViewableProofFailure::MaskCheck);
+
+    // s_r is first used in the ElGamal value check
+    let mut tampered = viewable_balance_proof.clone();
+    tampered.s_r = random_scalar();
+    let err = verify_viewable_balance_proof(&commitment, &view_key, &tampered, Network::LocalNet).unwrap_err();
+    assert_eq!(err, ViewableProofFailure::ValueCheck);
+
+    let mut tampered = viewable_balance_proof.clone();
+    tampered.elgamal_encrypted = RistrettoPublicKeyBytes::from([0xff; 32]);
+    let err = verify_viewable_balance_proof(&commitment, &view_key, &tampered, Network::LocalNet).unwrap_err();
+    assert_eq!(err, ViewableProofFailure::MalformedPoint { field: "E" });
+}
+
 #[test]
 fn serialize_deserialize() {
     let (_view_key_secret, view_key) = keypair_from_seed(1);
@@ -121,6 +261,7 @@ fn serialize_deserialize() {
         Amount(123),
         Some(&change_statement),
         Amount(0),
+        Network::LocalNet,
     )
     .unwrap();
 
@@ -3,6 +3,7 @@
 
 use std::ops::RangeInclusive;
 
+use tari_common::configuration::Network;
 use tari_common_types::types::{Commitment, PrivateKey, PublicKey};
 use tari_dan_wallet_crypto::{
     create_confidential_output_statement,
@@ -20,11 +21,13 @@ use tari_dan_wallet_crypto::{
 use tari_engine_types::confidential::{ConfidentialOutput, ElgamalVerifiableBalance, ValueLookupTable};
 use tari_template_lib::models::{Amount, ConfidentialOutputStatement, ConfidentialWithdrawProof, EncryptedData};
 
-pub struct ConfidentialCryptoApi;
+pub struct ConfidentialCryptoApi {
+    network: Network,
+}
 
 impl ConfidentialCryptoApi {
-    pub(crate) fn new() -> Self {
-        Self
+    pub(crate) fn new(network: Network) -> Self {
+        Self { network }
     }
 
     pub fn derive_encrypted_data_key_for_receiver(
@@ -51,6 +54,7 @@ impl ConfidentialCryptoApi {
             output_revealed_amount,
             change_statement,
             change_revealed_amount,
+            self.network,
         )?;
         Ok(proof)
     }
@@ -86,6 +90,7 @@ impl ConfidentialCryptoApi {
             revealed_amount,
             None,
             Amount::zero(),
+            self.network,
         )?;
         Ok(proof)
     }
@@ -3,6 +3,7 @@
 
 use std::{sync::Arc, time::Duration};
 
+use tari_common::configuration::Network;
 use tari_crypto::tari_utilities::SafePassword;
 use tari_dan_common_types::optional::{IsNotFoundError, Optional};
 use tari_key_manager::cipher_seed::CipherSeed;
@@ -34,6 +35,8 @@ pub struct WalletSdkConfig {
     // outside       of the SDK in the JWT handler.
     pub jwt_expiry: Duration,
     pub jwt_secret_key: String,
+    /// The network the wallet is used on. Viewable balance proofs are bound to this network.
+    pub network: Network,
 }
 
 #[derive(Debug, Clone)]
@@ -98,7 +101,7 @@ where
     }
 
     pub fn confidential_crypto_api(&self) -> ConfidentialCryptoApi {
-        ConfidentialCryptoApi::new()
+        ConfidentialCryptoApi::new(self.config.network)
     }
 
     pub fn jwt_api(&self) -> JwtApi<'_, TStore> {
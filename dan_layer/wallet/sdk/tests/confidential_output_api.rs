@@ -4,6 +4,7 @@
 use std::{convert::Infallible, time::Duration};
 
 use async_trait::async_trait;
+use tari_common::configuration::Network;
 use tari_common_types::types::Commitment;
 use tari_crypto::commitment::HomomorphicCommitmentFactory;
 use tari_dan_common_types::{optional::Optional, SubstateRequirement};
@@ -144,6 +145,7 @@ impl Test {
             password: None,
             jwt_expiry: Duration::from_secs(60),
             jwt_secret_key: "secret_key".to_string(),
+            network: Network::LocalNet,
         })
         .unwrap();
         let accounts_api = sdk.accounts_api();
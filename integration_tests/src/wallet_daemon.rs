@@ -26,7 +26,7 @@ use std::{
 };
 
 use reqwest::Url;
-use tari_common::configuration::CommonConfig;
+use tari_common::configuration::{CommonConfig, Network};
 use tari_dan_wallet_daemon::{
     config::{ApplicationConfig, WalletDaemonConfig},
     run_tari_dan_wallet_daemon,
@@ -72,6 +72,7 @@ pub async fn spawn_wallet_daemon(world: &mut TariWorld, wallet_daemon_name: Stri
     let mut config = ApplicationConfig {
         common: CommonConfig::default(),
         dan_wallet_daemon: WalletDaemonConfig::default(),
+        network: Network::LocalNet,
     };
 
     config.common.base_path.clone_from(&base_dir);
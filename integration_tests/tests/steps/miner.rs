@@ -1,6 +1,8 @@
 //   Copyright 2023 The Tari Project
 //   SPDX-License-Identifier: BSD-3-Clause
 
+use std::time::Duration;
+
 use cucumber::{given, when};
 use tari_base_node_client::BaseNodeClient;
 
@@ -26,15 +28,13 @@ pub async fn miner_mines_new_blocks(world: &mut TariWorld, miner_name: String, n
     // wait for all tips to be the new height
     for bn in world.base_nodes.values() {
         let mut client = bn.create_client();
-        let mut tip = client.get_tip_info().await.unwrap();
-        let mut iter_count = 0;
-        while tip.height_of_longest_chain < start_tip + num_blocks {
-            tip = client.get_tip_info().await.unwrap();
-            tokio::time::sleep(std::time::Duration::from_millis(350)).await;
-            if iter_count > 100 {
-                panic!("Timed out waiting for tip height to reach {}", start_tip + num_blocks);
-            }
-            iter_count += 1;
-        }
+        client
+            .wait_for_tip_height(
+                start_tip + num_blocks,
+                Duration::from_millis(350),
+                Duration::from_secs(35),
+            )
+            .await
+            .unwrap();
     }
 }
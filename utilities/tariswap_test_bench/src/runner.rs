@@ -4,6 +4,7 @@
 use std::{path::Path, time::Duration};
 
 use log::info;
+use tari_common::configuration::Network;
 use tari_dan_wallet_daemon::indexer_jrpc_impl::IndexerJsonRpcNetworkInterface;
 use tari_dan_wallet_sdk::{DanWalletSdk, WalletSdkConfig};
 use tari_dan_wallet_storage_sqlite::SqliteWalletStore;
@@ -118,6 +119,8 @@ fn initialize_wallet_sdk<P: AsRef<Path>>(db_path: P, indexer_url: Url) -> Result
         password: None,
         jwt_expiry: Duration::from_secs(100_000),
         jwt_secret_key: "secret".to_string(),
+        // The test bench runs against a local swarm
+        network: Network::LocalNet,
     };
     let indexer = IndexerJsonRpcNetworkInterface::new(indexer_url);
     let wallet = DanWalletSdk::initialize(store, indexer, sdk_config)?;